@@ -0,0 +1,120 @@
+//! Encode and decode throughput of the hot packets.
+//! Run with `cargo bench --bench packets`, the encode group compares the pooled
+//! back-patched frames with encoding the body and the frame in two separate buffers.
+
+use std::hint::black_box;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use uuid::Uuid;
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::data_types::ByteReader;
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::raw_bytes::RawBytes;
+use nullspace::networking::data_types::u_byte::UnsignedByte;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::data_types::{BufferWrite, PacketWrite};
+use nullspace::networking::packets::Packet;
+use nullspace::networking::packets::clientbound::{require_clientbound_id, ClientboundPacket};
+use nullspace::networking::packets::common::plugin_message_request::PluginMessageRequestPacket;
+use nullspace::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use nullspace::networking::packets::configuration::client_information_request::ClientInformationRequestPacket;
+use nullspace::networking::packets::configuration::known_packs_request::KnownPacksRequestPacket;
+use nullspace::networking::packets::configuration::known_packs_response::KnownPacksResponsePacket;
+use nullspace::networking::packets::configuration::registry_response::RegistryDataResponsePacket;
+use nullspace::networking::packets::handshake::HandshakePacket;
+use nullspace::networking::packets::login::login_start_request::LoginStartRequestPacket;
+use nullspace::networking::packets::play::login_response::LoginResponsePacket;
+use nullspace::networking::packets::play::synchronize_player_position_response::SynchronizePlayerPositionResponsePacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+use nullspace::networking::writer::encode_packet;
+
+const VERSION: ProtocolVersion = ProtocolVersion::LATEST;
+
+/// How frames were encoded before the pool: the body in one buffer, copied after its length into another
+fn encode_naive<T: ClientboundPacket>(phase: ConnectionPhase, packet: &T) -> Vec<u8> {
+    let packet_id = require_clientbound_id(VERSION, phase, T::NAME).unwrap();
+
+    let mut body_buffer = Vec::new();
+    body_buffer.write_type(VarInt(packet_id));
+    packet.write_for(VERSION, &mut body_buffer).unwrap();
+
+    let mut final_buffer = Vec::with_capacity(body_buffer.len() + VarInt::MAX_SIZE);
+    final_buffer.write_type(VarInt(body_buffer.len() as i32));
+    final_buffer.extend_from_slice(&body_buffer);
+    final_buffer
+}
+
+fn bench_encode<T: ClientboundPacket>(c: &mut Criterion, name: &str, phase: ConnectionPhase, packet: T) {
+    let mut group = c.benchmark_group(format!("encode/{}", name));
+    group.throughput(Throughput::Bytes(encode_naive(phase, &packet).len() as u64));
+
+    group.bench_function("pooled", |b| {
+        b.iter(|| encode_packet(VERSION, phase, black_box(&packet)).unwrap())
+    });
+    group.bench_function("naive", |b| {
+        b.iter(|| encode_naive(phase, black_box(&packet)))
+    });
+
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    bench_encode(c, "login", ConnectionPhase::Play, LoginResponsePacket::nullspace());
+    bench_encode(c, "known_packs", ConnectionPhase::Configuration, KnownPacksResponsePacket::nullspace(VERSION));
+    bench_encode(c, "synchronize_player_position", ConnectionPhase::Play, SynchronizePlayerPositionResponsePacket::nullspace());
+    bench_encode(c, "plugin_message", ConnectionPhase::Play, PluginMessageResponsePacket::nullspace());
+    // About the size of the largest registry, minecraft:recipe
+    bench_encode(c, "registry_data", ConnectionPhase::Configuration, RegistryDataResponsePacket { data: RawBytes(vec![0x2A; 54 * 1024]) });
+}
+
+fn bench_decode<T: Packet>(c: &mut Criterion, name: &str, body: Vec<u8>) {
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_function(name, |b| {
+        b.iter(|| T::decode(&mut ByteReader::new(black_box(&body))).unwrap())
+    });
+    group.finish();
+}
+
+fn encode_body(write: impl FnOnce(&mut Vec<u8>)) -> Vec<u8> {
+    let mut body = Vec::new();
+    write(&mut body);
+    body
+}
+
+fn decode(c: &mut Criterion) {
+    bench_decode::<HandshakePacket>(c, "handshake", encode_body(|body| {
+        body.write_type(VarInt(VERSION.protocol()));
+        body.write_type("play.example.com".to_string());
+        body.write_type(25565u16);
+        body.write_type(VarInt(2));
+    }));
+
+    bench_decode::<LoginStartRequestPacket>(c, "login_start", encode_body(|body| {
+        body.write_type("Notch".to_string());
+        body.write_type(Uuid::new_v4());
+    }));
+
+    bench_decode::<ClientInformationRequestPacket>(c, "client_information", encode_body(|body| {
+        body.write_type("en_us".to_string());
+        body.write_type(Byte(12));
+        body.write_type(VarInt(0));
+        body.write_type(true);
+        body.write_type(UnsignedByte(0x7F));
+        body.write_type(VarInt(1));
+        body.write_type(false);
+        body.write_type(true);
+        body.write_type(VarInt(0));
+    }));
+
+    // Clients answer with the same packs the server listed
+    bench_decode::<KnownPacksRequestPacket>(c, "known_packs", encode_body(|body| {
+        KnownPacksResponsePacket::nullspace(VERSION).write_to(body)
+    }));
+
+    bench_decode::<PluginMessageRequestPacket>(c, "plugin_message", encode_body(|body| {
+        PluginMessageResponsePacket::nullspace().write_to(body)
+    }));
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
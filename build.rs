@@ -0,0 +1,478 @@
+//! Generates the block state and item registries and the clientbound packet ids from the vanilla
+//! data generator reports in reports/. Regenerate the reports with
+//! `java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports` and copy `blocks.json`,
+//! `items.json` and `packets.json` over the ones in reports/, the code follows on the next build.
+
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use serde_json::{Map, Value};
+
+const BLOCKS_REPORT: &str = "reports/blocks.json";
+const ITEMS_REPORT: &str = "reports/items.json";
+const PACKETS_REPORT: &str = "reports/packets.json";
+
+/// The 1.21.11 registries have more states and items than this, fewer means a trimmed report
+const MIN_VANILLA_STATES: u32 = 1 << 14;
+const MIN_VANILLA_ITEMS: usize = 1024;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", BLOCKS_REPORT);
+    println!("cargo:rerun-if-changed={}", ITEMS_REPORT);
+    println!("cargo:rerun-if-changed={}", PACKETS_REPORT);
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let blocks = read_report(BLOCKS_REPORT);
+    let items = read_report(ITEMS_REPORT);
+    let packets = read_report(PACKETS_REPORT);
+    warn_if_partial(&blocks, &items);
+    warn_if_clientbound_only(&packets);
+
+    fs::write(Path::new(&out_dir).join("blocks.rs"), generate_blocks(&blocks)).unwrap();
+    fs::write(Path::new(&out_dir).join("items.rs"), generate_items(&items)).unwrap();
+    fs::write(Path::new(&out_dir).join("packets.rs"), generate_packets(&packets)).unwrap();
+}
+
+/// Entries of a report in file order, which is the registry order
+fn read_report(path: &str) -> Map<String, Value> {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("Can't read {}: {}", path, e));
+    match serde_json::from_str(&text) {
+        Ok(Value::Object(entries)) => entries,
+        _ => panic!("{} isn't a JSON object", path),
+    }
+}
+
+/// Ids past the ones of a trimmed report don't exist, e.g. in chunks written by a vanilla server
+fn warn_if_partial(blocks: &Map<String, Value>, items: &Map<String, Value>) {
+    let states: usize = blocks.values().map(|entry| entry["states"].as_array().map_or(0, Vec::len)).sum();
+    if (states as u32) < MIN_VANILLA_STATES || items.len() < MIN_VANILLA_ITEMS {
+        println!("cargo:warning=reports/ is a partial vanilla report ({} block states, {} items), regenerate it with the data generator", states, items.len());
+    }
+}
+
+/// The data generator lists the packets of both directions, a report with only clientbound ones was written by hand
+fn warn_if_clientbound_only(packets: &Map<String, Value>) {
+    if !packets.values().any(|phase| phase.get("serverbound").is_some()) {
+        println!("cargo:warning={} only has clientbound packets, regenerate it with the data generator", PACKETS_REPORT);
+    }
+}
+
+// Names
+
+/// "minecraft:oak_log" and "north_south" to "OakLog" and "NorthSouth"
+fn camel_case(name: &str) -> String {
+    let name = name.rsplit(':').next().unwrap();
+    let mut result: String = name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect();
+
+    if result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, 'V');
+    }
+    result
+}
+
+/// Property names as struct fields, "type" is a keyword
+fn field_name(name: &str) -> String {
+    match name {
+        "type" | "match" | "in" | "as" | "box" | "move" | "ref" | "mod" | "use" => format!("r#{}", name),
+        _ => name.to_string(),
+    }
+}
+
+// Blocks
+
+enum PropertyKind {
+    /// "true" then "false", like vanilla orders them
+    Bool,
+    /// Consecutive integers starting at the value
+    Int(u8),
+    /// Generated enum of the values
+    Enum(String),
+}
+
+struct Property {
+    name: String,
+    values: Vec<String>,
+    kind: PropertyKind,
+}
+
+impl Property {
+    fn rust_type(&self) -> String {
+        match &self.kind {
+            PropertyKind::Bool => "bool".to_string(),
+            PropertyKind::Int(_) => "u8".to_string(),
+            PropertyKind::Enum(name) => name.clone(),
+        }
+    }
+
+    /// Index of the field's value among the property values, as a u16 expression
+    fn index_expression(&self) -> String {
+        let field = field_name(&self.name);
+        match &self.kind {
+            PropertyKind::Bool => format!("(!{}) as u16", field),
+            PropertyKind::Int(0) => format!("{} as u16", field),
+            PropertyKind::Int(first) => format!("({} - {}) as u16", field, first),
+            PropertyKind::Enum(_) => format!("{} as u16", field),
+        }
+    }
+
+    /// Value of the property from the state offset divided by the property's stride
+    fn value_expression(&self, quotient: &str) -> String {
+        let index = format!("{} % {}", quotient, self.values.len());
+        match &self.kind {
+            PropertyKind::Bool if quotient == "offset" => "offset.is_multiple_of(2)".to_string(),
+            PropertyKind::Bool => format!("({}).is_multiple_of(2)", quotient),
+            PropertyKind::Int(0) => format!("({}) as u8", index),
+            PropertyKind::Int(first) => format!("({}) as u8 + {}", index, first),
+            PropertyKind::Enum(name) => format!("{}::VALUES[({}) as usize]", name, index),
+        }
+    }
+
+    fn value_literal(&self, value: &str) -> String {
+        match &self.kind {
+            PropertyKind::Bool | PropertyKind::Int(_) => value.to_string(),
+            PropertyKind::Enum(name) => format!("{}::{}", name, camel_case(value)),
+        }
+    }
+}
+
+struct BlockReport {
+    variant: String,
+    name: String,
+    properties: Vec<Property>,
+    first_id: u32,
+    state_count: u32,
+    /// Property values of the default state, in property order
+    default: Vec<String>,
+    default_id: u32,
+}
+
+fn property_values(entry: &Value) -> Vec<(String, Vec<String>)> {
+    let Some(Value::Object(properties)) = entry.get("properties") else {
+        return Vec::new();
+    };
+
+    properties.iter()
+        .map(|(name, values)| {
+            let values = values.as_array().unwrap().iter().map(|value| value.as_str().unwrap().to_string()).collect();
+            (name.clone(), values)
+        })
+        .collect()
+}
+
+fn is_int_range(values: &[String]) -> Option<u8> {
+    let numbers: Vec<u8> = values.iter().map(|value| value.parse().ok()).collect::<Option<_>>()?;
+    numbers.windows(2).all(|pair| pair[1] == pair[0] + 1).then_some(numbers[0])
+}
+
+/// Names the enum of every distinct (property, values) pair. A property name used with a single set of
+/// values gets its plain name, others are told apart by their number of values, then by a letter in report order.
+fn enum_names(blocks: &Map<String, Value>) -> BTreeMap<(String, Vec<String>), String> {
+    let mut by_name: BTreeMap<String, Vec<Vec<String>>> = BTreeMap::new();
+    for entry in blocks.values() {
+        for (name, values) in property_values(entry) {
+            if values == ["true", "false"] || is_int_range(&values).is_some() {
+                continue;
+            }
+            let sets = by_name.entry(name).or_default();
+            if !sets.contains(&values) {
+                sets.push(values);
+            }
+        }
+    }
+
+    let mut names = BTreeMap::new();
+    for (name, sets) in by_name {
+        let mut used = HashSet::new();
+        for values in sets.iter() {
+            let mut enum_name = camel_case(&name);
+            if sets.len() > 1 {
+                enum_name = format!("{}{}", enum_name, values.len());
+                let mut suffix = b'B';
+                while !used.insert(enum_name.clone()) {
+                    enum_name = format!("{}{}{}", camel_case(&name), values.len(), suffix as char);
+                    suffix += 1;
+                }
+            }
+            names.insert((name.clone(), values.clone()), enum_name);
+        }
+    }
+    names
+}
+
+fn parse_block(name: &str, entry: &Value, enums: &BTreeMap<(String, Vec<String>), String>) -> BlockReport {
+    let properties: Vec<Property> = property_values(entry).into_iter()
+        .map(|(property, values)| {
+            let kind = if values == ["true", "false"] {
+                PropertyKind::Bool
+            } else if let Some(first) = is_int_range(&values) {
+                PropertyKind::Int(first)
+            } else {
+                PropertyKind::Enum(enums[&(property.clone(), values.clone())].clone())
+            };
+            Property { name: property, values, kind }
+        })
+        .collect();
+
+    let states = entry["states"].as_array().unwrap();
+    let first_id = states.iter().map(|state| state["id"].as_u64().unwrap() as u32).min().unwrap();
+    let state_count: u32 = properties.iter().map(|property| property.values.len() as u32).product();
+    assert_eq!(states.len() as u32, state_count, "{} doesn't have a state for every property combination", name);
+
+    let mut default = None;
+    for state in states {
+        let values: Vec<String> = properties.iter()
+            .map(|property| state["properties"][&property.name].as_str().unwrap().to_string())
+            .collect();
+
+        // Ids follow the property order, the last property changes fastest
+        let mut offset = 0;
+        for (property, value) in properties.iter().zip(&values) {
+            let index = property.values.iter().position(|v| v == value).unwrap() as u32;
+            offset = offset * property.values.len() as u32 + index;
+        }
+        let id = state["id"].as_u64().unwrap() as u32;
+        assert_eq!(id, first_id + offset, "state ids of {} aren't in property order", name);
+
+        if state.get("default").and_then(Value::as_bool) == Some(true) {
+            default = Some((values, id));
+        }
+    }
+
+    let (default, default_id) = default.unwrap_or_else(|| panic!("{} has no default state", name));
+    BlockReport { variant: camel_case(name), name: name.to_string(), properties, first_id, state_count, default, default_id }
+}
+
+fn state_pattern(block: &BlockReport) -> String {
+    if block.properties.is_empty() {
+        format!("BlockState::{}", block.variant)
+    } else {
+        let fields: Vec<String> = block.properties.iter().map(|property| field_name(&property.name)).collect();
+        format!("BlockState::{} {{ {} }}", block.variant, fields.join(", "))
+    }
+}
+
+fn generate_blocks(report: &Map<String, Value>) -> String {
+    let enums = enum_names(report);
+    let blocks: Vec<BlockReport> = report.iter().map(|(name, entry)| parse_block(name, entry, &enums)).collect();
+    let state_count: u32 = blocks.iter().map(|block| block.state_count).sum();
+
+    let mut code = String::new();
+    writeln!(code, "// Generated by build.rs from {}, don't edit\n", BLOCKS_REPORT).unwrap();
+
+    // Property enums
+    for ((property, values), name) in &enums {
+        writeln!(code, "/// Values of the `{}` block state property", property).unwrap();
+        writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum {} {{", name).unwrap();
+        for value in values {
+            writeln!(code, "    {},", camel_case(value)).unwrap();
+        }
+        writeln!(code, "}}\n\nimpl {} {{", name).unwrap();
+        let variants: Vec<String> = values.iter().map(|value| format!("{}::{}", name, camel_case(value))).collect();
+        writeln!(code, "    pub const VALUES: [{}; {}] = [{}];\n", name, values.len(), variants.join(", ")).unwrap();
+        writeln!(code, "    pub fn as_str(self) -> &'static str {{\n        match self {{").unwrap();
+        for (value, variant) in values.iter().zip(&variants) {
+            writeln!(code, "            {} => \"{}\",", variant, value).unwrap();
+        }
+        writeln!(code, "        }}\n    }}\n}}\n").unwrap();
+    }
+
+    // Blocks
+    writeln!(code, "/// Block types, in registry order\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum Block {{").unwrap();
+    for block in &blocks {
+        writeln!(code, "    {},", block.variant).unwrap();
+    }
+    writeln!(code, "}}\n\nimpl Block {{").unwrap();
+    let variants: Vec<String> = blocks.iter().map(|block| format!("Block::{}", block.variant)).collect();
+    writeln!(code, "    pub const ALL: [Block; {}] = [{}];\n", blocks.len(), variants.join(", ")).unwrap();
+
+    writeln!(code, "    /// Vanilla name, e.g. \"minecraft:stone\"\n    pub fn name(self) -> &'static str {{\n        match self {{").unwrap();
+    for block in &blocks {
+        writeln!(code, "            Block::{} => \"{}\",", block.variant, block.name).unwrap();
+    }
+    writeln!(code, "        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    pub fn from_name(name: &str) -> Option<Block> {{\n        match name {{").unwrap();
+    for block in &blocks {
+        writeln!(code, "            \"{}\" => Some(Block::{}),", block.name, block.variant).unwrap();
+    }
+    writeln!(code, "            _ => None,\n        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    pub fn default_state(self) -> BlockState {{\n        match self {{").unwrap();
+    for block in &blocks {
+        if block.properties.is_empty() {
+            writeln!(code, "            Block::{0} => BlockState::{0},", block.variant).unwrap();
+        } else {
+            let fields: Vec<String> = block.properties.iter().zip(&block.default)
+                .map(|(property, value)| format!("{}: {}", field_name(&property.name), property.value_literal(value)))
+                .collect();
+            writeln!(code, "            Block::{0} => BlockState::{0} {{ {1} }},", block.variant, fields.join(", ")).unwrap();
+        }
+    }
+    writeln!(code, "        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    /// Ids of the block's states, they are consecutive\n    pub fn state_ids(self) -> std::ops::RangeInclusive<u16> {{\n        match self {{").unwrap();
+    for block in &blocks {
+        writeln!(code, "            Block::{} => {}..={},", block.variant, block.first_id, block.first_id + block.state_count - 1).unwrap();
+    }
+    writeln!(code, "        }}\n    }}\n}}\n").unwrap();
+
+    // Block states
+    writeln!(code, "/// Block with the values of its state properties, sent as the numeric state id\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum BlockState {{").unwrap();
+    for block in &blocks {
+        if block.properties.is_empty() {
+            writeln!(code, "    {},", block.variant).unwrap();
+        } else {
+            let fields: Vec<String> = block.properties.iter()
+                .map(|property| format!("{}: {}", field_name(&property.name), property.rust_type()))
+                .collect();
+            writeln!(code, "    {} {{ {} }},", block.variant, fields.join(", ")).unwrap();
+        }
+    }
+    writeln!(code, "}}\n\nimpl BlockState {{").unwrap();
+    writeln!(code, "    /// Number of block states, the highest id is one less\n    pub const COUNT: u16 = {};\n", state_count).unwrap();
+
+    writeln!(code, "    pub fn block(self) -> Block {{\n        match self {{").unwrap();
+    for block in &blocks {
+        let pattern = if block.properties.is_empty() { format!("BlockState::{}", block.variant) } else { format!("BlockState::{} {{ .. }}", block.variant) };
+        writeln!(code, "            {} => Block::{},", pattern, block.variant).unwrap();
+    }
+    writeln!(code, "        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    /// Numeric state id. Integer properties out of their range give the block's default state.").unwrap();
+    writeln!(code, "    pub fn id(self) -> u16 {{\n        match self {{").unwrap();
+    for block in &blocks {
+        if block.properties.is_empty() {
+            writeln!(code, "            BlockState::{} => {},", block.variant, block.first_id).unwrap();
+            continue;
+        }
+
+        writeln!(code, "            {} => {{", state_pattern(block)).unwrap();
+        let ranges: Vec<String> = block.properties.iter()
+            .filter_map(|property| match property.kind {
+                PropertyKind::Int(first) => Some(format!("!({}..={}).contains(&{})", first, first as usize + property.values.len() - 1, field_name(&property.name))),
+                _ => None,
+            })
+            .collect();
+        if !ranges.is_empty() {
+            writeln!(code, "                if {} {{\n                    return {};\n                }}", ranges.join(" || "), block.default_id).unwrap();
+        }
+
+        let mut terms = vec![block.first_id.to_string()];
+        let mut stride = 1;
+        let mut parts = Vec::new();
+        for property in block.properties.iter().rev() {
+            if stride == 1 {
+                parts.push(property.index_expression());
+            } else {
+                parts.push(format!("{} * {}", property.index_expression(), stride));
+            }
+            stride *= property.values.len() as u32;
+        }
+        parts.reverse();
+        terms.extend(parts);
+        writeln!(code, "                {}\n            }}", terms.join(" + ")).unwrap();
+    }
+    writeln!(code, "        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    pub fn from_id(id: u16) -> Option<BlockState> {{\n        match id {{").unwrap();
+    for block in &blocks {
+        if block.properties.is_empty() {
+            writeln!(code, "            {} => Some(BlockState::{}),", block.first_id, block.variant).unwrap();
+            continue;
+        }
+
+        let offset = if block.first_id == 0 { "id".to_string() } else { format!("id - {}", block.first_id) };
+        writeln!(code, "            {}..={} => {{\n                let offset = {};", block.first_id, block.first_id + block.state_count - 1, offset).unwrap();
+        let mut stride = 1;
+        let mut fields = Vec::new();
+        for property in block.properties.iter().rev() {
+            let count = property.values.len() as u32;
+            let quotient = if stride == 1 { "offset".to_string() } else { format!("offset / {}", stride) };
+            fields.push(format!("{}: {}", field_name(&property.name), property.value_expression(&quotient)));
+            stride *= count;
+        }
+        fields.reverse();
+        writeln!(code, "                Some(BlockState::{} {{ {} }})\n            }}", block.variant, fields.join(", ")).unwrap();
+    }
+    writeln!(code, "            _ => None,\n        }}\n    }}\n}}").unwrap();
+
+    code
+}
+
+// Items
+
+fn generate_items(report: &Map<String, Value>) -> String {
+    let mut code = String::new();
+    writeln!(code, "// Generated by build.rs from {}, don't edit\n", ITEMS_REPORT).unwrap();
+
+    let items: Vec<(String, String, i64)> = report.iter()
+        .map(|(name, entry)| {
+            let max_stack_size = entry["components"].get("minecraft:max_stack_size").and_then(Value::as_i64).unwrap_or(64);
+            (camel_case(name), name.clone(), max_stack_size)
+        })
+        .collect();
+
+    writeln!(code, "/// Item types, the discriminant is the protocol id\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum Item {{").unwrap();
+    for (id, (variant, _, _)) in items.iter().enumerate() {
+        writeln!(code, "    {} = {},", variant, id).unwrap();
+    }
+    writeln!(code, "}}\n\nimpl Item {{").unwrap();
+    let variants: Vec<String> = items.iter().map(|(variant, _, _)| format!("Item::{}", variant)).collect();
+    writeln!(code, "    pub const ALL: [Item; {}] = [{}];\n", items.len(), variants.join(", ")).unwrap();
+
+    writeln!(code, "    /// Vanilla name, e.g. \"minecraft:stone\"\n    pub fn name(self) -> &'static str {{\n        match self {{").unwrap();
+    for (variant, name, _) in &items {
+        writeln!(code, "            Item::{} => \"{}\",", variant, name).unwrap();
+    }
+    writeln!(code, "        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    pub fn from_name(name: &str) -> Option<Item> {{\n        match name {{").unwrap();
+    for (variant, name, _) in &items {
+        writeln!(code, "            \"{}\" => Some(Item::{}),", name, variant).unwrap();
+    }
+    writeln!(code, "            _ => None,\n        }}\n    }}\n").unwrap();
+
+    writeln!(code, "    /// Default `minecraft:max_stack_size` component\n    pub fn max_stack_size(self) -> i32 {{\n        match self {{").unwrap();
+    for (variant, _, max_stack_size) in &items {
+        writeln!(code, "            Item::{} => {},", variant, max_stack_size).unwrap();
+    }
+    writeln!(code, "        }}\n    }}\n}}").unwrap();
+
+    code
+}
+
+// Packets
+
+/// Clientbound ids by phase in id order, the serverbound packets are registered with their handlers
+fn generate_packets(report: &Map<String, Value>) -> String {
+    let mut code = String::new();
+    writeln!(code, "// Generated by build.rs from {}, don't edit\n", PACKETS_REPORT).unwrap();
+    writeln!(code, "const CLIENTBOUND_PACKETS: &[(ConnectionPhase, &str, i32)] = &[").unwrap();
+
+    for (phase, variant) in [("status", "Status"), ("login", "Login"), ("configuration", "Configuration"), ("play", "Play")] {
+        let Some(Value::Object(packets)) = report.get(phase).and_then(|packets| packets.get("clientbound")) else {
+            continue;
+        };
+
+        let mut ids: Vec<(i64, &String)> = packets.iter()
+            .map(|(name, entry)| (entry["protocol_id"].as_i64().unwrap_or_else(|| panic!("{} has no protocol_id", name)), name))
+            .collect();
+        ids.sort();
+
+        for (id, name) in ids {
+            writeln!(code, "    (ConnectionPhase::{}, {:?}, 0x{:02X}),", variant, name, id).unwrap();
+        }
+    }
+
+    writeln!(code, "];").unwrap();
+    code
+}
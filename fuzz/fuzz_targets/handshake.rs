@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nullspace::networking::connection::ConnectionPhase;
+
+fuzz_target!(|data: &[u8]| {
+    nullspace_fuzz::serverbound(data, ConnectionPhase::Handshaking);
+});
@@ -0,0 +1,85 @@
+//! Shared harness of the fuzz targets. Input is a raw stream of a connection: it's split into
+//! frames like the connections do, then every frame is decoded by the registries of each version.
+//! Run a target with `cargo fuzz run <phase>` from the repository root, e.g. `cargo fuzz run play`.
+//! Inputs that once crashed are kept in fuzz/regressions/, pass their directory after the target's corpus:
+//! `cargo fuzz run data_types fuzz/corpus/data_types fuzz/regressions/data_types`.
+
+use std::fmt::Debug;
+use std::sync::LazyLock;
+use bytes::BytesMut;
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::data_types::{ByteReader, FieldRead};
+use nullspace::networking::data_types::bit_set::BitSet;
+use nullspace::networking::data_types::id_or::{IdOr, IdSet};
+use nullspace::networking::data_types::identifier::Identifier;
+use nullspace::networking::data_types::item_stack::{HashedItemStack, ItemStack};
+use nullspace::networking::data_types::lp_vec3::LpVec3;
+use nullspace::networking::data_types::nbt::Nbt;
+use nullspace::networking::data_types::sound_event::SoundEvent;
+use nullspace::networking::data_types::text_component::TextComponent;
+use nullspace::networking::data_types::var_long::VarLong;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::framing::split_frame;
+use nullspace::networking::packets::{self, PacketRegistries};
+use nullspace::networking::packets::inspect::describe_clientbound;
+use nullspace::networking::protocol_version::ProtocolVersion;
+
+static REGISTRIES: LazyLock<PacketRegistries> = LazyLock::new(|| PacketRegistries::new(packets::register_all));
+
+/// Frames of the input stream, stops at the first invalid or incomplete one like a connection would
+fn frames(data: &[u8], phase: ConnectionPhase) -> Vec<BytesMut> {
+    let mut buffer = BytesMut::from(data);
+    let mut frames = Vec::new();
+    while let Ok(Some(frame)) = split_frame(&mut buffer, phase.max_frame_size()) {
+        frames.push(frame);
+    }
+    frames
+}
+
+/// Decodes serverbound frames of a phase, the same steps as handle_packet before a handler runs
+pub fn serverbound(data: &[u8], phase: ConnectionPhase) {
+    for frame in frames(data, phase) {
+        for version in ProtocolVersion::ALL {
+            let mut reader = ByteReader::new(&frame);
+            let Ok(VarInt(id)) = reader.read_field() else {
+                continue;
+            };
+
+            if let Some(Ok(packet)) = REGISTRIES.get(version).decode(phase, id, &mut reader) {
+                // Debug walks every decoded field, like the capture printer
+                let _ = format!("{:?}", packet);
+            }
+        }
+    }
+}
+
+/// Decodes clientbound frames of every phase, what the proxy and capture printer read from a server
+pub fn clientbound(data: &[u8]) {
+    for frame in frames(data, ConnectionPhase::Play) {
+        for version in ProtocolVersion::ALL {
+            for phase in [ConnectionPhase::Status, ConnectionPhase::Login, ConnectionPhase::Configuration, ConnectionPhase::Play] {
+                let _ = describe_clientbound(version, phase, &frame);
+            }
+        }
+    }
+}
+
+/// Decodes the input as each of the data types that aren't plain numbers or strings
+pub fn data_types(data: &[u8]) {
+    decode::<VarLong>(data);
+    decode::<Identifier>(data);
+    decode::<BitSet>(data);
+    decode::<IdOr<SoundEvent>>(data);
+    decode::<IdSet>(data);
+    decode::<LpVec3>(data);
+    decode::<Nbt>(data);
+    decode::<TextComponent>(data);
+    decode::<ItemStack>(data);
+    decode::<Option<HashedItemStack>>(data);
+}
+
+fn decode<T: FieldRead + Debug>(data: &[u8]) {
+    if let Ok(value) = T::read_from(&mut ByteReader::new(data)) {
+        let _ = format!("{:?}", value);
+    }
+}
@@ -0,0 +1,210 @@
+//! Derive macros for the packet and data type encoding traits of Nullspace.
+//!
+//! Fields are read and written in declaration order. Field attributes:
+//! - `#[field(max_length = N)]`: String with at most N characters, prefixed array with at most N items,
+//!   or raw bytes with at most N bytes
+//! - `#[field(length = expr)]`: array without its own length prefix, the length comes from an earlier field
+//! - `#[field(when = expr)]`: `Option<T>` only present when the condition holds, without the boolean prefix
+//!
+//! Expressions see the other fields as references, e.g. `#[field(when = *has_death_location)]`.
+//! A plain `Option<T>` is a prefixed optional (boolean + value).
+//!
+//! Generated code names the traits through `::nullspace`, so the derives work in the crate
+//! (which aliases itself under that name) and in the crates using it, like its tests.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, LitInt, Type};
+
+#[derive(Default)]
+struct FieldOptions {
+    max_length: Option<LitInt>,
+    length: Option<Expr>,
+    when: Option<Expr>,
+}
+
+struct PacketField {
+    name: Ident,
+    ty: Type,
+    options: FieldOptions,
+}
+
+/// Implements `Packet::decode` for serverbound packets
+#[proc_macro_derive(Packet, attributes(field))]
+pub fn derive_packet(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let fields = match parse_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let reader = Ident::new("reader", proc_macro2::Span::call_site());
+    let body = read_body(name, &fields, &reader);
+
+    quote! {
+        impl ::nullspace::networking::packets::Packet for #name {
+            fn decode(#reader: &mut ::nullspace::networking::data_types::ByteReader<'_>) -> anyhow::Result<Self> {
+                #body
+            }
+        }
+    }.into()
+}
+
+/// Implements `FieldRead` for data types nested inside packets
+#[proc_macro_derive(FieldRead, attributes(field))]
+pub fn derive_field_read(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let fields = match parse_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let reader = Ident::new("reader", proc_macro2::Span::call_site());
+    let body = read_body(name, &fields, &reader);
+
+    quote! {
+        impl ::nullspace::networking::data_types::FieldRead for #name {
+            fn read_from(#reader: &mut ::nullspace::networking::data_types::ByteReader<'_>) -> anyhow::Result<Self> {
+                #body
+            }
+        }
+    }.into()
+}
+
+/// Implements `PacketWrite` for clientbound packets and data types
+#[proc_macro_derive(PacketWrite, attributes(field))]
+pub fn derive_packet_write(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let fields = match parse_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Every field is visible by reference to the `length` and `when` expressions
+    let needs_bindings = fields.iter().any(|field| field.options.when.is_some());
+    let bindings = if needs_bindings {
+        let names = fields.iter().map(|field| &field.name);
+        quote! { #( #[allow(unused_variables)] let #names = &self.#names; )* }
+    } else {
+        quote! {}
+    };
+
+    let writes = fields.iter().map(|field| {
+        let field_name = &field.name;
+
+        if let Some(condition) = &field.options.when {
+            quote! {
+                if #condition {
+                    if let Some(value) = &self.#field_name {
+                        ::nullspace::networking::data_types::PacketWrite::write_to(value, buf);
+                    }
+                }
+            }
+        } else if field.options.length.is_some() {
+            quote! {
+                for item in &self.#field_name {
+                    ::nullspace::networking::data_types::PacketWrite::write_to(item, buf);
+                }
+            }
+        } else {
+            quote! { ::nullspace::networking::data_types::PacketWrite::write_to(&self.#field_name, buf); }
+        }
+    });
+
+    quote! {
+        impl ::nullspace::networking::data_types::PacketWrite for #name {
+            fn write_to(&self, buf: &mut Vec<u8>) {
+                #bindings
+                #( #writes )*
+            }
+        }
+    }.into()
+}
+
+fn read_body(name: &Ident, fields: &[PacketField], reader: &Ident) -> TokenStream2 {
+    let mut reads = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let field_name = &field.name;
+        let ty = &field.ty;
+
+        // Earlier fields are visible by reference to the `length` and `when` expressions
+        let earlier = fields[..index].iter().map(|field| &field.name);
+        let bindings = quote! { #( #[allow(unused_variables)] let #earlier = &#earlier; )* };
+
+        let read = if let Some(condition) = &field.options.when {
+            quote! {
+                let #field_name: #ty = if { #bindings #condition } {
+                    Some(::nullspace::networking::data_types::FieldRead::read_from(#reader)?)
+                } else {
+                    None
+                };
+            }
+        } else if let Some(length) = &field.options.length {
+            quote! {
+                let #field_name: #ty = ::nullspace::networking::data_types::read_array(#reader, { #bindings #length } as usize)?;
+            }
+        } else if let Some(max_length) = &field.options.max_length {
+            quote! {
+                let #field_name: #ty = ::nullspace::networking::data_types::BoundedFieldRead::read_bounded(#reader, #max_length)?;
+            }
+        } else {
+            quote! {
+                let #field_name: #ty = ::nullspace::networking::data_types::FieldRead::read_from(#reader)?;
+            }
+        };
+
+        reads.push(read);
+    }
+
+    let names = fields.iter().map(|field| &field.name);
+
+    quote! {
+        #( #reads )*
+        Ok(#name { #( #names ),* })
+    }
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<PacketField>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "Only structs can be derived"));
+    };
+
+    let named = match &data.fields {
+        Fields::Named(named) => named.named.iter().collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(_) => return Err(syn::Error::new_spanned(&input.ident, "Tuple structs can't be derived")),
+    };
+
+    named.into_iter().map(|field| {
+        let mut options = FieldOptions::default();
+
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("field")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("max_length") {
+                    options.max_length = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("length") {
+                    options.length = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("when") {
+                    options.when = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("Unknown field attribute, expected max_length, length or when"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(PacketField {
+            name: field.ident.clone().expect("Named field"),
+            ty: field.ty.clone(),
+            options,
+        })
+    }).collect()
+}
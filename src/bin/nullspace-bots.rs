@@ -0,0 +1,356 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::Serialize;
+use nullspace::config::ServerConfig;
+use nullspace::networking::data_types::bit_set::FixedBitSet;
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::metrics::{self, TickTimes};
+use nullspace::networking::packets::play::chat_message_request::ChatMessageRequestPacket;
+use nullspace::networking::packets::play::client_tick_end_request::ClientTickEndRequestPacket;
+use nullspace::networking::packets::play::set_player_position_and_rotation_request::SetPlayerPositionAndRotationRequestPacket;
+use nullspace::networking::packets::play::teleport_confirmation_request::TeleportConfirmationRequestPacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+use nullspace::testing::{self, TestClient};
+
+const USAGE: &str = "Usage: nullspace-bots [--bots N] [--duration SECONDS] [--join-rate BOTS_PER_SECOND] [--chat-interval SECONDS] [--protocol 773|774] [--address HOST:PORT] [--output REPORT.json]";
+
+/// Bots walk in circles of this radius around their spawn point
+const WALK_RADIUS: f64 = 8.0;
+
+struct Options {
+    bots: usize,
+    duration: Duration,
+    join_rate: f64,
+    chat_interval: Duration,
+    version: ProtocolVersion,
+    /// External server to connect to, an in-process one is started when missing
+    address: Option<SocketAddr>,
+    output: Option<String>,
+}
+
+impl Options {
+    fn parse() -> anyhow::Result<Options> {
+        let mut options = Options {
+            bots: 100,
+            duration: Duration::from_secs(30),
+            join_rate: 50.0,
+            chat_interval: Duration::from_secs(5),
+            version: ProtocolVersion::LATEST,
+            address: None,
+            output: None,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or_else(|| anyhow::anyhow!("Missing value for {}\n{}", flag, USAGE))?;
+
+            match flag.as_str() {
+                "--bots" => options.bots = value.parse()?,
+                "--duration" => options.duration = Duration::from_secs_f64(value.parse()?),
+                "--join-rate" => options.join_rate = value.parse()?,
+                "--chat-interval" => options.chat_interval = Duration::from_secs_f64(value.parse()?),
+                "--protocol" => {
+                    options.version = ProtocolVersion::from_protocol(value.parse()?)
+                        .ok_or_else(|| anyhow::anyhow!("Unsupported protocol {}, use one of {:?}", value, ProtocolVersion::ALL.map(ProtocolVersion::protocol)))?;
+                }
+                "--address" => options.address = Some(value.parse()?),
+                "--output" => options.output = Some(value),
+                _ => return Err(anyhow::anyhow!("Unknown option {}\n{}", flag, USAGE)),
+            }
+        }
+
+        if options.join_rate <= 0.0 {
+            return Err(anyhow::anyhow!("The join rate has to be positive"));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Counters shared by every bot
+#[derive(Default)]
+struct SwarmStats {
+    online: AtomicUsize,
+    failed: AtomicUsize,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    join_latencies: Mutex<Vec<Duration>>,
+}
+
+impl SwarmStats {
+    /// Adds the traffic of a client since the previous call
+    fn add_traffic(&self, client: &TestClient, counted: &mut (u64, u64)) {
+        self.bytes_sent.fetch_add(client.bytes_sent - counted.0, Ordering::Relaxed);
+        self.bytes_received.fetch_add(client.bytes_received - counted.1, Ordering::Relaxed);
+        *counted = (client.bytes_sent, client.bytes_received);
+    }
+}
+
+/// Summary of a run, written with `--output` to compare runs across commits
+#[derive(Serialize)]
+struct Report {
+    protocol: i32,
+    bots: usize,
+    joined: usize,
+    failed: usize,
+    duration_secs: f64,
+    join_latency_ms: Option<Percentiles>,
+    bytes_sent_per_sec: f64,
+    bytes_received_per_sec: f64,
+    /// Only known with the in-process server
+    tick_avg_ms: Option<f64>,
+    tick_max_ms: Option<f64>,
+    /// Resident memory of this process, server included when it runs in-process
+    peak_rss_mb: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct Percentiles {
+    min: f64,
+    avg: f64,
+    p50: f64,
+    p99: f64,
+    max: f64,
+}
+
+impl Percentiles {
+    fn of(durations: &mut [Duration]) -> Option<Percentiles> {
+        if durations.is_empty() {
+            return None;
+        }
+
+        durations.sort();
+        let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let at = |fraction: f64| millis(durations[((durations.len() - 1) as f64 * fraction).round() as usize]);
+        let total: Duration = durations.iter().sum();
+
+        Some(Percentiles {
+            min: millis(durations[0]),
+            avg: millis(total / durations.len() as u32),
+            p50: at(0.5),
+            p99: at(0.99),
+            max: millis(durations[durations.len() - 1]),
+        })
+    }
+}
+
+/// # Load test
+/// Joins a swarm of headless clients to a server, walks them around and makes them chat,
+/// then reports join latency, tick time, traffic and memory. Without `--address` the server
+/// runs in this process with the default configuration, so its tick times can be measured too.
+///
+/// The report is printed on stderr, `nullspace-bots > /dev/null` hides the server's own logging.
+/// Each bot holds a socket on both ends in-process, raise `ulimit -n` for large swarms.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let options = Options::parse()?;
+
+    let (address, in_process) = match options.address {
+        Some(address) => (address, false),
+        None => (testing::start_server(ServerConfig::default()).await?.0, true),
+    };
+
+    eprintln!("Joining {} bots to {} with protocol {} for {:?}", options.bots, address, options.version.protocol(), options.duration);
+
+    let stats = Arc::new(SwarmStats::default());
+    let start = Instant::now();
+    let end = start + options.duration;
+
+    // Joins are spread out at the join rate, like players arriving after a restart
+    let join_interval = Duration::from_secs_f64(1.0 / options.join_rate);
+    let mut bots = Vec::with_capacity(options.bots);
+    for index in 0..options.bots {
+        let stats = stats.clone();
+        let join_at = start + join_interval.mul_f64(index as f64);
+        let (version, chat_interval) = (options.version, options.chat_interval);
+
+        bots.push(tokio::spawn(async move {
+            tokio::time::sleep_until(join_at.into()).await;
+            if let Err(e) = run_bot(index, address, version, chat_interval, end, &stats).await {
+                eprintln!("Bot{} failed: {:#}", index, e);
+                stats.failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+    }
+
+    let progress = report_progress(&stats, end, in_process).await;
+    for bot in bots {
+        bot.await?;
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let tick_times = in_process.then_some(progress.ticks);
+    let mut join_latencies = std::mem::take(&mut *stats.join_latencies.lock());
+    let report = Report {
+        protocol: options.version.protocol(),
+        bots: options.bots,
+        joined: join_latencies.len(),
+        failed: stats.failed.load(Ordering::Relaxed),
+        duration_secs: elapsed,
+        join_latency_ms: Percentiles::of(&mut join_latencies),
+        bytes_sent_per_sec: stats.bytes_sent.load(Ordering::Relaxed) as f64 / elapsed,
+        bytes_received_per_sec: stats.bytes_received.load(Ordering::Relaxed) as f64 / elapsed,
+        tick_avg_ms: tick_times.map(|times| times.average().as_secs_f64() * 1000.0),
+        tick_max_ms: tick_times.map(|times| times.max.as_secs_f64() * 1000.0),
+        peak_rss_mb: progress.peak_rss,
+    };
+
+    let json = serde_json::to_string_pretty(&report)?;
+    eprintln!("{}", json);
+    if let Some(path) = &options.output {
+        std::fs::write(path, json)?;
+        eprintln!("Report written to {}", path);
+    }
+
+    Ok(())
+}
+
+/// Logs a bot in, then sends a position and a tick end every tick and a chat message
+/// at every chat interval until the end of the run, reading everything the server sends
+async fn run_bot(index: usize, address: SocketAddr, version: ProtocolVersion, chat_interval: Duration, end: Instant, stats: &SwarmStats) -> anyhow::Result<()> {
+    let joining = Instant::now();
+    let mut client = TestClient::join(address, version, &format!("Bot{}", index)).await?;
+    stats.join_latencies.lock().push(joining.elapsed());
+    stats.online.fetch_add(1, Ordering::Relaxed);
+
+    let result = play(index, &mut client, chat_interval, end, stats).await;
+
+    stats.online.fetch_sub(1, Ordering::Relaxed);
+    result
+}
+
+async fn play(index: usize, client: &mut TestClient, chat_interval: Duration, end: Instant, stats: &SwarmStats) -> anyhow::Result<()> {
+    let mut counted = (0, 0);
+    client.send(TeleportConfirmationRequestPacket { teleport_id: VarInt(0) }).await?;
+
+    // Spread the bots over a grid, and their chat messages over the interval
+    let center = ((index % 32) as f64 * 2.0 * WALK_RADIUS, (index / 32) as f64 * 2.0 * WALK_RADIUS);
+    let mut ticker = tokio::time::interval(Duration::from_millis(50));
+    let mut next_chat = Instant::now() + chat_interval.mul_f64((index % 100) as f64 / 100.0);
+    let mut tick: u64 = 0;
+    let mut messages: i64 = 0;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let now = Instant::now();
+                if now >= end {
+                    break;
+                }
+
+                // One lap every 10 seconds
+                let angle = tick as f64 / 200.0 * std::f64::consts::TAU;
+                client.send(SetPlayerPositionAndRotationRequestPacket {
+                    x: center.0 + WALK_RADIUS * angle.cos(),
+                    feet_y: 64.0,
+                    z: center.1 + WALK_RADIUS * angle.sin(),
+                    yaw: (angle.to_degrees() + 180.0) as f32,
+                    pitch: 0.0,
+                    flags: Byte(1),
+                }).await?;
+                client.send(ClientTickEndRequestPacket {}).await?;
+
+                if now >= next_chat {
+                    messages += 1;
+                    client.send(chat_message(format!("Message {} from bot {}", messages, index), messages)).await?;
+                    next_chat += chat_interval;
+                }
+
+                tick += 1;
+            }
+            packet = client.receive_any() => {
+                // Acknowledge chunk batches so the server keeps streaming them as the bot walks
+                client.handle_chunk_packet(&packet?).await?;
+            }
+        }
+
+        stats.add_traffic(client, &mut counted);
+    }
+
+    Ok(())
+}
+
+/// Unsigned chat message, as sent by an offline client without a chat session
+fn chat_message(message: String, salt: i64) -> ChatMessageRequestPacket {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_millis() as i64);
+
+    ChatMessageRequestPacket {
+        message,
+        timestamp,
+        salt,
+        signature: None,
+        message_count: VarInt(0),
+        acknowledged: FixedBitSet::new(),
+        checksum: Byte(0),
+    }
+}
+
+/// Measured over the whole run by [`report_progress`]
+#[derive(Default)]
+struct Progress {
+    ticks: TickTimes,
+    peak_rss: Option<f64>,
+}
+
+/// Prints the swarm state every second until the end of the run
+async fn report_progress(stats: &SwarmStats, end: Instant, in_process: bool) -> Progress {
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    ticker.tick().await;
+
+    // Ticks from before the first bot joined don't count
+    metrics::take_tick_times();
+
+    let mut progress = Progress::default();
+    let mut last_traffic = (0, 0);
+    let mut last_report = Instant::now();
+
+    while Instant::now() < end {
+        ticker.tick().await;
+
+        let traffic = (stats.bytes_sent.load(Ordering::Relaxed), stats.bytes_received.load(Ordering::Relaxed));
+        let seconds = last_report.elapsed().as_secs_f64();
+        let (sent, received) = ((traffic.0 - last_traffic.0) as f64 / seconds, (traffic.1 - last_traffic.1) as f64 / seconds);
+        last_traffic = traffic;
+        last_report = Instant::now();
+
+        let rss = resident_memory_mb();
+        if let Some(rss) = rss {
+            progress.peak_rss = Some(progress.peak_rss.map_or(rss, |peak| peak.max(rss)));
+        }
+
+        let ticks = if in_process {
+            let times = metrics::take_tick_times();
+            progress.ticks.ticks += times.ticks;
+            progress.ticks.total += times.total;
+            progress.ticks.max = progress.ticks.max.max(times.max);
+
+            format!("tick avg {:.2} ms max {:.2} ms", times.average().as_secs_f64() * 1000.0, times.max.as_secs_f64() * 1000.0)
+        } else {
+            "tick n/a".to_string()
+        };
+
+        eprintln!(
+            "online {} failed {} | out {:.1} KiB/s in {:.1} KiB/s | {} | rss {}",
+            stats.online.load(Ordering::Relaxed),
+            stats.failed.load(Ordering::Relaxed),
+            sent / 1024.0,
+            received / 1024.0,
+            ticks,
+            rss.map_or("n/a".to_string(), |rss| format!("{:.1} MiB", rss)),
+        );
+    }
+
+    progress
+}
+
+/// Resident set size of this process from /proc, None on other platforms
+fn resident_memory_mb() -> Option<f64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: f64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes / 1024.0)
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use nullspace::config::ServerConfig;
+use nullspace::networking::capture::{self, CaptureRecord, Direction};
+use nullspace::networking::channels::{self, ChannelRegistry};
+use nullspace::networking::packets::{self, PacketRegistries};
+use nullspace::networking::packets::inspect;
+
+const USAGE: &str = "Usage: nullspace-capture print <capture>\n       nullspace-capture replay <capture>";
+
+/// # Capture tool
+/// Prints the packets of a capture, or replays its inbound packets to a new connection
+/// and checks that the server answers with the same packets.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [command, path] = args.as_slice() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+
+    let records = capture::read_capture(path)?;
+    let registries = Arc::new(PacketRegistries::new(packets::register_all));
+
+    match command.as_str() {
+        "print" => {
+            for record in &records {
+                println!("{}", describe(record, &registries));
+            }
+        }
+        "replay" => {
+            let config = Arc::new(ServerConfig::load(ServerConfig::DEFAULT_PATH)?);
+            let mut channels = ChannelRegistry::new();
+            channels::register_all(&mut channels);
+
+            let expected: Vec<&CaptureRecord> = records.iter().filter(|record| record.direction == Direction::Outbound).collect();
+            let replayed = capture::replay(&records, &registries, config, Arc::new(channels)).await?;
+
+            if !compare(&expected, &replayed) {
+                std::process::exit(1);
+            }
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    Ok(())
+}
+
+/// One line per packet: time, direction, phase, id and the decoded packet
+fn describe(record: &CaptureRecord, registries: &PacketRegistries) -> String {
+    let (arrow, packet) = match record.direction {
+        Direction::Inbound => ("C->S", inspect::describe_serverbound(registries.get(record.version()), record.phase, &record.body)),
+        Direction::Outbound => ("S->C", inspect::describe_clientbound(record.version(), record.phase, &record.body)),
+    };
+
+    format!("{:>10.3}ms {} {:?} {}", record.elapsed.as_secs_f64() * 1000.0, arrow, record.phase, packet)
+}
+
+/// Compares the outbound packets by phase and id, their contents can change between runs
+fn compare(expected: &[&CaptureRecord], replayed: &[CaptureRecord]) -> bool {
+    let mut matches = true;
+
+    for index in 0..expected.len().max(replayed.len()) {
+        let expected = expected.get(index).map(|record| packet_key(record));
+        let replayed = replayed.get(index).map(packet_key);
+
+        if expected != replayed {
+            println!("Packet {}: expected {}, got {}", index, format_key(expected), format_key(replayed));
+            matches = false;
+        }
+    }
+
+    if matches {
+        println!("Replay matches, {} outbound packets", replayed.len());
+    }
+    matches
+}
+
+fn packet_key(record: &CaptureRecord) -> (String, i32) {
+    (format!("{:?}", record.phase), record.packet_id().map(|id| id.0).unwrap_or(-1))
+}
+
+fn format_key(key: Option<(String, i32)>) -> String {
+    match key {
+        Some((phase, id)) => format!("{} 0x{:02X}", phase, id),
+        None => "nothing".to_string(),
+    }
+}
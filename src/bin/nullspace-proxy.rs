@@ -0,0 +1,242 @@
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use bytes::BytesMut;
+use flate2::read::ZlibDecoder;
+use parking_lot::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::net::tcp::OwnedWriteHalf;
+use nullspace::networking::capture::Direction;
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::data_types::{BufferWrite, ByteReader};
+use nullspace::networking::data_types::raw_bytes::hex;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::framing::FrameReader;
+use nullspace::networking::packets::{self, inspect, Packet, PacketRegistries};
+use nullspace::networking::packets::clientbound::clientbound_name;
+use nullspace::networking::packets::handshake::HandshakePacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:25566";
+const DEFAULT_UPSTREAM: &str = "127.0.0.1:25565";
+
+// Serverbound ids of the packets the proxy follows, the same in every supported version
+const LOGIN_KEY: i32 = 0x01;
+const LOGIN_ACKNOWLEDGED: i32 = 0x03;
+const FINISH_CONFIGURATION_ACKNOWLEDGED: i32 = 0x03;
+const CONFIGURATION_ACKNOWLEDGED: i32 = 0x0F;
+
+/// Largest uncompressed packet accepted, like the vanilla decoder
+const MAX_DATA_LENGTH: usize = 8 * 1024 * 1024;
+
+/// What the proxy knows about a connection from the packets seen so far.
+/// Each direction switches phase on its own packets, like both ends of the connection do.
+struct ProxyState {
+    version: ProtocolVersion,
+    serverbound: ConnectionPhase,
+    clientbound: ConnectionPhase,
+    /// Frames carry the uncompressed length once the server sent "Set compression"
+    compressed: bool,
+}
+
+/// # Protocol proxy
+/// Listens locally and forwards every frame unchanged to an upstream server, printing both
+/// directions decoded with the server's own framing and decoders, so it doubles as a test of them.
+///
+/// Usage: `nullspace-proxy [listen address] [upstream address]`.
+/// The upstream server has to run in offline mode, encrypted connections are forwarded but not decoded.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let listen = args.next().unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+    let upstream = args.next().unwrap_or_else(|| DEFAULT_UPSTREAM.to_string());
+
+    let registries = Arc::new(PacketRegistries::new(packets::register_all));
+    let listener = TcpListener::bind(&listen).await?;
+    println!("Proxying {} to {}", listen, upstream);
+
+    let connections = AtomicUsize::new(0);
+    loop {
+        let (client, address) = listener.accept().await?;
+        let id = connections.fetch_add(1, Ordering::Relaxed) + 1;
+        println!("[{}] Client {} connected", id, address);
+
+        let upstream = upstream.clone();
+        let registries = registries.clone();
+        tokio::spawn(async move {
+            if let Err(e) = proxy(id, client, &upstream, registries).await {
+                eprintln!("[{}] Proxy error: {:?}", id, e);
+            }
+            println!("[{}] Closed", id);
+        });
+    }
+}
+
+async fn proxy(id: usize, client: TcpStream, upstream: &str, registries: Arc<PacketRegistries>) -> anyhow::Result<()> {
+    let server = TcpStream::connect(upstream).await?;
+    let _ = client.set_nodelay(true);
+    let _ = server.set_nodelay(true);
+
+    let state = Arc::new(Mutex::new(ProxyState {
+        version: ProtocolVersion::LATEST,
+        serverbound: ConnectionPhase::Handshaking,
+        clientbound: ConnectionPhase::Handshaking,
+        compressed: false,
+    }));
+
+    let (client_read, client_write) = client.into_split();
+    let (server_read, server_write) = server.into_split();
+
+    let serverbound = forward(id, Direction::Inbound, FrameReader::new(client_read), server_write, state.clone(), registries.clone());
+    let clientbound = forward(id, Direction::Outbound, FrameReader::new(server_read), client_write, state, registries);
+
+    let (serverbound, clientbound) = tokio::join!(serverbound, clientbound);
+    serverbound.and(clientbound)
+}
+
+/// Forwards the frames of one direction, printing each one as it goes through
+async fn forward(id: usize, direction: Direction, mut frames: FrameReader, mut output: OwnedWriteHalf, state: Arc<Mutex<ProxyState>>, registries: Arc<PacketRegistries>) -> anyhow::Result<()> {
+    let arrow = match direction {
+        Direction::Inbound => "C->S",
+        Direction::Outbound => "S->C",
+    };
+
+    loop {
+        // Frames are checked against the vanilla limit in both directions
+        let frame = match frames.read_frame(ConnectionPhase::Play.max_frame_size()).await {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break,
+            Err(e) => {
+                println!("[{}] {} {}, forwarding the rest without decoding it", id, arrow, e);
+                return forward_raw(frames, output).await;
+            }
+        };
+
+        let (version, phase, compressed) = {
+            let state = state.lock();
+            let phase = match direction {
+                Direction::Inbound => state.serverbound,
+                Direction::Outbound => state.clientbound,
+            };
+            (state.version, phase, state.compressed)
+        };
+
+        // Followed before forwarding, so the other direction sees a phase or compression change in time
+        let body = if compressed { decompress(&frame) } else { Ok(frame.to_vec()) };
+        let follow = match body {
+            Ok(body) => {
+                let description = match direction {
+                    Direction::Inbound => inspect::describe_serverbound(registries.get(version), phase, &body),
+                    Direction::Outbound => inspect::describe_clientbound(version, phase, &body),
+                };
+                println!("[{}] {} {:?} {}", id, arrow, phase, description);
+
+                follow_packet(id, direction, phase, &body, &mut state.lock())
+            }
+            Err(e) => {
+                println!("[{}] {} {:?} failed to decompress: {} {}", id, arrow, phase, e, hex(&frame));
+                Follow::Decode
+            }
+        };
+
+        let mut prefixed = Vec::with_capacity(frame.len() + VarInt::MAX_SIZE);
+        prefixed.write_type(VarInt(frame.len() as i32));
+        prefixed.extend_from_slice(&frame);
+        output.write_all(&prefixed).await?;
+
+        if follow == Follow::Encrypted {
+            println!("[{}] {} Encryption enabled, forwarding the rest without decoding it", id, arrow);
+            return forward_raw(frames, output).await;
+        }
+    }
+
+    let _ = output.shutdown().await;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Follow {
+    Decode,
+    /// The frames after this one are encrypted
+    Encrypted,
+}
+
+/// Follows the packets that change the phase, the version or the framing of the connection
+fn follow_packet(id: usize, direction: Direction, phase: ConnectionPhase, body: &[u8], state: &mut ProxyState) -> Follow {
+    let mut reader = ByteReader::new(body);
+    let Ok(VarInt(packet_id)) = reader.read_field() else {
+        return Follow::Decode;
+    };
+
+    match direction {
+        Direction::Inbound => match (phase, packet_id) {
+            (ConnectionPhase::Handshaking, 0x00) => {
+                let Ok(handshake) = HandshakePacket::decode(&mut reader) else {
+                    return Follow::Decode;
+                };
+
+                state.version = ProtocolVersion::from_protocol(handshake.protocol_version.0).unwrap_or_else(|| {
+                    println!("[{}] Unsupported protocol {}, decoding as {}", id, handshake.protocol_version.0, ProtocolVersion::LATEST);
+                    ProtocolVersion::LATEST
+                });
+
+                let next = if handshake.next_state.0 == 1 { ConnectionPhase::Status } else { ConnectionPhase::Login };
+                switch(id, "Serverbound", &mut state.serverbound, next);
+                switch(id, "Clientbound", &mut state.clientbound, next);
+            }
+            (ConnectionPhase::Login, LOGIN_KEY) => return Follow::Encrypted,
+            (ConnectionPhase::Login, LOGIN_ACKNOWLEDGED) => switch(id, "Serverbound", &mut state.serverbound, ConnectionPhase::Configuration),
+            (ConnectionPhase::Configuration, FINISH_CONFIGURATION_ACKNOWLEDGED) => switch(id, "Serverbound", &mut state.serverbound, ConnectionPhase::Play),
+            (ConnectionPhase::Play, CONFIGURATION_ACKNOWLEDGED) => switch(id, "Serverbound", &mut state.serverbound, ConnectionPhase::Configuration),
+            _ => {}
+        },
+        Direction::Outbound => match clientbound_name(state.version, phase, packet_id) {
+            Some("minecraft:hello") => return Follow::Encrypted,
+            Some("minecraft:login_compression") => {
+                println!("[{}] Compression enabled", id);
+                state.compressed = true;
+            }
+            Some("minecraft:login_finished") => switch(id, "Clientbound", &mut state.clientbound, ConnectionPhase::Configuration),
+            Some("minecraft:finish_configuration") => switch(id, "Clientbound", &mut state.clientbound, ConnectionPhase::Play),
+            Some("minecraft:start_configuration") => switch(id, "Clientbound", &mut state.clientbound, ConnectionPhase::Configuration),
+            _ => {}
+        },
+    }
+
+    Follow::Decode
+}
+
+fn switch(id: usize, direction: &str, phase: &mut ConnectionPhase, next: ConnectionPhase) {
+    println!("[{}] {} phase {:?} -> {:?}", id, direction, phase, next);
+    *phase = next;
+}
+
+/// Body (ID + Data) of a compressed frame: the uncompressed length, 0 if the data is sent as it is, and the data
+fn decompress(frame: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut reader = ByteReader::new(frame);
+    let VarInt(data_length) = reader.read_field()?;
+    let data = reader.read_remaining();
+
+    if data_length == 0 {
+        return Ok(data.to_vec());
+    }
+
+    if data_length < 0 || data_length as usize > MAX_DATA_LENGTH {
+        return Err(anyhow::anyhow!("Invalid uncompressed length {}", data_length));
+    }
+
+    let mut body = Vec::with_capacity(data_length as usize);
+    ZlibDecoder::new(data).take(data_length as u64).read_to_end(&mut body)?;
+    Ok(body)
+}
+
+/// Copies the rest of the stream as it is, starting with the bytes already buffered
+async fn forward_raw(frames: FrameReader, mut output: OwnedWriteHalf) -> anyhow::Result<()> {
+    let (mut input, buffered): (_, BytesMut) = frames.into_parts();
+    output.write_all(&buffered).await?;
+    tokio::io::copy(&mut input, &mut output).await?;
+
+    let _ = output.shutdown().await;
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::networking::packets::common::custom_report_details_response::ReportDetail;
+use crate::networking::packets::common::server_links_response::ServerLink;
+
+/// Server configuration, loaded from a JSON file next to the executable.
+/// Every field has a default, so the file only needs to contain what changes.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub resource_packs: Vec<ResourcePackConfig>,
+    pub resource_pack_server: ResourcePackServerConfig,
+    /// Links shown in the client's pause menu
+    pub server_links: Vec<ServerLink>,
+    /// Extra details attached to client crash reports
+    pub custom_report_details: Vec<ReportDetail>,
+    pub protocol_violations: ProtocolViolationConfig,
+    pub connection: ConnectionConfig,
+    pub rate_limits: RateLimitConfig,
+    pub capture: CaptureConfig,
+    pub world: WorldConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+    /// Radius in chunks sent around each player, players asking for less get their own
+    pub view_distance: u8,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        WorldConfig { view_distance: 10 }
+    }
+}
+
+/// What happens when a client goes over one of its rate limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitAction {
+    /// Discards the packet, or closes a new connection before it sends anything
+    Drop,
+    /// Disconnects the client, a new connection has no phase to send a reason in and is closed
+    Kick,
+    /// Disconnects the client and refuses its IP for `ban_secs`
+    Ban,
+}
+
+/// Budgets as token buckets: a sustained rate per second, with bursts up to the burst size
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// New connections from a single IP
+    pub connections_per_second: f64,
+    pub connection_burst: f64,
+    pub connection_action: LimitAction,
+    /// Local connections skip the connection throttle, like proxies on the same machine
+    pub exempt_loopback: bool,
+    /// Movement packets of a single connection
+    pub movement_per_second: f64,
+    pub movement_burst: f64,
+    /// Every other packet of a single connection
+    pub packets_per_second: f64,
+    pub packets_burst: f64,
+    pub packet_action: LimitAction,
+    pub ban_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            // One connection every 4 seconds, like the vanilla throttle
+            connections_per_second: 0.25,
+            connection_burst: 3.0,
+            connection_action: LimitAction::Drop,
+            exempt_loopback: true,
+            // Clients send at most one movement packet per tick
+            movement_per_second: 40.0,
+            movement_burst: 80.0,
+            packets_per_second: 100.0,
+            packets_burst: 300.0,
+            packet_action: LimitAction::Kick,
+            ban_secs: 300,
+        }
+    }
+}
+
+/// Who may connect and how long a client may keep a connection without making progress
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConnectionConfig {
+    /// Time from accepting the socket to the end of the login phase
+    pub login_timeout_secs: u64,
+    /// Time without receiving any bytes, clients in play send packets every tick
+    pub read_timeout_secs: u64,
+    /// Bytes waiting to be written to a client before it's kicked for being too slow
+    pub max_queued_bytes: usize,
+    /// Time a single write to the socket may take, a client that stopped reading is dropped after it
+    pub write_timeout_secs: u64,
+    /// Whether clients transferred from another server may log in, vanilla's "accepts-transfers"
+    pub accept_transfers: bool,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        ConnectionConfig {
+            login_timeout_secs: 30,
+            read_timeout_secs: 30,
+            max_queued_bytes: 8 * 1024 * 1024,
+            write_timeout_secs: 30,
+            accept_transfers: false,
+        }
+    }
+}
+
+/// Opt-in recording of the frames of connections, read them with the nullspace-capture binary
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CaptureConfig {
+    pub enabled: bool,
+    /// Each connection gets its own file in it
+    pub directory: PathBuf,
+    /// Only connections from these addresses are captured, every connection if empty
+    pub addresses: Vec<IpAddr>,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        CaptureConfig {
+            enabled: false,
+            directory: PathBuf::from("captures"),
+            addresses: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResourcePackConfig {
+    pub id: Uuid,
+    pub url: String,
+    /// SHA-1 of the zip file as 40 hex characters
+    pub hash: String,
+    /// Players declining or failing to load a required pack are kicked
+    #[serde(default)]
+    pub required: bool,
+    /// Text shown on the client's prompt screen
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// Embedded HTTP server hosting the zip files of a directory as resource packs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ResourcePackServerConfig {
+    pub enabled: bool,
+    pub bind: String,
+    /// Base URL the clients use to reach the server, e.g. "http://play.example.com:8080"
+    pub public_url: String,
+    pub directory: PathBuf,
+    /// Applies to every hosted pack
+    pub required: bool,
+    pub prompt: Option<String>,
+    /// Time a client has to send its request once connected, and to take each part of the response
+    pub request_timeout_secs: u64,
+}
+
+impl Default for ResourcePackServerConfig {
+    fn default() -> Self {
+        ResourcePackServerConfig {
+            enabled: false,
+            bind: "0.0.0.0:8080".to_string(),
+            public_url: "http://127.0.0.1:8080".to_string(),
+            directory: PathBuf::from("resource_packs"),
+            required: false,
+            prompt: None,
+            request_timeout_secs: 10,
+        }
+    }
+}
+
+/// What the server does when a client breaks the protocol.
+/// The offending packet is never handled, the action only decides what else happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationAction {
+    Ignore,
+    Log,
+    Kick,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProtocolViolationConfig {
+    /// Packet id the server doesn't handle in the current phase
+    pub unknown_packet: ViolationAction,
+    /// Handled packet arriving out of order, e.g. a second login start
+    pub unexpected_packet: ViolationAction,
+    /// Bytes left in the frame after the packet was decoded
+    pub trailing_bytes: ViolationAction,
+}
+
+impl Default for ProtocolViolationConfig {
+    fn default() -> Self {
+        ProtocolViolationConfig {
+            // Most of the play packets have no handler yet
+            unknown_packet: ViolationAction::Log,
+            unexpected_packet: ViolationAction::Kick,
+            trailing_bytes: ViolationAction::Kick,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub const DEFAULT_PATH: &'static str = "nullspace.json";
+
+    /// Reads the configuration file, or falls back to the defaults if it does not exist
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<ServerConfig> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            println!("No config found at {}, using defaults", path.display());
+            return Ok(ServerConfig::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid config {}: {}", path.display(), e))
+    }
+}
@@ -0,0 +1,18 @@
+//! Nullspace, a Minecraft: Java Edition server.
+//! The binary in main.rs runs it, benches and tools use the protocol types from here.
+
+// The derive macros name their traits through `::nullspace`, in here as well as in other crates
+extern crate self as nullspace;
+
+use std::sync::Arc;
+use dashmap::DashMap;
+use uuid::Uuid;
+use crate::world::entities::player::Player;
+
+pub mod config;
+pub mod networking;
+pub mod server;
+pub mod testing;
+pub mod world;
+
+pub type PlayerList = Arc<DashMap<Uuid, Player>>;
@@ -0,0 +1,117 @@
+use std::ops::Deref;
+use parking_lot::Mutex;
+use crate::networking::data_types::var_int::VarInt;
+
+/// Bytes reserved in front of a frame for its length, a 3 byte VarInt like the vanilla frame decoder reads
+const FRAME_PREFIX_SIZE: usize = 3;
+/// Largest frame body the prefix can hold
+const MAX_FRAME_BODY: usize = (1 << (7 * FRAME_PREFIX_SIZE)) - 1;
+
+/// Buffers kept for reuse at most, the rest are freed
+const MAX_POOLED_BUFFERS: usize = 1024;
+/// Buffers that grew above this are freed instead of kept, so one big packet doesn't pin its memory
+const MAX_POOLED_CAPACITY: usize = 64 * 1024;
+const INITIAL_CAPACITY: usize = 512;
+
+/// Encoding buffers released by the writer tasks once their frames are on the wire
+static POOL: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+/// Takes an empty buffer from the pool, or allocates one if the pool is empty
+pub fn take() -> Vec<u8> {
+    POOL.lock().pop().unwrap_or_else(|| Vec::with_capacity(INITIAL_CAPACITY))
+}
+
+fn release(mut buffer: Vec<u8>) {
+    if buffer.capacity() > MAX_POOLED_CAPACITY {
+        return;
+    }
+
+    buffer.clear();
+
+    let mut pool = POOL.lock();
+    if pool.len() < MAX_POOLED_BUFFERS {
+        pool.push(buffer);
+    }
+}
+
+/// Encoded frames in a pooled buffer, the buffer goes back to the pool when the frame is dropped
+pub struct PooledFrame {
+    buffer: Vec<u8>,
+    /// The length prefix takes less than the reserved bytes for most frames, they start after the unused ones
+    start: usize,
+}
+
+impl PooledFrame {
+    /// Encodes a single frame: the body is written once after room for the length,
+    /// which is back-patched in front of it when the body is done
+    pub fn encode(write_body: impl FnOnce(&mut Vec<u8>) -> anyhow::Result<()>) -> anyhow::Result<PooledFrame> {
+        let mut buffer = take();
+        buffer.resize(FRAME_PREFIX_SIZE, 0);
+        if let Err(e) = write_body(&mut buffer) {
+            release(buffer);
+            return Err(e);
+        }
+
+        let (prefix, prefix_size) = frame_prefix(buffer.len() - FRAME_PREFIX_SIZE)?;
+        let start = FRAME_PREFIX_SIZE - prefix_size;
+        buffer[start..FRAME_PREFIX_SIZE].copy_from_slice(&prefix[..prefix_size]);
+
+        Ok(PooledFrame { buffer, start })
+    }
+
+    /// Wraps frames written back to back with [`append_frame`]
+    pub fn from_frames(buffer: Vec<u8>) -> PooledFrame {
+        PooledFrame { buffer, start: 0 }
+    }
+}
+
+impl Deref for PooledFrame {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.start..]
+    }
+}
+
+impl Drop for PooledFrame {
+    fn drop(&mut self) {
+        release(std::mem::take(&mut self.buffer));
+    }
+}
+
+/// Appends a frame to a buffer holding other frames. The body is written once,
+/// and only moved back by the unused prefix bytes when its length needs less than 3.
+/// The buffer is left as it was if the frame can't be written.
+pub fn append_frame(buffer: &mut Vec<u8>, write_body: impl FnOnce(&mut Vec<u8>) -> anyhow::Result<()>) -> anyhow::Result<()> {
+    let frame_start = buffer.len();
+    buffer.resize(frame_start + FRAME_PREFIX_SIZE, 0);
+    if let Err(e) = write_body(buffer) {
+        buffer.truncate(frame_start);
+        return Err(e);
+    }
+
+    let body_start = frame_start + FRAME_PREFIX_SIZE;
+    let (prefix, prefix_size) = match frame_prefix(buffer.len() - body_start) {
+        Ok(prefix) => prefix,
+        Err(e) => {
+            buffer.truncate(frame_start);
+            return Err(e);
+        }
+    };
+
+    buffer[frame_start..frame_start + prefix_size].copy_from_slice(&prefix[..prefix_size]);
+    if prefix_size < FRAME_PREFIX_SIZE {
+        buffer.copy_within(body_start.., frame_start + prefix_size);
+        buffer.truncate(buffer.len() - (FRAME_PREFIX_SIZE - prefix_size));
+    }
+
+    Ok(())
+}
+
+fn frame_prefix(body_length: usize) -> anyhow::Result<([u8; VarInt::MAX_SIZE], usize)> {
+    if body_length > MAX_FRAME_BODY {
+        return Err(anyhow::anyhow!("Packet of {} bytes is over the {} bytes frame limit", body_length, MAX_FRAME_BODY));
+    }
+
+    Ok(VarInt(body_length as i32).encode())
+}
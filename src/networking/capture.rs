@@ -0,0 +1,278 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use crate::config::{CaptureConfig, ServerConfig};
+use crate::networking::channels::ChannelRegistry;
+use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::data_types::{BufferWrite, ByteReader};
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::PacketRegistries;
+use crate::networking::protocol_version::ProtocolVersion;
+use crate::networking::rate_limit::IpThrottle;
+
+/// First bytes of a capture file, the last one is the version of the format
+const MAGIC: &[u8; 6] = b"NSCAP\x01";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Sent by the client
+    Inbound,
+    /// Sent by the server
+    Outbound,
+}
+
+/// A captured frame with what the connection knew when it went through
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureRecord {
+    /// Time since the capture started
+    pub elapsed: Duration,
+    pub direction: Direction,
+    pub phase: ConnectionPhase,
+    /// Protocol number of the connection, the latest one until the handshake negotiated it
+    pub protocol: i32,
+    /// Packet id and data, without the length prefix
+    pub body: Vec<u8>,
+}
+
+impl CaptureRecord {
+    pub fn packet_id(&self) -> anyhow::Result<VarInt> {
+        ByteReader::new(&self.body).read_field()
+    }
+
+    /// Version to decode the packet with, the latest one if the protocol isn't supported
+    pub fn version(&self) -> ProtocolVersion {
+        ProtocolVersion::from_protocol(self.protocol).unwrap_or(ProtocolVersion::LATEST)
+    }
+
+    /// Packet id and data as they were written to the socket
+    pub fn frame(&self) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(self.body.len() + VarInt::MAX_SIZE);
+        frame.write_type(VarInt(self.body.len() as i32));
+        frame.extend_from_slice(&self.body);
+        frame
+    }
+
+    // Layout: elapsed micros (u64), direction (u8), phase (u8), protocol (i32), body length (u32) and body, big endian
+
+    fn write(&self, output: &mut impl Write) -> std::io::Result<()> {
+        output.write_all(&(self.elapsed.as_micros() as u64).to_be_bytes())?;
+        output.write_all(&[self.direction as u8, phase_to_byte(self.phase)])?;
+        output.write_all(&self.protocol.to_be_bytes())?;
+        output.write_all(&(self.body.len() as u32).to_be_bytes())?;
+        output.write_all(&self.body)
+    }
+
+    /// Reads the next record, None at the end of the capture
+    fn read(input: &mut impl Read) -> anyhow::Result<Option<CaptureRecord>> {
+        let mut header = [0u8; 18];
+        match input.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let elapsed = Duration::from_micros(u64::from_be_bytes(header[0..8].try_into()?));
+        let direction = match header[8] {
+            0 => Direction::Inbound,
+            1 => Direction::Outbound,
+            other => return Err(anyhow::anyhow!("Invalid capture direction {}", other)),
+        };
+        let phase = phase_from_byte(header[9])?;
+        let protocol = i32::from_be_bytes(header[10..14].try_into()?);
+        let length = u32::from_be_bytes(header[14..18].try_into()?) as usize;
+
+        let mut body = vec![0u8; length];
+        input.read_exact(&mut body)?;
+
+        Ok(Some(CaptureRecord { elapsed, direction, phase, protocol, body }))
+    }
+}
+
+fn phase_to_byte(phase: ConnectionPhase) -> u8 {
+    match phase {
+        ConnectionPhase::Handshaking => 0,
+        ConnectionPhase::Status => 1,
+        ConnectionPhase::Login => 2,
+        ConnectionPhase::Configuration => 3,
+        ConnectionPhase::Play => 4,
+    }
+}
+
+fn phase_from_byte(byte: u8) -> anyhow::Result<ConnectionPhase> {
+    match byte {
+        0 => Ok(ConnectionPhase::Handshaking),
+        1 => Ok(ConnectionPhase::Status),
+        2 => Ok(ConnectionPhase::Login),
+        3 => Ok(ConnectionPhase::Configuration),
+        4 => Ok(ConnectionPhase::Play),
+        other => Err(anyhow::anyhow!("Invalid capture phase {}", other)),
+    }
+}
+
+enum CaptureSink {
+    File(BufWriter<File>),
+    /// Kept for [`replay`], which compares them with a capture
+    Memory(Vec<CaptureRecord>),
+    /// Writing to the file failed, the rest of the connection isn't captured
+    Closed,
+}
+
+struct CaptureState {
+    started: Instant,
+    sink: CaptureSink,
+}
+
+/// Records the frames of a connection, shared by the connection for inbound
+/// frames and by its [`crate::networking::writer::PacketWriter`] for outbound ones
+#[derive(Clone)]
+pub struct PacketCapture {
+    state: Arc<Mutex<CaptureState>>,
+}
+
+impl PacketCapture {
+    /// Starts capturing a new connection if the config selects it.
+    /// A capture that can't be created is logged, the connection goes on without it.
+    pub fn for_connection(config: &CaptureConfig, address: SocketAddr) -> Option<PacketCapture> {
+        if !config.enabled || (!config.addresses.is_empty() && !config.addresses.contains(&address.ip())) {
+            return None;
+        }
+
+        match PacketCapture::create(&config.directory, address) {
+            Ok(capture) => Some(capture),
+            Err(e) => {
+                eprintln!("Could not capture packets of {}: {:?}", address, e);
+                None
+            }
+        }
+    }
+
+    /// Creates a capture file named after the time and the address of the connection
+    pub fn create(directory: &Path, address: SocketAddr) -> anyhow::Result<PacketCapture> {
+        std::fs::create_dir_all(directory)?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let name = format!("{}-{}-{}.nscap", timestamp, address.ip(), address.port()).replace(':', "_");
+        let path = directory.join(name);
+
+        let mut file = BufWriter::new(File::create(&path)?);
+        file.write_all(MAGIC)?;
+        println!("Capturing packets of {} to {}", address, path.display());
+
+        Ok(PacketCapture::new(CaptureSink::File(file)))
+    }
+
+    pub fn in_memory() -> PacketCapture {
+        PacketCapture::new(CaptureSink::Memory(Vec::new()))
+    }
+
+    fn new(sink: CaptureSink) -> PacketCapture {
+        PacketCapture { state: Arc::new(Mutex::new(CaptureState { started: Instant::now(), sink })) }
+    }
+
+    /// Records a frame body (ID + Data)
+    pub fn record(&self, direction: Direction, version: ProtocolVersion, phase: ConnectionPhase, body: &[u8]) {
+        let mut state = self.state.lock();
+        let record = CaptureRecord {
+            elapsed: state.started.elapsed(),
+            direction,
+            phase,
+            protocol: version.protocol(),
+            body: body.to_vec(),
+        };
+
+        match &mut state.sink {
+            // Flushed every time, so a capture is complete even if the server crashes
+            CaptureSink::File(file) => {
+                if let Err(e) = record.write(file).and_then(|_| file.flush()) {
+                    eprintln!("Stopping packet capture: {}", e);
+                    state.sink = CaptureSink::Closed;
+                }
+            }
+            CaptureSink::Memory(records) => records.push(record),
+            CaptureSink::Closed => {}
+        }
+    }
+
+    /// Records every frame of length prefixed frames written back to back, like a bundle
+    pub fn record_frames(&self, direction: Direction, version: ProtocolVersion, phase: ConnectionPhase, mut frames: &[u8]) {
+        while let Ok(Some((length, prefix_size))) = VarInt::peek(frames) {
+            let Ok(length) = usize::try_from(length.0) else {
+                break;
+            };
+            let Some(end) = prefix_size.checked_add(length).filter(|end| *end <= frames.len()) else {
+                break;
+            };
+
+            self.record(direction, version, phase, &frames[prefix_size..end]);
+            frames = &frames[end..];
+        }
+    }
+
+    /// Records of an in-memory capture, always empty for a file
+    pub fn records(&self) -> Vec<CaptureRecord> {
+        match &self.state.lock().sink {
+            CaptureSink::Memory(records) => records.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Reads every record of a capture file
+pub fn read_capture(path: impl AsRef<Path>) -> anyhow::Result<Vec<CaptureRecord>> {
+    let path = path.as_ref();
+    let mut input = BufReader::new(File::open(path)?);
+
+    let mut magic = [0u8; MAGIC.len()];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(anyhow::anyhow!("{} is not a packet capture", path.display()));
+    }
+
+    let mut records = Vec::new();
+    while let Some(record) = CaptureRecord::read(&mut input)? {
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Replays the inbound frames of a capture to a new connection, in order and without waiting between them.
+/// Returns the frames the server sent back, to compare them with the outbound frames of the capture.
+pub async fn replay(records: &[CaptureRecord], registries: &Arc<PacketRegistries>, config: Arc<ServerConfig>, channels: Arc<ChannelRegistry>) -> anyhow::Result<Vec<CaptureRecord>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let client = TcpStream::connect(listener.local_addr()?).await?;
+    let (socket, address) = listener.accept().await?;
+
+    let capture = PacketCapture::in_memory();
+    let throttle = Arc::new(IpThrottle::new(&config.rate_limits));
+    let mut connection = Connection::with_capture(socket, address, ConnectionPhase::Handshaking, Arc::new(DashMap::new()), config, channels, throttle, Some(capture.clone()));
+
+    let registries = registries.clone();
+    let server = tokio::spawn(async move { connection.run(&registries).await });
+
+    // The responses are recorded by the capture, the socket only has to be drained
+    let (mut client_read, mut client_write) = client.into_split();
+    let drain = tokio::spawn(async move { tokio::io::copy(&mut client_read, &mut tokio::io::sink()).await });
+
+    for record in records.iter().filter(|record| record.direction == Direction::Inbound) {
+        // A kicked connection stops reading, the frames after the kick are left out
+        if client_write.write_all(&record.frame()).await.is_err() {
+            break;
+        }
+    }
+    let _ = client_write.shutdown().await;
+
+    server.await??;
+    drain.abort();
+
+    Ok(capture.records().into_iter()
+        .filter(|record| record.direction == Direction::Outbound)
+        .collect())
+}
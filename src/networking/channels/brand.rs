@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+use crate::networking::channels::ChannelHandler;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::ByteReader;
+
+/// "minecraft:brand", the client tells its mod loader or "vanilla"
+pub struct BrandChannel;
+
+#[async_trait]
+impl ChannelHandler for BrandChannel {
+    async fn handle(&self, data: &[u8], ctx: &mut Connection) -> anyhow::Result<()> {
+        let brand_name: String = ByteReader::new(data).read_field()?;
+        println!("Client Brand: {}", brand_name);
+
+        if brand_name != "vanilla" {
+            println!("Modded client detected!");
+        }
+
+        if let Some(mut player) = ctx.player_mut() {
+            player.brand = Some(brand_name);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+pub mod brand;
+pub mod register;
+
+use std::collections::HashMap;
+use anyhow::Result;
+use async_trait::async_trait;
+use crate::networking::channels::brand::BrandChannel;
+use crate::networking::channels::register::{RegisterChannel, UnregisterChannel};
+use crate::networking::connection::Connection;
+use crate::networking::data_types::identifier::Identifier;
+
+/// Handles the payload of a custom channel, in both the configuration and play phases
+#[async_trait]
+pub trait ChannelHandler {
+    async fn handle(&self, data: &[u8], context: &mut Connection) -> Result<()>;
+}
+
+#[derive(Default)]
+pub struct ChannelRegistry {
+    handlers: HashMap<Identifier, Box<dyn ChannelHandler + Send + Sync>>,
+}
+
+impl ChannelRegistry {
+    pub fn new() -> Self {
+        Self { handlers: HashMap::new() }
+    }
+
+    pub fn register<H>(&mut self, channel: Identifier, handler: H)
+    where
+        H: ChannelHandler + Send + Sync + 'static,
+    {
+        self.handlers.insert(channel, Box::new(handler));
+    }
+
+    /// Channels outside the "minecraft" namespace the server listens on.
+    /// They are announced to the client with "minecraft:register".
+    pub fn custom_channels(&self) -> Vec<Identifier> {
+        let mut channels: Vec<Identifier> = self.handlers.keys()
+            .filter(|channel| channel.namespace != "minecraft")
+            .cloned()
+            .collect();
+
+        channels.sort_by_key(|channel| channel.to_string());
+        channels
+    }
+
+    pub async fn handle(&self, channel: &Identifier, data: &[u8], ctx: &mut Connection) -> Result<()> {
+        if let Some(handler) = self.handlers.get(channel) {
+            handler.handle(data, ctx).await
+        } else {
+            println!("Unknown plugin channel: {}", channel);
+            Ok(())
+        }
+    }
+}
+
+/// Payload of "minecraft:register" and "minecraft:unregister", channel names separated by null bytes
+pub fn encode_channel_list(channels: &[Identifier]) -> Vec<u8> {
+    channels.iter()
+        .map(|channel| channel.to_string())
+        .collect::<Vec<String>>()
+        .join("\0")
+        .into_bytes()
+}
+
+/// Invalid channel names are skipped
+pub fn decode_channel_list(data: &[u8]) -> Vec<Identifier> {
+    data.split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| std::str::from_utf8(name).ok())
+        .filter_map(|name| name.parse::<Identifier>().ok())
+        .collect()
+}
+
+/// Registers the channels the server handles itself
+pub fn register_all(channels: &mut ChannelRegistry) {
+    channels.register(Identifier::minecraft("brand"), BrandChannel);
+    channels.register(Identifier::minecraft("register"), RegisterChannel);
+    channels.register(Identifier::minecraft("unregister"), UnregisterChannel);
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use crate::networking::channels::{decode_channel_list, ChannelHandler};
+use crate::networking::connection::Connection;
+
+/// Channels a client may register at most, like Paper
+const MAX_CHANNELS: usize = 128;
+/// Longest channel name accepted, like Bukkit's messenger
+const MAX_CHANNEL_NAME_LENGTH: usize = 64;
+
+/// "minecraft:register", the client declares the channels it listens on
+pub struct RegisterChannel;
+
+#[async_trait]
+impl ChannelHandler for RegisterChannel {
+    async fn handle(&self, data: &[u8], ctx: &mut Connection) -> anyhow::Result<()> {
+        let channels = decode_channel_list(data);
+        println!("Client registered channels: {:?}", channels);
+
+        if let Some(mut player) = ctx.player_mut() {
+            for channel in channels {
+                if channel.to_string().len() > MAX_CHANNEL_NAME_LENGTH {
+                    println!("Ignoring channel {}, the name is over {} characters", channel, MAX_CHANNEL_NAME_LENGTH);
+                    continue;
+                }
+
+                if player.channels.len() >= MAX_CHANNELS && !player.channels.contains(&channel) {
+                    println!("Ignoring the remaining channels, {} are already registered", MAX_CHANNELS);
+                    break;
+                }
+
+                player.channels.insert(channel);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// "minecraft:unregister", the client stops listening on some channels
+pub struct UnregisterChannel;
+
+#[async_trait]
+impl ChannelHandler for UnregisterChannel {
+    async fn handle(&self, data: &[u8], ctx: &mut Connection) -> anyhow::Result<()> {
+        let channels = decode_channel_list(data);
+        println!("Client unregistered channels: {:?}", channels);
+
+        if let Some(mut player) = ctx.player_mut() {
+            for channel in &channels {
+                player.channels.remove(channel);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,34 @@
-use std::io::Cursor;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use std::time::Duration;
 use tokio::net::TcpStream;
-use tokio::sync::mpsc;
-use crate::networking::data_types::{BufferWrite, PacketWrite, StreamExt, StreamWrite};
+use tokio::time::Instant;
+use dashmap::mapref::one::RefMut;
+use uuid::Uuid;
+use bytes::BytesMut;
+use crate::networking::data_types::ByteReader;
 use crate::networking::data_types::var_int::VarInt;
-use crate::networking::packets::{PacketRegistry};
+use crate::networking::framing::{FrameError, FrameReader};
+use crate::networking::packets::PacketRegistries;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::networking::writer::{PacketBundle, PacketWriter};
+use std::net::SocketAddr;
+use crate::config::{LimitAction, ServerConfig, ViolationAction};
+use crate::networking::channels::ChannelRegistry;
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::raw_bytes::RawBytes;
+use crate::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::protocol_version::ProtocolVersion;
+use crate::networking::capture::{Direction, PacketCapture};
+use crate::networking::metrics::{self, Rejection};
+use crate::networking::rate_limit::{IpThrottle, PacketRateLimiter};
 use crate::PlayerList;
-use crate::world::World;
+use crate::world::entities::player::Player;
+
+/// Largest legal handshake frame: the packet id, protocol version and intent as VarInts of any size,
+/// the port, and the 255 character server address with its length. A character is one UTF-16 unit
+/// for the limit and at most 3 bytes on the wire.
+const MAX_HANDSHAKE_SIZE: usize = VarInt::MAX_SIZE * 3 + 2 + VarInt::MAX_SIZE + 255 * 3;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ConnectionPhase {
@@ -19,125 +39,259 @@ pub enum ConnectionPhase {
     Play,
 }
 
-pub enum NetMessage {
-    SendPacket(Vec<u8>),
-    Disconnect,
+impl ConnectionPhase {
+    /// Whether a connection in this phase may move to the next one.
+    /// Transfers arrive through the handshake as a login, so they need no phase of their own.
+    pub fn can_switch_to(self, next: ConnectionPhase) -> bool {
+        use ConnectionPhase::*;
+
+        matches!((self, next),
+            (Handshaking, Status)
+            | (Handshaking, Login)
+            | (Login, Configuration)
+            | (Configuration, Play)
+            | (Play, Configuration))
+    }
+
+    /// Largest frame (ID + Data) a client may send in this phase.
+    /// The early phases only carry a few small packets, later ones follow the vanilla limit.
+    pub fn max_frame_size(self) -> usize {
+        match self {
+            ConnectionPhase::Handshaking => MAX_HANDSHAKE_SIZE,
+            ConnectionPhase::Status => 64,
+            ConnectionPhase::Login => 32 * 1024,
+            // Largest length a 3 byte VarInt prefix can hold
+            ConnectionPhase::Configuration | ConnectionPhase::Play => 2_097_151,
+        }
+    }
+
+    /// Phases in which the client is still connecting, bounded by the login deadline
+    pub fn is_login(self) -> bool {
+        matches!(self, ConnectionPhase::Handshaking | ConnectionPhase::Status | ConnectionPhase::Login)
+    }
+}
+
+/// Ways a client can break the protocol, each one has its action in [`crate::config::ProtocolViolationConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolViolation {
+    UnknownPacket,
+    UnexpectedPacket,
+    TrailingBytes,
 }
 
 pub struct Connection {
-    read_stream: OwnedReadHalf,
-    pub(crate) writer_sender: mpsc::Sender<NetMessage>,
-    pub phase: ConnectionPhase,
+    pub address: SocketAddr,
+    frames: FrameReader,
+    pub(crate) writer: PacketWriter,
+    /// Only changes through [`Connection::switch_phase`]
+    phase: ConnectionPhase,
+    /// Negotiated in the handshake, the latest version until then
+    pub protocol: ProtocolVersion,
     is_alive: bool,
     pub player_list: PlayerList,
+    pub player_uuid: Option<Uuid>,
+    pub config: Arc<ServerConfig>,
+    pub channels: Arc<ChannelRegistry>,
+    /// The connection must leave the login phases before this instant
+    login_deadline: Instant,
+    rate_limiter: PacketRateLimiter,
+    throttle: Arc<IpThrottle>,
+    capture: Option<PacketCapture>,
 }
 
 impl Connection {
-    pub(crate) fn new(stream: TcpStream, phase: ConnectionPhase, player_list: PlayerList) -> Connection {
+    pub fn new(stream: TcpStream, address: SocketAddr, phase: ConnectionPhase, player_list: PlayerList, config: Arc<ServerConfig>, channels: Arc<ChannelRegistry>, throttle: Arc<IpThrottle>) -> Connection {
+        let capture = PacketCapture::for_connection(&config.capture, address);
+        Connection::with_capture(stream, address, phase, player_list, config, channels, throttle, capture)
+    }
+
+    /// Like [`Connection::new`], recording the frames to a given capture instead of the configured one
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_capture(stream: TcpStream, address: SocketAddr, phase: ConnectionPhase, player_list: PlayerList, config: Arc<ServerConfig>, channels: Arc<ChannelRegistry>, throttle: Arc<IpThrottle>, capture: Option<PacketCapture>) -> Connection {
+        // Packets are batched by the writer, so Nagle's algorithm would only add latency
+        let _ = stream.set_nodelay(true);
+
         let (read_stream, write_stream) = stream.into_split();
-        let (tx, rx) = mpsc::channel(128); // 128 packets
+        let writer = PacketWriter::spawn(write_stream, config.connection.max_queued_bytes, Duration::from_secs(config.connection.write_timeout_secs), capture.clone());
 
-        tokio::spawn(handle_writes(write_stream, rx));
+        let login_deadline = Instant::now() + Duration::from_secs(config.connection.login_timeout_secs);
+        let rate_limiter = PacketRateLimiter::new(&config.rate_limits);
 
-        Connection { read_stream, writer_sender: tx, phase, is_alive: true, player_list }
+        Connection { address, frames: FrameReader::new(read_stream), writer, phase, protocol: ProtocolVersion::LATEST, is_alive: true, player_list, player_uuid: None, config, channels, login_deadline, rate_limiter, throttle, capture }
     }
 
-    pub async fn close(&mut self) -> anyhow::Result<()> {
-        self.is_alive = false;
-        let _ = self.writer_sender.send(NetMessage::Disconnect).await;
-        Ok(())
+    /// Removes the player of this connection from the list, once the connection ended.
+    /// A later login with the same uuid replaced the entry, that player is kept.
+    pub fn remove_player(&self) {
+        if let Some(uuid) = self.player_uuid {
+            self.player_list.remove_if(&uuid, |_, player| player.writer.same_connection(&self.writer));
+        }
     }
 
-    pub(crate) async fn run(&mut self, registry: &Arc<PacketRegistry>) -> anyhow::Result<()> {
-        loop {
-            if !self.is_alive {
-                break;
-            }
+    /// The player logged through this connection.
+    /// Don't keep the reference across an await, it locks the player list shard.
+    pub fn player_mut(&self) -> Option<RefMut<'_, Uuid, Player>> {
+        self.player_uuid.and_then(|uuid| self.player_list.get_mut(&uuid))
+    }
 
-            let packet_length_res = self.read_stream.read_type::<VarInt>().await;
-            let packet_length = match packet_length_res {
-                Ok(len) => len,
-                Err(_) => {
-                    break; // Disconnected
-                }
-            };
+    pub fn phase(&self) -> ConnectionPhase {
+        self.phase
+    }
+
+    /// Changes the phase of the connection, keeping the phase of the logged player in sync.
+    /// Fails if the protocol doesn't allow going from the current phase to the new one.
+    pub fn switch_phase(&mut self, phase: ConnectionPhase) -> anyhow::Result<()> {
+        if !self.phase.can_switch_to(phase) {
+            return Err(anyhow::anyhow!("Invalid phase transition from {:?} to {:?}", self.phase, phase));
+        }
 
-            let mut packet_buffer = vec![0u8; packet_length.0 as usize];
-            self.read_stream.read_exact(&mut packet_buffer).await?;
-            let mut cursor = Cursor::new(&packet_buffer[..]);
+        self.phase = phase;
 
-            let packet_id: VarInt = cursor.read_type().await?;
-            registry.handle_packet(self.phase, packet_id.0, &mut cursor, self).await?;
+        if let Some(uuid) = self.player_uuid
+            && let Some(mut player) = self.player_list.get_mut(&uuid) {
+            player.phase = phase;
         }
 
         Ok(())
     }
 
-    pub async fn send_packet<T: PacketWrite>(&mut self, packet_id: i32, packet: T) -> anyhow::Result<()> {
-        send_packet(&self.writer_sender, packet_id, packet).await
+    pub async fn close(&mut self) -> anyhow::Result<()> {
+        self.is_alive = false;
+        self.writer.disconnect();
+        Ok(())
     }
 
-    pub async fn send_raw_packet(&mut self, packet_id: i32, body: &[u8]) -> anyhow::Result<()> {
-        send_raw_packet(&self.writer_sender, packet_id, body).await
+    /// Sends the disconnect packet of the current phase with a reason and closes the connection.
+    /// Packets still waiting to be written are discarded.
+    pub async fn kick(&mut self, reason: impl Into<TextComponent>) -> anyhow::Result<()> {
+        let reason = reason.into();
+        println!("Kicking connection at phase {:?}: {}", self.phase, reason.plain_text());
+
+        self.is_alive = false;
+        self.writer.kick(self.protocol, self.phase, reason);
+        Ok(())
     }
-}
 
-pub async fn send_packet<T: PacketWrite>(writer_sender: &mpsc::Sender<NetMessage>, packet_id: i32, packet: T) -> anyhow::Result<()> {
-    // Create a buffer for the Packet Body (ID + Data)
-    let mut body_buffer = Vec::new();
-    body_buffer.write_type(VarInt(packet_id));
-    body_buffer.write_type(packet);
+    /// Applies the configured action for a protocol violation
+    pub async fn protocol_violation(&mut self, violation: ProtocolViolation, details: String) -> anyhow::Result<()> {
+        let violations = &self.config.protocol_violations;
+        let (action, rejection) = match violation {
+            ProtocolViolation::UnknownPacket => (violations.unknown_packet, Rejection::UnknownPacket),
+            ProtocolViolation::UnexpectedPacket => (violations.unexpected_packet, Rejection::UnexpectedPacket),
+            ProtocolViolation::TrailingBytes => (violations.trailing_bytes, Rejection::TrailingBytes),
+        };
+        metrics::record(rejection);
 
-    // Calculate total length
-    let length = VarInt(body_buffer.len() as i32);
-    let mut final_buffer = Vec::new();
-    final_buffer.write_type(length);
-    final_buffer.extend_from_slice(&body_buffer);
+        match action {
+            ViolationAction::Ignore => Ok(()),
+            ViolationAction::Log => {
+                println!("Protocol violation at phase {:?}: {}", self.phase, details);
+                Ok(())
+            }
+            ViolationAction::Kick => self.kick(format!("Protocol violation: {}", details)).await,
+        }
+    }
 
-    let _ = writer_sender.send(NetMessage::SendPacket(final_buffer)).await;
-    Ok(())
-}
+    pub async fn run(&mut self, registries: &Arc<PacketRegistries>) -> anyhow::Result<()> {
+        loop {
+            if !self.is_alive {
+                break;
+            }
 
-pub async fn send_raw_packet(writer_sender: &mpsc::Sender<NetMessage>, packet_id: i32, body: &[u8]) -> anyhow::Result<()> {
-    // Packet buffer, ID + Body
-    let mut payload_buffer = Vec::with_capacity(5 + body.len());
+            let frame = match self.read_frame().await? {
+                Some(frame) => frame,
+                None => break, // Disconnected
+            };
 
-    // Write Packet ID
-    payload_buffer.write_type(VarInt(packet_id));
+            if let Some(capture) = &self.capture {
+                capture.record(Direction::Inbound, self.protocol, self.phase, &frame);
+            }
 
-    // Write Raw Body
-    payload_buffer.extend_from_slice(body);
+            let mut reader = ByteReader::new(&frame);
 
-    let length = VarInt(payload_buffer.len() as i32);
-    let mut final_buffer = Vec::with_capacity(5 + payload_buffer.len());
+            let packet_id: VarInt = reader.read_field()?;
+            // The handshake can change the version, so the registry is picked for every packet
+            let registry = registries.get(self.protocol);
 
-    // Write Length Header
-    final_buffer.write_type(length);
+            let is_movement = registry.is_movement(self.phase, packet_id.0);
+            if !self.rate_limiter.allow(is_movement) {
+                self.rate_limited().await?;
+                continue;
+            }
 
-    // Append the payload
-    final_buffer.append(&mut payload_buffer);
+            registry.handle_packet(self.phase, packet_id.0, &mut reader, self).await?;
+        }
 
-    // Send to the Writer Task via the Channel
-    writer_sender
-        .send(NetMessage::SendPacket(final_buffer))
-        .await
-        .map_err(|_| anyhow::anyhow!("Writer task closed (Connection dropped)"))?;
+        Ok(())
+    }
 
-    Ok(())
-}
+    /// Applies the configured action to a packet over the rate limit, the packet itself is dropped
+    async fn rate_limited(&mut self) -> anyhow::Result<()> {
+        metrics::record(Rejection::PacketRateLimited);
 
-async fn handle_writes(mut stream: OwnedWriteHalf, mut rx: mpsc::Receiver<NetMessage>) {
-    while let Some(msg) = rx.recv().await {
-        match msg {
-            NetMessage::SendPacket(data) => {
-                if stream.write_all(&data).await.is_err() {
-                    break; // Client disconnected
-                }
+        match self.config.rate_limits.packet_action {
+            LimitAction::Drop => Ok(()),
+            LimitAction::Kick => self.kick("Sending packets too fast").await,
+            LimitAction::Ban => {
+                self.throttle.ban(self.address.ip(), Duration::from_secs(self.config.rate_limits.ban_secs));
+                self.kick("Temporarily banned for sending packets too fast").await
             }
-            NetMessage::Disconnect => {
-                let _ = stream.shutdown().await;
-                break;
+        }
+    }
+
+    /// Reads the next packet frame (ID + Data) from the socket, within the limits of the phase.
+    /// Invalid or oversized frames and clients that stall end the connection, returning None.
+    async fn read_frame(&mut self) -> anyhow::Result<Option<BytesMut>> {
+        loop {
+            match self.frames.next_frame(self.phase.max_frame_size()) {
+                Ok(Some(frame)) => return Ok(Some(frame)),
+                Ok(None) => {}
+                Err(FrameError::InvalidLength) => return self.reject_frame(Rejection::InvalidFrameLength, "Invalid packet length").await,
+                Err(FrameError::TooLarge) => return self.reject_frame(Rejection::OversizedFrame, "Packet too large").await,
+            }
+
+            // Wait for more bytes until the read timeout, or the login deadline if it comes first
+            let mut deadline = Instant::now() + Duration::from_secs(self.config.connection.read_timeout_secs);
+            let logging_in = self.phase.is_login() && self.login_deadline < deadline;
+            if logging_in {
+                deadline = self.login_deadline;
+            }
+
+            // The writer gives up on clients that stopped reading, there's no point reading from them either
+            let read = tokio::select! {
+                read = tokio::time::timeout_at(deadline, self.frames.fill()) => read,
+                _ = self.writer.closed() => return Ok(None),
+            };
+
+            match read {
+                Ok(Ok(0)) | Ok(Err(_)) => return Ok(None),
+                Ok(Ok(_)) => {}
+                Err(_) if logging_in => return self.reject_frame(Rejection::LoginTimeout, "Took too long to log in").await,
+                Err(_) => return self.reject_frame(Rejection::IdleTimeout, "Timed out").await,
             }
         }
     }
+
+    /// Counts the rejection and kicks the client, the socket can't be read any further
+    async fn reject_frame(&mut self, rejection: Rejection, reason: &str) -> anyhow::Result<Option<BytesMut>> {
+        metrics::record(rejection);
+        self.kick(reason).await?;
+        Ok(None)
+    }
+
+    /// Sends a packet with the id and layout it has in the current version and phase.
+    /// Fails without sending anything if the packet doesn't exist in this phase.
+    pub async fn send_packet<T: ClientboundPacket>(&mut self, packet: T) -> anyhow::Result<()> {
+        self.writer.send_packet(self.protocol, self.phase, packet)
+    }
+
+    /// Sends play packets the client applies together in the same tick
+    pub async fn send_bundle(&mut self, bundle: PacketBundle) -> anyhow::Result<()> {
+        self.writer.send_bundle(bundle)
+    }
+
+    /// Sends a payload on a custom channel, using the plugin message packet of the current phase
+    pub async fn send_plugin_message(&mut self, channel: Identifier, data: Vec<u8>) -> anyhow::Result<()> {
+        self.send_packet(PluginMessageResponsePacket { channel, data: RawBytes(data) }).await
+    }
 }
@@ -0,0 +1,28 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+
+/// Rotation angle in steps of 1/256 of a full turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Angle(pub u8);
+
+impl Angle {
+    /// Closest angle to a rotation in degrees, wrapping around any number of turns
+    pub fn from_degrees(degrees: f32) -> Self {
+        Angle((degrees * 256.0 / 360.0).round().rem_euclid(256.0) as u8)
+    }
+
+    pub fn to_degrees(self) -> f32 {
+        self.0 as f32 * 360.0 / 256.0
+    }
+}
+
+impl FieldRead for Angle {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(Angle(reader.read_byte()?))
+    }
+}
+
+impl PacketWrite for Angle {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.push(self.0);
+    }
+}
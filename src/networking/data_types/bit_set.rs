@@ -0,0 +1,110 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::var_int::VarInt;
+
+/// Bits packed into a length prefixed array of longs, bit `i` is bit `i % 64` of long `i / 64`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSet(pub Vec<u64>);
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.0.get(index / 64).is_some_and(|long| long & (1 << (index % 64)) != 0)
+    }
+
+    /// Sets a bit, growing the set if it's past the last long
+    pub fn set(&mut self, index: usize, value: bool) {
+        let long = index / 64;
+        if long >= self.0.len() {
+            if !value {
+                return;
+            }
+            self.0.resize(long + 1, 0);
+        }
+
+        if value {
+            self.0[long] |= 1 << (index % 64);
+        } else {
+            self.0[long] &= !(1 << (index % 64));
+        }
+    }
+}
+
+impl FieldRead for BitSet {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let count = i32::from(reader.read_field::<VarInt>()?);
+
+        // Checked before allocating, a long takes 8 bytes
+        if count < 0 || count as usize > reader.remaining() / 8 {
+            return Err(anyhow::anyhow!("BitSet length {} exceeds the {} bytes left", count, reader.remaining()));
+        }
+
+        let mut longs = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            longs.push(u64::from_be_bytes(reader.read_array()?));
+        }
+
+        Ok(BitSet(longs))
+    }
+}
+
+impl PacketWrite for BitSet {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        VarInt(self.0.len() as i32).write_to(buf);
+        for long in &self.0 {
+            buf.extend_from_slice(&long.to_be_bytes());
+        }
+    }
+}
+
+/// `N` bits sent as `ceil(N / 8)` bytes without a prefix, bit `i` is bit `i % 8` of byte `i / 8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedBitSet<const N: usize> {
+    bytes: Vec<u8>,
+}
+
+impl<const N: usize> FixedBitSet<N> {
+    pub const SIZE: usize = N.div_ceil(8);
+
+    pub fn new() -> Self {
+        Self { bytes: vec![0; Self::SIZE] }
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        index < N && self.bytes[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < N, "bit {} out of a fixed bit set of {}", index, N);
+
+        if value {
+            self.bytes[index / 8] |= 1 << (index % 8);
+        } else {
+            self.bytes[index / 8] &= !(1 << (index % 8));
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize> Default for FixedBitSet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> FieldRead for FixedBitSet<N> {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(Self { bytes: reader.read_bytes(Self::SIZE)?.to_vec() })
+    }
+}
+
+impl<const N: usize> PacketWrite for FixedBitSet<N> {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.bytes);
+    }
+}
@@ -1,22 +1,8 @@
-use std::io::Read;
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt};
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
-
-#[async_trait]
-impl PacketRead for bool {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 1];
-        stream.read_exact(&mut buf).await?;
-        Ok(buf[0] != 0)
-    }
-}
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
 
 impl FieldRead for bool {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 1];
-        stream.read_exact(&mut buf)?;
-        Ok(buf[0] != 0)
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(reader.read_byte()? != 0)
     }
 }
 
@@ -24,4 +10,4 @@ impl PacketWrite for bool {
     fn write_to(&self, buf: &mut Vec<u8>) {
         buf.push(if *self { 1 } else { 0 });
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,14 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+
+/// Byte array whose length is fixed by the protocol, so it's sent without a prefix (e.g. message signatures)
+impl<const N: usize> FieldRead for [u8; N] {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        reader.read_array()
+    }
+}
+
+impl<const N: usize> PacketWrite for [u8; N] {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::networking::data_types::FieldRead;
+
+/// Reads fields from a complete packet frame without copying it.
+/// Only the frame boundaries are read from the socket, everything else is decoded from memory.
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    buf: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+
+    pub fn read_field<T: FieldRead>(&mut self) -> anyhow::Result<T> {
+        T::read_from(self)
+    }
+
+    /// Borrows the next bytes of the frame
+    pub fn read_bytes(&mut self, length: usize) -> anyhow::Result<&'a [u8]> {
+        if length > self.remaining() {
+            return Err(anyhow::anyhow!("Unexpected end of packet, needed {} bytes but {} are left", length, self.remaining()));
+        }
+
+        let bytes = &self.buf[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn read_byte(&mut self) -> anyhow::Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    /// Borrows everything left in the frame
+    pub fn read_remaining(&mut self) -> &'a [u8] {
+        let bytes = &self.buf[self.position..];
+        self.position = self.buf.len();
+        bytes
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.position
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+}
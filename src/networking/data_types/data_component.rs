@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use crate::networking::data_types::{BoundedFieldRead, ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::nbt::Nbt;
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// Lines of lore an item can have, as vanilla limits it
+const MAX_LORE_LINES: usize = 256;
+
+/// Ids of the supported data component types in protocol 774 (1.21.11).
+/// Mirrors the `minecraft:data_component_type` registry of `reports/registries.json` from the
+/// vanilla data generator, regenerate it from there when the protocol changes.
+const DATA_COMPONENT_TYPES: &[(&str, i32)] = &[
+    ("minecraft:custom_data", 0),
+    ("minecraft:max_stack_size", 1),
+    ("minecraft:max_damage", 2),
+    ("minecraft:damage", 3),
+    ("minecraft:unbreakable", 4),
+    ("minecraft:custom_name", 6),
+    ("minecraft:item_name", 9),
+    ("minecraft:lore", 11),
+    ("minecraft:rarity", 12),
+    ("minecraft:enchantments", 13),
+    ("minecraft:repair_cost", 19),
+    ("minecraft:enchantment_glint_override", 21),
+    ("minecraft:stored_enchantments", 41),
+    ("minecraft:dyed_color", 42),
+];
+
+/// Differences of protocol 773 (1.21.9 and 1.21.10) from the table above,
+/// the components added by 1.21.11 (use_effects, damage_type, attack_range...) shift the ids after them
+const V1_21_9_CHANGES: &[(&str, i32)] = &[
+    ("minecraft:custom_name", 5),
+    ("minecraft:item_name", 6),
+    ("minecraft:lore", 8),
+    ("minecraft:rarity", 9),
+    ("minecraft:enchantments", 10),
+    ("minecraft:repair_cost", 16),
+    ("minecraft:enchantment_glint_override", 18),
+    ("minecraft:stored_enchantments", 34),
+    ("minecraft:dyed_color", 35),
+];
+
+fn version_changes(version: ProtocolVersion) -> &'static [(&'static str, i32)] {
+    match version {
+        ProtocolVersion::V1_21_9 => V1_21_9_CHANGES,
+        ProtocolVersion::V1_21_11 => &[],
+    }
+}
+
+static DATA_COMPONENT_IDS: LazyLock<HashMap<(ProtocolVersion, &'static str), i32>> = LazyLock::new(|| {
+    let mut ids = HashMap::new();
+
+    for version in ProtocolVersion::ALL {
+        for (name, id) in DATA_COMPONENT_TYPES.iter().chain(version_changes(version)) {
+            ids.insert((version, *name), *id);
+        }
+    }
+
+    ids
+});
+
+/// Id of a supported data component type in a version
+pub fn data_component_id(version: ProtocolVersion, name: &str) -> Option<i32> {
+    DATA_COMPONENT_IDS.get(&(version, name)).copied()
+}
+
+/// Name of a supported data component type id in a version
+pub fn data_component_name(version: ProtocolVersion, id: i32) -> Option<&'static str> {
+    DATA_COMPONENT_IDS.iter()
+        .find(|((v, _), component_id)| *v == version && **component_id == id)
+        .map(|((_, name), _)| *name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl FieldRead for Rarity {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        match reader.read_field::<VarInt>()?.0 {
+            0 => Ok(Rarity::Common),
+            1 => Ok(Rarity::Uncommon),
+            2 => Ok(Rarity::Rare),
+            3 => Ok(Rarity::Epic),
+            other => Err(anyhow::anyhow!("Invalid rarity: {}", other)),
+        }
+    }
+}
+
+impl PacketWrite for Rarity {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        VarInt(*self as i32).write_to(buf);
+    }
+}
+
+/// Enchantment of an item, by its id in the `minecraft:enchantment` registry sent during configuration
+#[derive(Debug, Clone, Copy, PartialEq, FieldRead, PacketWrite)]
+pub struct Enchantment {
+    pub id: VarInt,
+    pub level: VarInt,
+}
+
+/// Data component of an item stack. Only the components listed here can be decoded, the data of
+/// the others has no length prefix so they can't be skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataComponent {
+    /// Arbitrary NBT compound vanilla doesn't read, for plugins to tag items
+    CustomData(Nbt),
+    MaxStackSize(i32),
+    MaxDamage(i32),
+    Damage(i32),
+    Unbreakable,
+    CustomName(TextComponent),
+    ItemName(TextComponent),
+    Lore(Vec<TextComponent>),
+    Rarity(Rarity),
+    Enchantments(Vec<Enchantment>),
+    RepairCost(i32),
+    EnchantmentGlintOverride(bool),
+    /// Enchantments an enchanted book applies, rather than its own
+    StoredEnchantments(Vec<Enchantment>),
+    /// RGB color of leather armor and other dyeable items
+    DyedColor(i32),
+}
+
+impl DataComponent {
+    /// Vanilla name of the component type
+    pub fn name(&self) -> &'static str {
+        match self {
+            DataComponent::CustomData(_) => "minecraft:custom_data",
+            DataComponent::MaxStackSize(_) => "minecraft:max_stack_size",
+            DataComponent::MaxDamage(_) => "minecraft:max_damage",
+            DataComponent::Damage(_) => "minecraft:damage",
+            DataComponent::Unbreakable => "minecraft:unbreakable",
+            DataComponent::CustomName(_) => "minecraft:custom_name",
+            DataComponent::ItemName(_) => "minecraft:item_name",
+            DataComponent::Lore(_) => "minecraft:lore",
+            DataComponent::Rarity(_) => "minecraft:rarity",
+            DataComponent::Enchantments(_) => "minecraft:enchantments",
+            DataComponent::RepairCost(_) => "minecraft:repair_cost",
+            DataComponent::EnchantmentGlintOverride(_) => "minecraft:enchantment_glint_override",
+            DataComponent::StoredEnchantments(_) => "minecraft:stored_enchantments",
+            DataComponent::DyedColor(_) => "minecraft:dyed_color",
+        }
+    }
+
+    /// Decodes the data of a component type, the type id was already read
+    pub fn read_data(name: &str, reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(match name {
+            "minecraft:custom_data" => DataComponent::CustomData(reader.read_field()?),
+            "minecraft:max_stack_size" => DataComponent::MaxStackSize(reader.read_field::<VarInt>()?.0),
+            "minecraft:max_damage" => DataComponent::MaxDamage(reader.read_field::<VarInt>()?.0),
+            "minecraft:damage" => DataComponent::Damage(reader.read_field::<VarInt>()?.0),
+            "minecraft:unbreakable" => DataComponent::Unbreakable,
+            "minecraft:custom_name" => DataComponent::CustomName(reader.read_field()?),
+            "minecraft:item_name" => DataComponent::ItemName(reader.read_field()?),
+            "minecraft:lore" => DataComponent::Lore(Vec::read_bounded(reader, MAX_LORE_LINES)?),
+            "minecraft:rarity" => DataComponent::Rarity(reader.read_field()?),
+            "minecraft:enchantments" => DataComponent::Enchantments(reader.read_field()?),
+            "minecraft:repair_cost" => DataComponent::RepairCost(reader.read_field::<VarInt>()?.0),
+            "minecraft:enchantment_glint_override" => DataComponent::EnchantmentGlintOverride(reader.read_field()?),
+            "minecraft:stored_enchantments" => DataComponent::StoredEnchantments(reader.read_field()?),
+            "minecraft:dyed_color" => DataComponent::DyedColor(reader.read_field()?),
+            other => return Err(anyhow::anyhow!("Unsupported data component {}", other)),
+        })
+    }
+
+    /// Writes the data of the component, without its type id
+    pub fn write_data(&self, buf: &mut Vec<u8>) {
+        match self {
+            DataComponent::CustomData(nbt) => nbt.write_to(buf),
+            DataComponent::MaxStackSize(value)
+            | DataComponent::MaxDamage(value)
+            | DataComponent::Damage(value)
+            | DataComponent::RepairCost(value) => VarInt(*value).write_to(buf),
+            DataComponent::Unbreakable => {}
+            DataComponent::CustomName(text) | DataComponent::ItemName(text) => text.write_to(buf),
+            DataComponent::Lore(lines) => lines.write_to(buf),
+            DataComponent::Rarity(rarity) => rarity.write_to(buf),
+            DataComponent::Enchantments(enchantments)
+            | DataComponent::StoredEnchantments(enchantments) => enchantments.write_to(buf),
+            DataComponent::EnchantmentGlintOverride(value) => value.write_to(buf),
+            DataComponent::DyedColor(color) => color.write_to(buf),
+        }
+    }
+}
@@ -1,22 +1,8 @@
-use std::io::Read;
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt};
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
-
-#[async_trait]
-impl PacketRead for f64 {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 8];
-        stream.read_exact(&mut buf).await?;
-        Ok(f64::from_be_bytes(buf))
-    }
-}
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
 
 impl FieldRead for f64 {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 8];
-        stream.read_exact(&mut buf)?;
-        Ok(f64::from_be_bytes(buf))
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(f64::from_be_bytes(reader.read_array()?))
     }
 }
 
@@ -24,4 +10,4 @@ impl PacketWrite for f64 {
     fn write_to(&self, buf: &mut Vec<u8>) {
         buf.extend_from_slice(&self.to_be_bytes());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,84 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::var_int::VarInt;
+
+/// Registry entry sent either by id or inline, as "ID or X" fields do.
+/// The VarInt is 0 before an inline value, otherwise the registry id + 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdOr<T> {
+    Id(i32),
+    Inline(T),
+}
+
+impl<T: FieldRead> FieldRead for IdOr<T> {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        match reader.read_field::<VarInt>()?.0 {
+            0 => Ok(IdOr::Inline(T::read_from(reader)?)),
+            id if id > 0 => Ok(IdOr::Id(id - 1)),
+            // The shifted value, id - 1 overflows for i32::MIN
+            id => Err(anyhow::anyhow!("Invalid ID or X value {}", id)),
+        }
+    }
+}
+
+impl<T: PacketWrite> PacketWrite for IdOr<T> {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            IdOr::Id(id) => {
+                debug_assert!((0..i32::MAX).contains(id), "registry id {} can't be sent shifted by one", id);
+                VarInt(id.wrapping_add(1)).write_to(buf)
+            }
+            IdOr::Inline(value) => {
+                VarInt(0).write_to(buf);
+                value.write_to(buf);
+            }
+        }
+    }
+}
+
+/// Set of registry entries, given by a tag or as a list of ids.
+/// The VarInt is 0 before a tag name, otherwise the number of ids + 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdSet {
+    Tag(Identifier),
+    Ids(Vec<VarInt>),
+}
+
+impl FieldRead for IdSet {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let count = match reader.read_field::<VarInt>()?.0 {
+            0 => return Ok(IdSet::Tag(reader.read_field()?)),
+            kind if kind > 0 => (kind - 1) as usize,
+            kind => return Err(anyhow::anyhow!("Invalid ID Set type {}", kind)),
+        };
+
+        // Checked before allocating, an id takes a byte at least
+        if count > reader.remaining() {
+            return Err(anyhow::anyhow!("ID Set length {} exceeds the {} bytes left", count, reader.remaining()));
+        }
+
+        let mut ids = Vec::with_capacity(count);
+        for _ in 0..count {
+            ids.push(reader.read_field()?);
+        }
+
+        Ok(IdSet::Ids(ids))
+    }
+}
+
+impl PacketWrite for IdSet {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            IdSet::Tag(tag) => {
+                VarInt(0).write_to(buf);
+                tag.write_to(buf);
+            }
+            IdSet::Ids(ids) => {
+                VarInt(ids.len() as i32 + 1).write_to(buf);
+                for id in ids {
+                    id.write_to(buf);
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,7 @@
 use std::fmt;
-use std::io::Read;
 use std::str::FromStr;
 use anyhow::anyhow;
-use async_trait::async_trait;
-use tokio::io::AsyncRead;
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Identifier {
@@ -77,17 +74,9 @@ impl PacketWrite for Identifier {
 }
 
 // Reading: Read as String, then Parse
-#[async_trait]
-impl PacketRead for Identifier {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
-        let s = <String as PacketRead>::read_from(stream).await?;
-        Identifier::from_str(&s)
-    }
-}
-
 impl FieldRead for Identifier {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        let s = <String as FieldRead>::read_from(stream)?;
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let s = reader.read_field::<String>()?;
         Identifier::from_str(&s)
     }
 }
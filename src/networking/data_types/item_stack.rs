@@ -0,0 +1,146 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::data_component::{data_component_id, data_component_name, DataComponent};
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// Changes of an item stack from the default components of its item
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataComponentPatch {
+    pub added: Vec<DataComponent>,
+    /// Names of the default components the stack doesn't have
+    pub removed: Vec<&'static str>,
+}
+
+/// Item stack as sent in a Slot: item id, count and component patch. A count of 0 is an empty slot,
+/// which carries nothing else. FieldRead and PacketWrite use the component ids of the latest version,
+/// packets sent to other versions translate with [`ItemStack::write_for`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemStack {
+    /// Id in the `minecraft:item` registry
+    pub item_id: i32,
+    pub count: i32,
+    pub components: DataComponentPatch,
+}
+
+impl ItemStack {
+    pub const EMPTY: ItemStack = ItemStack { item_id: 0, count: 0, components: DataComponentPatch { added: Vec::new(), removed: Vec::new() } };
+
+    pub fn new(item_id: i32, count: i32) -> Self {
+        Self { item_id, count, components: DataComponentPatch::default() }
+    }
+
+    pub fn with(mut self, component: DataComponent) -> Self {
+        self.components.added.push(component);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count <= 0
+    }
+
+    pub fn read_for(version: ProtocolVersion, reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let count = reader.read_field::<VarInt>()?.0;
+        if count <= 0 {
+            return Ok(ItemStack::EMPTY);
+        }
+
+        let item_id = reader.read_field::<VarInt>()?.0;
+        let added_count = read_count(reader)?;
+        let removed_count = read_count(reader)?;
+
+        let mut added = Vec::with_capacity(added_count);
+        for _ in 0..added_count {
+            let name = read_component_type(version, reader)?;
+            added.push(DataComponent::read_data(name, reader)?);
+        }
+
+        let mut removed = Vec::with_capacity(removed_count);
+        for _ in 0..removed_count {
+            removed.push(read_component_type(version, reader)?);
+        }
+
+        Ok(ItemStack { item_id, count, components: DataComponentPatch { added, removed } })
+    }
+
+    /// Fails if a removed component has no id in the version
+    pub fn write_for(&self, version: ProtocolVersion, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        if self.is_empty() {
+            VarInt(0).write_to(buf);
+            return Ok(());
+        }
+
+        VarInt(self.count).write_to(buf);
+        VarInt(self.item_id).write_to(buf);
+        VarInt(self.components.added.len() as i32).write_to(buf);
+        VarInt(self.components.removed.len() as i32).write_to(buf);
+
+        for component in &self.components.added {
+            write_component_type(version, component.name(), buf)?;
+            component.write_data(buf);
+        }
+
+        for name in &self.components.removed {
+            write_component_type(version, name, buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of components in a patch, rejected before allocating if the bytes left can't hold them
+fn read_count(reader: &mut ByteReader<'_>) -> anyhow::Result<usize> {
+    let count = reader.read_field::<VarInt>()?.0;
+    if count < 0 || count as usize > reader.remaining() {
+        return Err(anyhow::anyhow!("Component count {} exceeds the {} bytes left", count, reader.remaining()));
+    }
+    Ok(count as usize)
+}
+
+fn read_component_type(version: ProtocolVersion, reader: &mut ByteReader<'_>) -> anyhow::Result<&'static str> {
+    let id = reader.read_field::<VarInt>()?.0;
+    data_component_name(version, id).ok_or_else(|| anyhow::anyhow!("Unsupported data component id {}", id))
+}
+
+fn write_component_type(version: ProtocolVersion, name: &str, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+    let id = data_component_id(version, name)
+        .ok_or_else(|| anyhow::anyhow!("No id for data component {} in {}", name, version))?;
+    VarInt(id).write_to(buf);
+    Ok(())
+}
+
+impl FieldRead for ItemStack {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        ItemStack::read_for(ProtocolVersion::LATEST, reader)
+    }
+}
+
+/// Can't fail, so a stack the latest version can't represent is written as an empty slot.
+/// Packets carrying items write them with [`ItemStack::write_for`], which fails instead.
+impl PacketWrite for ItemStack {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        let start = buf.len();
+        if let Err(e) = self.write_for(ProtocolVersion::LATEST, buf) {
+            eprintln!("Writing an empty slot instead of {:?}: {}", self, e);
+            buf.truncate(start);
+            VarInt(0).write_to(buf);
+        }
+    }
+}
+
+/// Component of a [`HashedItemStack`], with the CRC32C hash of its data instead of the data
+#[derive(Debug, Clone, Copy, PartialEq, FieldRead, PacketWrite)]
+pub struct HashedComponent {
+    pub component_type: VarInt,
+    pub hash: i32,
+}
+
+/// Item stack the client believes a slot holds, sent in container clicks. The components are hashed
+/// so clients don't send whole stacks back, a mismatch with the server's stack makes it resync the slot.
+/// A Hashed Slot is an `Option<HashedItemStack>`, with the same boolean prefix.
+#[derive(Debug, Clone, PartialEq, FieldRead, PacketWrite)]
+pub struct HashedItemStack {
+    pub item_id: VarInt,
+    pub count: VarInt,
+    pub added: Vec<HashedComponent>,
+    pub removed: Vec<VarInt>,
+}
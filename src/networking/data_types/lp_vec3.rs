@@ -0,0 +1,90 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::var_int::VarInt;
+
+/// Low precision vector used for entity velocities since 1.21.9.
+/// Each component is scaled into 15 bits, the three of them and a scale fit in 6 bytes:
+/// 2 scale bits, a flag for the rest of the scale in a trailing VarInt, then x, y and z.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LpVec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl LpVec3 {
+    /// Vectors whose largest component is under this are sent as a single 0 byte
+    const ABS_MIN: f64 = 3.051944088384301E-5;
+    const ABS_MAX: f64 = 1.7179869183E10;
+    const MAX_QUANTIZED: f64 = 32766.0;
+
+    pub const ZERO: LpVec3 = LpVec3 { x: 0.0, y: 0.0, z: 0.0 };
+
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    fn sanitize(value: f64) -> f64 {
+        if value.is_nan() { 0.0 } else { value.clamp(-Self::ABS_MAX, Self::ABS_MAX) }
+    }
+
+    /// Maps [-1, 1] to [0, 32766]
+    fn pack(value: f64) -> u64 {
+        ((value * 0.5 + 0.5) * Self::MAX_QUANTIZED).round() as u64
+    }
+
+    fn unpack(value: u64) -> f64 {
+        ((value & 0x7FFF) as f64).min(Self::MAX_QUANTIZED) * 2.0 / Self::MAX_QUANTIZED - 1.0
+    }
+}
+
+impl FieldRead for LpVec3 {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let first = reader.read_byte()?;
+        if first == 0 {
+            return Ok(LpVec3::ZERO);
+        }
+
+        let second = reader.read_byte()?;
+        let rest = u32::from_be_bytes(reader.read_array()?);
+        let packed = (rest as u64) << 16 | (second as u64) << 8 | first as u64;
+
+        let mut scale = (first & 3) as u64;
+        if first & 4 != 0 {
+            scale |= (reader.read_field::<VarInt>()?.0 as u32 as u64) << 2;
+        }
+        let scale = scale as f64;
+
+        Ok(LpVec3 {
+            x: Self::unpack(packed >> 3) * scale,
+            y: Self::unpack(packed >> 18) * scale,
+            z: Self::unpack(packed >> 33) * scale,
+        })
+    }
+}
+
+impl PacketWrite for LpVec3 {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        let (x, y, z) = (Self::sanitize(self.x), Self::sanitize(self.y), Self::sanitize(self.z));
+        let max = x.abs().max(y.abs()).max(z.abs());
+        if max < Self::ABS_MIN {
+            buf.push(0);
+            return;
+        }
+
+        let scale = max.ceil() as u64;
+        let extended = scale & 3 != scale;
+        let markers = if extended { scale & 3 | 4 } else { scale };
+        let scale_f = scale as f64;
+        let packed = markers
+            | Self::pack(x / scale_f) << 3
+            | Self::pack(y / scale_f) << 18
+            | Self::pack(z / scale_f) << 33;
+
+        buf.push(packed as u8);
+        buf.push((packed >> 8) as u8);
+        buf.extend_from_slice(&((packed >> 16) as u32).to_be_bytes());
+        if extended {
+            VarInt((scale >> 2) as i32).write_to(buf);
+        }
+    }
+}
@@ -1,50 +1,50 @@
-pub(crate) mod var_int;
+pub mod var_int;
 mod boolean;
 mod u_short;
 mod i_integer;
 mod string;
-pub(crate) mod u_byte;
+pub mod u_byte;
 mod i_short;
 mod i_long;
 mod float;
 mod double;
 mod uuid;
 mod prefixed_array;
-pub(crate) mod game_profile;
-pub(crate) mod known_pack;
-pub(crate) mod identifier;
-pub(crate) mod raw_bytes;
-pub(crate) mod i_byte;
-pub(crate) mod registries;
-pub(crate) mod position;
-
-use std::io::Read;
-use tokio::io::{AsyncRead};
-use async_trait::async_trait;
+pub mod game_profile;
+pub mod known_pack;
+pub mod identifier;
+pub mod raw_bytes;
+pub mod i_byte;
+pub mod registries;
+pub mod position;
+mod byte_reader;
+pub mod text_component;
+mod byte_array;
+pub mod var_long;
+pub mod angle;
+pub mod bit_set;
+pub mod id_or;
+pub mod sound_event;
+pub mod lp_vec3;
+pub mod teleport_flags;
+pub mod nbt;
+pub mod data_component;
+pub mod item_stack;
+
 use anyhow::Result;
-use tokio::io::AsyncWriteExt;
+
+pub use byte_reader::ByteReader;
+pub use nullspace_macros::{FieldRead, PacketWrite};
 
 // Encoding & Decoding
 
-#[async_trait]
 pub trait PacketWrite {
     fn write_to(&self, buf: &mut Vec<u8>);
 }
 
-#[async_trait]
-pub trait PacketRead: Sized {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> Result<Self>;
-}
-
-// Extending reading
-pub trait StreamExt {
-    async fn read_type<T: PacketRead>(&mut self) -> anyhow::Result<T>;
-}
-
-impl<R: AsyncRead + Unpin + Send> StreamExt for R {
-    async fn read_type<T: PacketRead>(&mut self) -> anyhow::Result<T> {
-        T::read_from(self).await
-    }
+/// Decodes a field from a packet frame, which is already fully in memory
+pub trait FieldRead: Sized {
+    fn read_from(reader: &mut ByteReader<'_>) -> Result<Self>;
 }
 
 // Extending writing
@@ -60,22 +60,6 @@ impl BufferWrite for Vec<u8> {
     }
 }
 
-#[async_trait::async_trait]
-pub trait StreamWrite {
-    async fn write_stream_type<T: PacketWrite + Send + Sync>(&mut self, value: T) -> anyhow::Result<()>;
-}
-
-#[async_trait::async_trait]
-impl<W: tokio::io::AsyncWrite + Unpin + Send> StreamWrite for W {
-    async fn write_stream_type<T: PacketWrite + Send + Sync>(&mut self, value: T) -> anyhow::Result<()> {
-        let mut buf = Vec::new();
-        value.write_to(&mut buf);
-
-        self.write_all(&buf).await?;
-        Ok(())
-    }
-}
-
 // Optional signatures
 
 impl<T: PacketWrite> PacketWrite for Option<T> {
@@ -92,12 +76,11 @@ impl<T: PacketWrite> PacketWrite for Option<T> {
     }
 }
 
-#[async_trait]
-impl<T: PacketRead + Send> PacketRead for Option<T> {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> Result<Self> {
-        let has_value = <bool as PacketRead>::read_from(stream).await?;
+impl<T: FieldRead> FieldRead for Option<T> {
+    fn read_from(reader: &mut ByteReader<'_>) -> Result<Self> {
+        let has_value = reader.read_field::<bool>()?;
         if has_value {
-            let val = T::read_from(stream).await?;
+            let val = T::read_from(reader)?;
             Ok(Some(val))
         } else {
             Ok(None)
@@ -105,11 +88,11 @@ impl<T: PacketRead + Send> PacketRead for Option<T> {
     }
 }
 
-impl<T: FieldRead> FieldRead for Option<T> {
-    fn read_from<R: Read>(stream: &mut R) -> Result<Self> {
-        let has_value = <bool as FieldRead>::read_from(stream)?;
+impl<T: BoundedFieldRead> BoundedFieldRead for Option<T> {
+    fn read_bounded(reader: &mut ByteReader<'_>, max_length: usize) -> Result<Self> {
+        let has_value = reader.read_field::<bool>()?;
         if has_value {
-            let val = T::read_from(stream)?;
+            let val = T::read_bounded(reader, max_length)?;
             Ok(Some(val))
         } else {
             Ok(None)
@@ -117,19 +100,22 @@ impl<T: FieldRead> FieldRead for Option<T> {
     }
 }
 
-// 1. Define a Sync version of PacketRead
-pub trait FieldRead: Sized {
-    fn read_from<R: Read>(reader: &mut R) -> anyhow::Result<Self>;
-}
+// Length limited reading, used by #[field(max_length = N)]
 
-// 2. Define a Sync Extension (like your StreamExt)
-pub trait BufferReadExt {
-    fn read_field<T: FieldRead>(&mut self) -> anyhow::Result<T>;
+pub trait BoundedFieldRead: Sized {
+    fn read_bounded(reader: &mut ByteReader<'_>, max_length: usize) -> Result<Self>;
 }
 
-// 3. Implement the extension for any Sync Reader (like Cursor)
-impl<R: Read> BufferReadExt for R {
-    fn read_field<T: FieldRead>(&mut self) -> anyhow::Result<T> {
-        T::read_from(self)
+/// Items reserved up front when reading an array. Only the bytes left bound the length,
+/// which is far too many for big items, so longer arrays grow as they are read.
+pub(crate) const MAX_PREALLOCATED_ITEMS: usize = 256;
+
+/// Reads an array without length prefix, the length was sent in an earlier field
+pub fn read_array<T: FieldRead>(reader: &mut ByteReader<'_>, length: usize) -> Result<Vec<T>> {
+    let mut items = Vec::with_capacity(length.min(MAX_PREALLOCATED_ITEMS));
+    for _ in 0..length {
+        items.push(T::read_from(reader)?);
     }
-}
\ No newline at end of file
+
+    Ok(items)
+}
@@ -0,0 +1,255 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+/// Nesting of lists and compounds allowed when reading, the same as vanilla's
+const MAX_DEPTH: usize = 512;
+
+/// NBT tag in the network format: the root is a type byte and a payload, without a name.
+/// Compounds keep their entries in order, so a decoded tag is written back byte for byte.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Nbt {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    /// Every element has the same type, an empty list is written with the End type
+    List(Vec<Nbt>),
+    Compound(Vec<(String, Nbt)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Nbt {
+    /// Entry of a compound, None for other tags
+    pub fn get(&self, key: &str) -> Option<&Nbt> {
+        match self {
+            Nbt::Compound(entries) => entries.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn tag_type(&self) -> u8 {
+        match self {
+            Nbt::Byte(_) => TAG_BYTE,
+            Nbt::Short(_) => TAG_SHORT,
+            Nbt::Int(_) => TAG_INT,
+            Nbt::Long(_) => TAG_LONG,
+            Nbt::Float(_) => TAG_FLOAT,
+            Nbt::Double(_) => TAG_DOUBLE,
+            Nbt::ByteArray(_) => TAG_BYTE_ARRAY,
+            Nbt::String(_) => TAG_STRING,
+            Nbt::List(_) => TAG_LIST,
+            Nbt::Compound(_) => TAG_COMPOUND,
+            Nbt::IntArray(_) => TAG_INT_ARRAY,
+            Nbt::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    fn write_payload(&self, buf: &mut Vec<u8>) {
+        match self {
+            Nbt::Byte(value) => buf.push(*value as u8),
+            Nbt::Short(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Nbt::Int(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Nbt::Long(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Nbt::Float(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Nbt::Double(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            Nbt::ByteArray(values) => {
+                buf.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                buf.extend(values.iter().map(|value| *value as u8));
+            }
+            Nbt::String(value) => write_string(value, buf),
+            Nbt::List(values) => {
+                buf.push(values.first().map_or(TAG_END, Nbt::tag_type));
+                buf.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for value in values {
+                    value.write_payload(buf);
+                }
+            }
+            Nbt::Compound(entries) => {
+                for (name, value) in entries {
+                    buf.push(value.tag_type());
+                    write_string(name, buf);
+                    value.write_payload(buf);
+                }
+                buf.push(TAG_END);
+            }
+            Nbt::IntArray(values) => {
+                buf.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for value in values {
+                    buf.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+            Nbt::LongArray(values) => {
+                buf.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for value in values {
+                    buf.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    fn read_payload(tag_type: u8, reader: &mut ByteReader<'_>, depth: usize) -> anyhow::Result<Nbt> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow::anyhow!("NBT is nested deeper than {}", MAX_DEPTH));
+        }
+
+        Ok(match tag_type {
+            TAG_BYTE => Nbt::Byte(reader.read_byte()? as i8),
+            TAG_SHORT => Nbt::Short(i16::from_be_bytes(reader.read_array()?)),
+            TAG_INT => Nbt::Int(i32::from_be_bytes(reader.read_array()?)),
+            TAG_LONG => Nbt::Long(i64::from_be_bytes(reader.read_array()?)),
+            TAG_FLOAT => Nbt::Float(f32::from_be_bytes(reader.read_array()?)),
+            TAG_DOUBLE => Nbt::Double(f64::from_be_bytes(reader.read_array()?)),
+            TAG_BYTE_ARRAY => {
+                let length = read_length(reader, 1)?;
+                Nbt::ByteArray(reader.read_bytes(length)?.iter().map(|byte| *byte as i8).collect())
+            }
+            TAG_STRING => Nbt::String(read_string(reader)?),
+            TAG_LIST => {
+                let element_type = reader.read_byte()?;
+                let length = read_length(reader, 1)?;
+                if element_type == TAG_END && length > 0 {
+                    return Err(anyhow::anyhow!("NBT list of {} elements has no element type", length));
+                }
+
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    values.push(Nbt::read_payload(element_type, reader, depth + 1)?);
+                }
+                Nbt::List(values)
+            }
+            TAG_COMPOUND => {
+                let mut entries = Vec::new();
+                loop {
+                    let entry_type = reader.read_byte()?;
+                    if entry_type == TAG_END {
+                        break;
+                    }
+                    let name = read_string(reader)?;
+                    entries.push((name, Nbt::read_payload(entry_type, reader, depth + 1)?));
+                }
+                Nbt::Compound(entries)
+            }
+            TAG_INT_ARRAY => {
+                let length = read_length(reader, 4)?;
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    values.push(i32::from_be_bytes(reader.read_array()?));
+                }
+                Nbt::IntArray(values)
+            }
+            TAG_LONG_ARRAY => {
+                let length = read_length(reader, 8)?;
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    values.push(i64::from_be_bytes(reader.read_array()?));
+                }
+                Nbt::LongArray(values)
+            }
+            other => return Err(anyhow::anyhow!("Invalid NBT tag type {}", other)),
+        })
+    }
+}
+
+impl From<&str> for Nbt {
+    fn from(value: &str) -> Self {
+        Nbt::String(value.to_string())
+    }
+}
+
+/// Length of an array or list, rejected before allocating if the bytes left can't hold it
+fn read_length(reader: &mut ByteReader<'_>, element_size: usize) -> anyhow::Result<usize> {
+    let length = i32::from_be_bytes(reader.read_array()?);
+    if length < 0 || length as usize > reader.remaining() / element_size {
+        return Err(anyhow::anyhow!("NBT length {} exceeds the {} bytes left", length, reader.remaining()));
+    }
+    Ok(length as usize)
+}
+
+/// NBT strings use Java's "modified UTF-8": null is written as two bytes and
+/// characters outside the BMP are written as a surrogate pair of 3 bytes each.
+/// The length is a u16, longer strings are cut after the last character that fits.
+fn write_string(text: &str, buf: &mut Vec<u8>) {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut units = [0; 2];
+
+    for character in text.chars() {
+        let start = bytes.len();
+        for unit in character.encode_utf16(&mut units) {
+            match *unit {
+                0x0001..=0x007F => bytes.push(*unit as u8),
+                0x0000 | 0x0080..=0x07FF => {
+                    bytes.push(0xC0 | (*unit >> 6) as u8);
+                    bytes.push(0x80 | (*unit & 0x3F) as u8);
+                }
+                _ => {
+                    bytes.push(0xE0 | (*unit >> 12) as u8);
+                    bytes.push(0x80 | ((*unit >> 6) & 0x3F) as u8);
+                    bytes.push(0x80 | (*unit & 0x3F) as u8);
+                }
+            }
+        }
+
+        if bytes.len() > u16::MAX as usize {
+            bytes.truncate(start);
+            break;
+        }
+    }
+
+    buf.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&bytes);
+}
+
+fn read_string(reader: &mut ByteReader<'_>) -> anyhow::Result<String> {
+    let length = u16::from_be_bytes(reader.read_array()?) as usize;
+    let bytes = reader.read_bytes(length)?;
+
+    let mut units = Vec::with_capacity(length);
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index] as u16;
+        let (unit, size) = match byte {
+            0x01..=0x7F => (byte, 1),
+            0xC0..=0xDF if index + 1 < bytes.len() => ((byte & 0x1F) << 6 | (bytes[index + 1] as u16 & 0x3F), 2),
+            0xE0..=0xEF if index + 2 < bytes.len() => {
+                ((byte & 0x0F) << 12 | (bytes[index + 1] as u16 & 0x3F) << 6 | (bytes[index + 2] as u16 & 0x3F), 3)
+            }
+            _ => return Err(anyhow::anyhow!("Invalid modified UTF-8 in NBT string")),
+        };
+        units.push(unit);
+        index += size;
+    }
+
+    String::from_utf16(&units).map_err(|_| anyhow::anyhow!("Unpaired surrogate in NBT string"))
+}
+
+impl FieldRead for Nbt {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let tag_type = reader.read_byte()?;
+        Nbt::read_payload(tag_type, reader, 0)
+    }
+}
+
+impl PacketWrite for Nbt {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.push(self.tag_type());
+        self.write_payload(buf);
+    }
+}
@@ -1,7 +1,4 @@
-use std::io::Read;
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt};
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
@@ -17,7 +14,7 @@ impl Position {
 
     /// Encodes the Position into the Minecraft 1.14+ i64 format.
     /// Format: ((x & 0x3FFFFFF) << 38) | ((z & 0x3FFFFFF) << 12) | (y & 0xFFF)
-    fn to_u64(&self) -> u64 {
+    fn to_u64(self) -> u64 {
         let x = self.x as i64;
         let z = self.z as i64;
         let y = self.y as i64;
@@ -52,22 +49,10 @@ impl Position {
     }
 }
 
-#[async_trait]
-impl PacketRead for Position {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
-        // Position is sent as a Long (8 bytes)
-        let mut buf = [0u8; 8];
-        stream.read_exact(&mut buf).await?;
-        let val = u64::from_be_bytes(buf);
-        Ok(Position::from_u64(val))
-    }
-}
-
 impl FieldRead for Position {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 8];
-        stream.read_exact(&mut buf)?;
-        let val = u64::from_be_bytes(buf);
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        // Position is sent as a Long (8 bytes)
+        let val = u64::from_be_bytes(reader.read_array()?);
         Ok(Position::from_u64(val))
     }
 }
@@ -1,8 +1,24 @@
-use std::io::Read;
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
-use async_trait::async_trait;
-use tokio::io::AsyncRead;
+use crate::networking::data_types::{BoundedFieldRead, ByteReader, FieldRead, PacketWrite, MAX_PREALLOCATED_ITEMS};
 use crate::networking::data_types::var_int::VarInt;
+use crate::networking::metrics::{self, Rejection};
+
+/// Reads the length prefix of an array, rejecting it before anything is allocated if it's
+/// negative, above `max_length`, or more items than the bytes left could hold (one byte at least each)
+fn read_length(reader: &mut ByteReader<'_>, max_length: usize) -> anyhow::Result<usize> {
+    let count = i32::from(reader.read_field::<VarInt>()?);
+
+    if count < 0 || count as usize > max_length {
+        metrics::record(Rejection::OversizedArray);
+        return Err(anyhow::anyhow!("Array length {} out of bounds (max {})", count, max_length));
+    }
+
+    if count as usize > reader.remaining() {
+        metrics::record(Rejection::OversizedArray);
+        return Err(anyhow::anyhow!("Array length {} exceeds the {} bytes left", count, reader.remaining()));
+    }
+
+    Ok(count as usize)
+}
 
 // WRITING: Length (VarInt) + Items
 impl<T: PacketWrite> PacketWrite for Vec<T> {
@@ -19,35 +35,30 @@ impl<T: PacketWrite> PacketWrite for Vec<T> {
 }
 
 // READING: Length (VarInt) + Items
-#[async_trait]
-impl<T: PacketRead + Send> PacketRead for Vec<T> {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
+impl<T: FieldRead> FieldRead for Vec<T> {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
         // Read the length
-        let len = <VarInt as PacketRead>::read_from(stream).await?;
-        let count = i32::from(len);
+        let count = read_length(reader, i32::MAX as usize)?;
 
-        // Read items
-        let mut items = Vec::with_capacity(count as usize);
+        // Read items, a large count only grows the Vec as far as the items are really there
+        let mut items = Vec::with_capacity(count.min(MAX_PREALLOCATED_ITEMS));
         for _ in 0..count {
-            items.push(T::read_from(stream).await?);
+            items.push(T::read_from(reader)?);
         }
 
         Ok(items)
     }
 }
 
-impl<T: FieldRead> FieldRead for Vec<T> {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        // Read the length
-        let len = <VarInt as FieldRead>::read_from(stream)?;
-        let count = i32::from(len);
+impl<T: FieldRead> BoundedFieldRead for Vec<T> {
+    fn read_bounded(reader: &mut ByteReader<'_>, max_length: usize) -> anyhow::Result<Self> {
+        let count = read_length(reader, max_length)?;
 
-        // Read items
-        let mut items = Vec::with_capacity(count as usize);
+        let mut items = Vec::with_capacity(count.min(MAX_PREALLOCATED_ITEMS));
         for _ in 0..count {
-            items.push(T::read_from(stream)?);
+            items.push(T::read_from(reader)?);
         }
 
         Ok(items)
     }
-}
\ No newline at end of file
+}
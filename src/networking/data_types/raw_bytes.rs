@@ -1,28 +1,26 @@
-use std::io::Read;
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt};
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
-
-/// Represents raw binary data that consumes the stream until EOF.
-/// WARNING: Only use this on a bounded stream (like a Cursor over a fixed buffer).
-/// If we use this on a TcpStream, it will hang forever waiting for the connection to close.
-#[derive(Debug, Clone)]
+use std::fmt;
+use crate::networking::data_types::{BoundedFieldRead, ByteReader, FieldRead, PacketWrite};
+
+/// Bytes printed at most by [`hex`]
+const HEX_PREVIEW: usize = 64;
+
+/// Represents raw binary data that consumes the rest of the packet frame.
+#[derive(Clone)]
 pub struct RawBytes(pub Vec<u8>);
 
-#[async_trait]
-impl PacketRead for RawBytes {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = Vec::new();
-        stream.read_to_end(&mut buf).await?;
-        Ok(RawBytes(buf))
+impl FieldRead for RawBytes {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(RawBytes(reader.read_remaining().to_vec()))
     }
 }
 
-impl FieldRead for RawBytes {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = Vec::new();
-        stream.read_to_end(&mut buf)?;
-        Ok(RawBytes(buf))
+impl BoundedFieldRead for RawBytes {
+    fn read_bounded(reader: &mut ByteReader<'_>, max_length: usize) -> anyhow::Result<Self> {
+        if reader.remaining() > max_length {
+            return Err(anyhow::anyhow!("{} bytes left, more than the max of {}", reader.remaining(), max_length));
+        }
+
+        Self::read_from(reader)
     }
 }
 
@@ -30,4 +28,22 @@ impl PacketWrite for RawBytes {
     fn write_to(&self, buf: &mut Vec<u8>) {
         buf.extend_from_slice(&self.0);
     }
-}
\ No newline at end of file
+}
+
+/// Hex instead of a list of numbers, registry payloads are tens of kilobytes
+impl fmt::Debug for RawBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RawBytes({})", hex(&self.0))
+    }
+}
+
+/// Bytes as hex, cut after the first few
+pub fn hex(bytes: &[u8]) -> String {
+    let preview: Vec<String> = bytes.iter().take(HEX_PREVIEW).map(|byte| format!("{:02x}", byte)).collect();
+
+    if bytes.len() > HEX_PREVIEW {
+        format!("[{} ... {} bytes]", preview.join(" "), bytes.len())
+    } else {
+        format!("[{}]", preview.join(" "))
+    }
+}
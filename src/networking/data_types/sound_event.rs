@@ -0,0 +1,10 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::identifier::Identifier;
+
+/// Sound sent inline instead of by its registry id, usually in an [`IdOr`](crate::networking::data_types::id_or::IdOr)
+#[derive(Debug, Clone, PartialEq, FieldRead, PacketWrite)]
+pub struct SoundEvent {
+    pub sound_name: Identifier,
+    /// Fixed distance the sound is heard at, None for a range that depends on the volume
+    pub fixed_range: Option<f32>,
+}
@@ -1,39 +1,36 @@
-use std::io::Read;
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt};
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
+use crate::networking::data_types::{BoundedFieldRead, ByteReader, FieldRead, PacketWrite};
 use crate::networking::data_types::var_int::VarInt;
 
-#[async_trait]
-impl PacketRead for String {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
+impl FieldRead for String {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
         // We reuse the VarInt logic to read the length!
-        let len = <VarInt as PacketRead>::read_from(stream).await?.0;
+        let len = reader.read_field::<VarInt>()?.0;
 
-        if len > 32767 || len < 0 {
+        if !(0..=32767).contains(&len) {
             return Err(anyhow::anyhow!("String length invalid"));
         }
 
-        let mut buf = vec![0u8; len as usize];
-        stream.read_exact(&mut buf).await?;
-
-        String::from_utf8(buf).map_err(|_| anyhow::anyhow!("Invalid UTF-8"))
+        let bytes = reader.read_bytes(len as usize)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| anyhow::anyhow!("Invalid UTF-8"))
     }
 }
 
-impl FieldRead for String {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        // We reuse the VarInt logic to read the length!
-        let len = <VarInt as FieldRead>::read_from(stream)?.0;
+/// String(n): at most n UTF-16 code units, so at most n * 3 bytes on the wire
+impl BoundedFieldRead for String {
+    fn read_bounded(reader: &mut ByteReader<'_>, max_length: usize) -> anyhow::Result<Self> {
+        let len = reader.read_field::<VarInt>()?.0;
 
-        if len > 32767 || len < 0 {
+        if len < 0 || len as usize > max_length * 3 {
             return Err(anyhow::anyhow!("String length invalid"));
         }
 
-        let mut buf = vec![0u8; len as usize];
-        stream.read_exact(&mut buf)?;
+        let bytes = reader.read_bytes(len as usize)?;
+        let value = String::from_utf8(bytes.to_vec()).map_err(|_| anyhow::anyhow!("Invalid UTF-8"))?;
+        if value.encode_utf16().count() > max_length {
+            return Err(anyhow::anyhow!("String longer than {} characters", max_length));
+        }
 
-        String::from_utf8(buf).map_err(|_| anyhow::anyhow!("Invalid UTF-8"))
+        Ok(value)
     }
 }
 
@@ -44,4 +41,4 @@ impl PacketWrite for String {
         VarInt(bytes.len() as i32).write_to(buf);
         buf.extend_from_slice(bytes);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,49 @@
+use std::ops::BitOr;
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+
+/// Which fields of a teleport are relative to the player's current state instead of absolute, sent as an Int
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TeleportFlags(pub i32);
+
+impl TeleportFlags {
+    /// Every field is absolute
+    pub const ABSOLUTE: TeleportFlags = TeleportFlags(0);
+    pub const RELATIVE_X: TeleportFlags = TeleportFlags(0x0001);
+    pub const RELATIVE_Y: TeleportFlags = TeleportFlags(0x0002);
+    pub const RELATIVE_Z: TeleportFlags = TeleportFlags(0x0004);
+    pub const RELATIVE_YAW: TeleportFlags = TeleportFlags(0x0008);
+    pub const RELATIVE_PITCH: TeleportFlags = TeleportFlags(0x0010);
+    pub const RELATIVE_VELOCITY_X: TeleportFlags = TeleportFlags(0x0020);
+    pub const RELATIVE_VELOCITY_Y: TeleportFlags = TeleportFlags(0x0040);
+    pub const RELATIVE_VELOCITY_Z: TeleportFlags = TeleportFlags(0x0080);
+    /// Rotates the velocity by the change in rotation before applying it
+    pub const ROTATE_VELOCITY: TeleportFlags = TeleportFlags(0x0100);
+
+    pub const RELATIVE_POSITION: TeleportFlags = TeleportFlags(0x0007);
+    pub const RELATIVE_ROTATION: TeleportFlags = TeleportFlags(0x0018);
+    pub const RELATIVE_VELOCITY: TeleportFlags = TeleportFlags(0x00E0);
+
+    pub fn contains(self, flags: TeleportFlags) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl BitOr for TeleportFlags {
+    type Output = TeleportFlags;
+
+    fn bitor(self, rhs: TeleportFlags) -> TeleportFlags {
+        TeleportFlags(self.0 | rhs.0)
+    }
+}
+
+impl FieldRead for TeleportFlags {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(TeleportFlags(reader.read_field()?))
+    }
+}
+
+impl PacketWrite for TeleportFlags {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.0.write_to(buf);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::nbt::Nbt;
+
+/// Text component sent as network NBT (no root name).
+/// Components built here are plain text, a single String tag. Components read from a client keep
+/// their tag, so styling and children are written back unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextComponent(Nbt);
+
+impl TextComponent {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self(Nbt::String(text.into()))
+    }
+
+    /// Text without styling: the String tag, or the "text" of a compound without its children
+    pub fn plain_text(&self) -> &str {
+        match &self.0 {
+            Nbt::String(text) => text,
+            compound => match compound.get("text") {
+                Some(Nbt::String(text)) => text,
+                _ => "",
+            },
+        }
+    }
+
+    /// JSON form of the component, used by the packets sent before configuration (like login disconnect).
+    /// Only the plain text is kept.
+    pub fn to_json(&self) -> String {
+        serde_json::json!({ "text": self.plain_text() }).to_string()
+    }
+}
+
+impl From<&str> for TextComponent {
+    fn from(text: &str) -> Self {
+        TextComponent::text(text)
+    }
+}
+
+impl From<String> for TextComponent {
+    fn from(text: String) -> Self {
+        TextComponent::text(text)
+    }
+}
+
+impl FieldRead for TextComponent {
+    /// A String tag, or a compound with its styling and children
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        match reader.read_field::<Nbt>()? {
+            tag @ (Nbt::String(_) | Nbt::Compound(_)) => Ok(TextComponent(tag)),
+            other => Err(anyhow::anyhow!("Invalid text component {:?}", other)),
+        }
+    }
+}
+
+impl PacketWrite for TextComponent {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.0.write_to(buf);
+    }
+}
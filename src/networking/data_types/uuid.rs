@@ -1,23 +1,9 @@
-use std::io::Read;
-use async_trait::async_trait;
-use tokio::io::{AsyncRead, AsyncReadExt};
 use uuid::Uuid;
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
-
-#[async_trait]
-impl PacketRead for Uuid {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 16];
-        stream.read_exact(&mut buf).await?;
-        Ok(Uuid::from_bytes(buf))
-    }
-}
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
 
 impl FieldRead for Uuid {
-    fn read_from<R: Read>(stream: &mut R) -> anyhow::Result<Self> {
-        let mut buf = [0u8; 16];
-        stream.read_exact(&mut buf)?;
-        Ok(Uuid::from_bytes(buf))
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        Ok(Uuid::from_bytes(reader.read_array()?))
     }
 }
 
@@ -25,4 +11,4 @@ impl PacketWrite for Uuid {
     fn write_to(&self, buf: &mut Vec<u8>) {
         buf.extend_from_slice(self.as_bytes());
     }
-}
\ No newline at end of file
+}
@@ -1,14 +1,28 @@
-use std::io::Read;
-use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use tokio::io::{AsyncRead, AsyncReadExt};
-use crate::networking::data_types::{FieldRead, PacketRead, PacketWrite};
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct VarInt(pub i32);
 
 impl VarInt {
-    const MAX_SIZE: usize = 5;
+    pub const MAX_SIZE: usize = 5;
+
+    /// Encodes the VarInt on the stack, returns the bytes and how many of them are used
+    pub fn encode(self) -> ([u8; Self::MAX_SIZE], usize) {
+        let mut bytes = [0; Self::MAX_SIZE];
+        let mut temp = self.0 as u32;
+        let mut size = 0;
+
+        loop {
+            if (temp & !0x7F) == 0 {
+                bytes[size] = temp as u8;
+                return (bytes, size + 1);
+            }
+            bytes[size] = (temp & 0x7F) as u8 | 0x80;
+            temp >>= 7;
+            size += 1;
+        }
+    }
 }
 
 impl From<i32> for VarInt {
@@ -18,45 +32,46 @@ impl From<VarInt> for i32 {
     fn from(v: VarInt) -> Self { v.0 }
 }
 
-#[async_trait]
-impl PacketRead for VarInt {
-    async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> anyhow::Result<Self> {
+impl VarInt {
+    /// Decodes a VarInt from the start of a buffer, without consuming it.
+    /// Returns the value and its size, or None if the buffer ends before the VarInt does.
+    pub fn peek(buf: &[u8]) -> anyhow::Result<Option<(VarInt, usize)>> {
         let mut num = 0;
-        let mut received = 0;
-        loop {
-            let mut buf = [0; 1];
-            stream.read_exact(&mut buf).await?;
-            let byte = buf[0];
-            let value = (byte & 0x7F) as i32;
-            num |= value << (7 * received);
-
-            received += 1;
-            if received > Self::MAX_SIZE {
+        for (received, byte) in buf.iter().enumerate() {
+            if received >= Self::MAX_SIZE {
                 return Err(anyhow::anyhow!("VarInt too big"));
             }
 
+            let value = (byte & 0x7F) as i32;
+            num |= value << (7 * received);
+
             if (byte & 0x80) == 0 {
-                return Ok(VarInt(num));
+                return Ok(Some((VarInt(num), received + 1)));
             }
         }
+
+        if buf.len() >= Self::MAX_SIZE {
+            return Err(anyhow::anyhow!("VarInt too big"));
+        }
+
+        Ok(None)
     }
 }
 
 impl FieldRead for VarInt {
-    fn read_from<R: Read>(reader: &mut R) -> anyhow::Result<Self> {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
         let mut num = 0;
         let mut received = 0;
         loop {
-            let mut buf = [0; 1];
-            reader.read_exact(&mut buf)?;
-            let byte = buf[0];
+            // Checked before shifting, a 6th byte would shift past the 32 bits
+            if received >= Self::MAX_SIZE {
+                return Err(anyhow::anyhow!("VarInt too big"));
+            }
+
+            let byte = reader.read_byte()?;
             let value = (byte & 0x7F) as i32;
             num |= value << (7 * received);
-
             received += 1;
-            if received > Self::MAX_SIZE {
-                return Err(anyhow::anyhow!("VarInt too big"));
-            }
 
             if (byte & 0x80) == 0 {
                 return Ok(VarInt(num));
@@ -65,7 +80,6 @@ impl FieldRead for VarInt {
     }
 }
 
-
 impl PacketWrite for VarInt {
     fn write_to(&self, buf: &mut Vec<u8>) {
         let mut temp = self.0 as u32;
@@ -0,0 +1,52 @@
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+
+/// Variable length i64, encoded like [`VarInt`](crate::networking::data_types::var_int::VarInt) in up to 10 bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VarLong(pub i64);
+
+impl VarLong {
+    pub const MAX_SIZE: usize = 10;
+}
+
+impl From<i64> for VarLong {
+    fn from(v: i64) -> Self { VarLong(v) }
+}
+impl From<VarLong> for i64 {
+    fn from(v: VarLong) -> Self { v.0 }
+}
+
+impl FieldRead for VarLong {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let mut num = 0;
+        let mut received = 0;
+        loop {
+            // Checked before shifting, an 11th byte would shift past the 64 bits
+            if received >= Self::MAX_SIZE {
+                return Err(anyhow::anyhow!("VarLong too big"));
+            }
+
+            let byte = reader.read_byte()?;
+            let value = (byte & 0x7F) as i64;
+            num |= value << (7 * received);
+            received += 1;
+
+            if (byte & 0x80) == 0 {
+                return Ok(VarLong(num));
+            }
+        }
+    }
+}
+
+impl PacketWrite for VarLong {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        let mut temp = self.0 as u64;
+        loop {
+            if (temp & !0x7F) == 0 {
+                buf.push(temp as u8);
+                return;
+            }
+            buf.push((temp & 0x7F) as u8 | 0x80);
+            temp >>= 7;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use bytes::{Buf, BytesMut};
+use tokio::io::AsyncReadExt;
+use tokio::net::tcp::OwnedReadHalf;
+use crate::networking::data_types::var_int::VarInt;
+
+/// Frame that can't be read, the stream is out of sync after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// Length prefix that is negative or not a valid VarInt
+    InvalidLength,
+    /// Length over the maximum given by the caller
+    TooLarge,
+}
+
+/// Splits a socket into length prefixed frames (ID + Data), shared by the connections and the proxy.
+/// Only the frame boundaries are decoded here, frames are split off the buffer without copying.
+pub struct FrameReader {
+    stream: OwnedReadHalf,
+    /// Bytes received from the socket that don't form a complete frame yet
+    buffer: BytesMut,
+}
+
+impl FrameReader {
+    pub fn new(stream: OwnedReadHalf) -> FrameReader {
+        FrameReader { stream, buffer: BytesMut::with_capacity(4096) }
+    }
+
+    /// Splits the next frame off the buffer if it was completely received
+    pub fn next_frame(&mut self, max_size: usize) -> Result<Option<BytesMut>, FrameError> {
+        split_frame(&mut self.buffer, max_size)
+    }
+
+    /// Reads more bytes from the socket, 0 once it's closed
+    pub async fn fill(&mut self) -> std::io::Result<usize> {
+        self.stream.read_buf(&mut self.buffer).await
+    }
+
+    /// Reads until the next frame is complete, None once the socket is closed
+    pub async fn read_frame(&mut self, max_size: usize) -> anyhow::Result<Option<BytesMut>> {
+        loop {
+            if let Some(frame) = self.next_frame(max_size).map_err(|e| anyhow::anyhow!("Invalid frame: {:?}", e))? {
+                return Ok(Some(frame));
+            }
+
+            if self.fill().await? == 0 {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// The socket and the bytes received but not read as frames yet
+    pub fn into_parts(self) -> (OwnedReadHalf, BytesMut) {
+        (self.stream, self.buffer)
+    }
+}
+
+/// Splits the next length prefixed frame off the start of a buffer, None if it's incomplete.
+/// The buffer reserves the missing bytes of a frame whose length is known.
+pub fn split_frame(buffer: &mut BytesMut, max_size: usize) -> Result<Option<BytesMut>, FrameError> {
+    let Some((length, prefix_size)) = VarInt::peek(buffer).map_err(|_| FrameError::InvalidLength)? else {
+        return Ok(None);
+    };
+
+    if length.0 < 0 {
+        return Err(FrameError::InvalidLength);
+    }
+
+    let length = length.0 as usize;
+    if length > max_size {
+        return Err(FrameError::TooLarge);
+    }
+
+    if buffer.len() >= prefix_size + length {
+        buffer.advance(prefix_size);
+        return Ok(Some(buffer.split_to(length)));
+    }
+
+    buffer.reserve(prefix_size + length - buffer.len());
+    Ok(None)
+}
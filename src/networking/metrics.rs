@@ -0,0 +1,139 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Reasons a connection or a packet gets rejected, each one has its own counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// Frame length prefix that is negative or not a valid VarInt
+    InvalidFrameLength,
+    /// Frame longer than the maximum of its phase
+    OversizedFrame,
+    /// Prefixed array claiming more items than its frame can hold
+    OversizedArray,
+    /// Handshake and login not completed before the deadline
+    LoginTimeout,
+    /// Nothing received for longer than the read timeout
+    IdleTimeout,
+    UnknownPacket,
+    UnexpectedPacket,
+    TrailingBytes,
+    /// New connection over the budget of its IP
+    ConnectionThrottled,
+    /// New connection from a temporarily banned IP
+    BannedAddress,
+    /// Packet over the budget of its connection
+    PacketRateLimited,
+    /// Client not reading its packets fast enough
+    SlowConsumer,
+    /// Write to the socket not done before the write timeout, the client stopped reading
+    WriteTimeout,
+}
+
+impl Rejection {
+    pub const ALL: [Rejection; 13] = [
+        Rejection::InvalidFrameLength,
+        Rejection::OversizedFrame,
+        Rejection::OversizedArray,
+        Rejection::LoginTimeout,
+        Rejection::IdleTimeout,
+        Rejection::UnknownPacket,
+        Rejection::UnexpectedPacket,
+        Rejection::TrailingBytes,
+        Rejection::ConnectionThrottled,
+        Rejection::BannedAddress,
+        Rejection::PacketRateLimited,
+        Rejection::SlowConsumer,
+        Rejection::WriteTimeout,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rejection::InvalidFrameLength => "invalid_frame_length",
+            Rejection::OversizedFrame => "oversized_frame",
+            Rejection::OversizedArray => "oversized_array",
+            Rejection::LoginTimeout => "login_timeout",
+            Rejection::IdleTimeout => "idle_timeout",
+            Rejection::UnknownPacket => "unknown_packet",
+            Rejection::UnexpectedPacket => "unexpected_packet",
+            Rejection::TrailingBytes => "trailing_bytes",
+            Rejection::ConnectionThrottled => "connection_throttled",
+            Rejection::BannedAddress => "banned_address",
+            Rejection::PacketRateLimited => "packet_rate_limited",
+            Rejection::SlowConsumer => "slow_consumer",
+            Rejection::WriteTimeout => "write_timeout",
+        }
+    }
+}
+
+static COUNTERS: [AtomicU64; Rejection::ALL.len()] = [const { AtomicU64::new(0) }; Rejection::ALL.len()];
+
+/// Counts one rejection
+pub fn record(rejection: Rejection) {
+    COUNTERS[rejection as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Rejections counted since the server started
+pub fn count(rejection: Rejection) -> u64 {
+    COUNTERS[rejection as usize].load(Ordering::Relaxed)
+}
+
+static TICKS: AtomicU64 = AtomicU64::new(0);
+static TICK_MICROS: AtomicU64 = AtomicU64::new(0);
+static TICK_MAX_MICROS: AtomicU64 = AtomicU64::new(0);
+
+/// Time spent in world ticks since the last [`take_tick_times`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickTimes {
+    pub ticks: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl TickTimes {
+    pub fn average(&self) -> Duration {
+        if self.ticks == 0 { Duration::ZERO } else { self.total / self.ticks as u32 }
+    }
+}
+
+/// Counts the time one world tick took
+pub fn record_tick(duration: Duration) {
+    let micros = duration.as_micros() as u64;
+    TICKS.fetch_add(1, Ordering::Relaxed);
+    TICK_MICROS.fetch_add(micros, Ordering::Relaxed);
+    TICK_MAX_MICROS.fetch_max(micros, Ordering::Relaxed);
+}
+
+/// Tick times since the previous call, the counters start over
+pub fn take_tick_times() -> TickTimes {
+    TickTimes {
+        ticks: TICKS.swap(0, Ordering::Relaxed),
+        total: Duration::from_micros(TICK_MICROS.swap(0, Ordering::Relaxed)),
+        max: Duration::from_micros(TICK_MAX_MICROS.swap(0, Ordering::Relaxed)),
+    }
+}
+
+/// Prints the rejection counters at every interval in which any of them changed
+pub fn spawn_reporter(interval: Duration) {
+    tokio::spawn(async move {
+        let mut last = [0u64; Rejection::ALL.len()];
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            let current = Rejection::ALL.map(count);
+            if current == last {
+                continue;
+            }
+
+            let counters: Vec<String> = Rejection::ALL.iter()
+                .zip(current)
+                .filter(|(_, value)| *value > 0)
+                .map(|(rejection, value)| format!("{}={}", rejection.name(), value))
+                .collect();
+            println!("Rejections: {}", counters.join(", "));
+
+            last = current;
+        }
+    });
+}
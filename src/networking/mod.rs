@@ -1,4 +1,13 @@
-pub(crate) mod data_types;
+pub mod data_types;
 pub mod packets;
 pub mod connection;
-pub mod account;
\ No newline at end of file
+pub mod account;
+pub mod resource_pack_server;
+pub mod channels;
+pub mod protocol_version;
+pub mod metrics;
+pub mod rate_limit;
+pub mod writer;
+pub mod buffer_pool;
+pub mod capture;
+pub mod framing;
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use crate::networking::connection::ConnectionPhase;
+use crate::networking::data_types::PacketWrite;
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// Packet sent by the server, bound to its vanilla packet name.
+/// The id is looked up in the table of the connection's version and phase, so a packet
+/// can't be sent in a phase it doesn't exist in.
+pub trait ClientboundPacket: PacketWrite {
+    /// Name of the packet in the vanilla packet report, e.g. "minecraft:login"
+    const NAME: &'static str;
+
+    /// Writes the packet with the layout of a protocol version.
+    /// Packets whose layout changed between the supported versions translate here,
+    /// the rest are written as they are for the latest version.
+    /// Fails if the packet holds something the version can't represent.
+    fn write_for(&self, version: ProtocolVersion, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        let _ = version;
+        self.write_to(buf);
+        Ok(())
+    }
+}
+
+// Clientbound packet ids of protocol 774 (1.21.11) by phase and vanilla packet name, generated from reports/packets.json.
+// Protocol 773 (1.21.9 and 1.21.10) uses the same ids, the versions differ in the registries
+// sent during configuration and in data component ids, which packets translate in `ClientboundPacket::write_for`.
+include!(concat!(env!("OUT_DIR"), "/packets.rs"));
+
+type ClientboundIds = HashMap<(ProtocolVersion, ConnectionPhase, &'static str), i32>;
+
+static CLIENTBOUND_IDS: LazyLock<ClientboundIds> = LazyLock::new(|| {
+    let mut ids = HashMap::new();
+
+    for version in ProtocolVersion::ALL {
+        for (phase, name, id) in CLIENTBOUND_PACKETS {
+            ids.insert((version, *phase, *name), *id);
+        }
+    }
+
+    ids
+});
+
+/// Id of a clientbound packet in a version and phase, None if the packet doesn't exist in it
+pub fn clientbound_id(version: ProtocolVersion, phase: ConnectionPhase, name: &str) -> Option<i32> {
+    CLIENTBOUND_IDS.get(&(version, phase, name)).copied()
+}
+
+/// Like [`clientbound_id`], but fails with a descriptive error
+pub fn require_clientbound_id(version: ProtocolVersion, phase: ConnectionPhase, name: &str) -> anyhow::Result<i32> {
+    clientbound_id(version, phase, name)
+        .ok_or_else(|| anyhow::anyhow!("Packet {} can't be sent in the {:?} phase of {}", name, phase, version))
+}
+
+/// Vanilla name of a clientbound packet id in a version and phase, for tools printing captured packets
+pub fn clientbound_name(version: ProtocolVersion, phase: ConnectionPhase, id: i32) -> Option<&'static str> {
+    CLIENTBOUND_IDS.iter()
+        .find(|((v, p, _), packet_id)| *v == version && *p == phase && **packet_id == id)
+        .map(|((_, _, name), _)| *name)
+}
@@ -0,0 +1,57 @@
+use uuid::Uuid;
+use crate::config::ResourcePackConfig;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// "Add resource pack - 0x09" in configuration and "Add resource pack - 0x4F" in play
+#[derive(PacketWrite)]
+pub struct AddResourcePackResponsePacket {
+    pub uuid: Uuid,
+    pub url: String,
+    pub hash: String,
+    pub forced: bool,
+    pub prompt_message: Option<TextComponent>,
+}
+
+impl ClientboundPacket for AddResourcePackResponsePacket {
+    const NAME: &'static str = "minecraft:resource_pack_push";
+}
+
+impl From<&ResourcePackConfig> for AddResourcePackResponsePacket {
+    fn from(pack: &ResourcePackConfig) -> Self {
+        AddResourcePackResponsePacket {
+            uuid: pack.id,
+            url: pack.url.clone(),
+            hash: pack.hash.clone(),
+            forced: pack.required,
+            prompt_message: pack.prompt.clone().map(TextComponent::text),
+        }
+    }
+}
+
+/// Pushes every pack from the configuration during the configuration phase.
+/// Returns false when there was nothing to push.
+pub async fn send_configured_resource_packs(connection: &mut Connection) -> anyhow::Result<bool> {
+    let config = connection.config.clone();
+
+    if config.resource_packs.is_empty() {
+        return Ok(false);
+    }
+
+    if let Some(uuid) = connection.player_uuid
+        && let Some(mut player) = connection.player_list.get_mut(&uuid) {
+        for pack in &config.resource_packs {
+            player.resource_packs.insert(pack.id, None);
+        }
+    }
+
+    for pack in &config.resource_packs {
+        // Send "Add resource pack - 0x09"
+        connection.send_packet(AddResourcePackResponsePacket::from(pack)).await?;
+        println!("Sent resource pack: {}", pack.url);
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// The client only keeps this many details
+pub const MAX_DETAILS: usize = 32;
+const MAX_TITLE_LENGTH: usize = 128;
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReportDetail {
+    pub title: String,
+    pub description: String,
+}
+
+impl PacketWrite for ReportDetail {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.write_type(truncate(&self.title, MAX_TITLE_LENGTH));
+        buf.write_type(truncate(&self.description, MAX_DESCRIPTION_LENGTH));
+    }
+}
+
+/// "Custom report details - 0x0F" in configuration and "Custom report details - 0x86" in play.
+/// Attached by the client to its crash reports.
+pub struct CustomReportDetailsResponsePacket {
+    pub details: Vec<ReportDetail>,
+}
+
+impl ClientboundPacket for CustomReportDetailsResponsePacket {
+    const NAME: &'static str = "minecraft:custom_report_details";
+}
+
+impl PacketWrite for CustomReportDetailsResponsePacket {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        let details: Vec<ReportDetail> = self.details.iter().take(MAX_DETAILS).cloned().collect();
+        buf.write_type(details);
+    }
+}
+
+/// Sends the report details from the configuration, if there are any
+pub async fn send_configured_report_details(connection: &mut Connection) -> anyhow::Result<()> {
+    if connection.config.custom_report_details.is_empty() {
+        return Ok(());
+    }
+
+    let packet = CustomReportDetailsResponsePacket { details: connection.config.custom_report_details.clone() };
+
+    // Send "Custom report details - 0x0F"
+    connection.send_packet(packet).await
+}
+
+/// Cuts a string to a maximum amount of characters
+fn truncate(value: &str, max_chars: usize) -> String {
+    value.chars().take(max_chars).collect()
+}
@@ -0,0 +1,13 @@
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// "Disconnect - 0x02" in configuration and "Disconnect - 0x20" in play
+#[derive(PacketWrite)]
+pub struct DisconnectResponsePacket {
+    pub reason: TextComponent,
+}
+
+impl ClientboundPacket for DisconnectResponsePacket {
+    const NAME: &'static str = "minecraft:disconnect";
+}
@@ -0,0 +1,11 @@
+//! Packets that exist in both the configuration and play phases with the same layout.
+//! Only the packet id changes between phases.
+
+pub mod disconnect_response;
+pub mod plugin_message_request;
+pub mod plugin_message_response;
+pub mod add_resource_pack_response;
+pub mod remove_resource_pack_response;
+pub mod resource_pack_response_request;
+pub mod server_links_response;
+pub mod custom_report_details_response;
@@ -0,0 +1,26 @@
+use anyhow::{Result};
+use async_trait::async_trait;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::raw_bytes::RawBytes;
+use crate::networking::data_types::PacketWrite;
+use crate::networking::packets::{Packet, PacketHandler};
+
+/// "Plugin message - 0x02" in configuration and "Plugin message - 0x15" in play
+#[derive(Debug, Packet, PacketWrite)]
+pub struct PluginMessageRequestPacket {
+    pub channel: Identifier,
+    /// Vanilla's limit for serverbound payloads
+    #[field(max_length = 32767)]
+    pub data: RawBytes,
+}
+
+#[async_trait]
+impl PacketHandler for PluginMessageRequestPacket {
+    async fn handle(&self, ctx: &mut Connection) -> Result<()> {
+        println!("Handling Plugin Message on channel: {}", self.channel);
+
+        let channels = ctx.channels.clone();
+        channels.handle(&self.channel, &self.data.0, ctx).await
+    }
+}
@@ -0,0 +1,27 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::raw_bytes::RawBytes;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// "Plugin message - 0x01" in configuration and "Plugin message - 0x18" in play
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct PluginMessageResponsePacket {
+    pub channel: Identifier,
+    pub data: RawBytes,
+}
+
+impl ClientboundPacket for PluginMessageResponsePacket {
+    const NAME: &'static str = "minecraft:custom_payload";
+}
+
+impl PluginMessageResponsePacket {
+    pub fn nullspace() -> PluginMessageResponsePacket {
+        let mut payload_buffer = Vec::new();
+        "Nullspace".to_string().write_to(&mut payload_buffer);
+
+        PluginMessageResponsePacket {
+            channel: Identifier::new("minecraft", "brand"),
+            data: RawBytes(payload_buffer),
+        }
+    }
+}
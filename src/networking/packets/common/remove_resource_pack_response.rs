@@ -0,0 +1,14 @@
+use uuid::Uuid;
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// "Remove resource pack - 0x08" in configuration and "Remove resource pack - 0x4E" in play.
+/// Without an uuid the client removes every server pack.
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct RemoveResourcePackResponsePacket {
+    pub uuid: Option<Uuid>,
+}
+
+impl ClientboundPacket for RemoveResourcePackResponsePacket {
+    const NAME: &'static str = "minecraft:resource_pack_pop";
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::{Packet, PacketHandler};
+use crate::networking::packets::configuration::finish_configuration_response::finish_configuration;
+
+/// State of a resource pack pushed to a player, as reported by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourcePackStatus {
+    Loaded,
+    Declined,
+    FailedDownload,
+    Accepted,
+    Downloaded,
+    InvalidUrl,
+    FailedReload,
+    Discarded,
+}
+
+impl ResourcePackStatus {
+    /// The client will not send more updates for this pack
+    pub fn is_final(self) -> bool {
+        !matches!(self, ResourcePackStatus::Accepted | ResourcePackStatus::Downloaded)
+    }
+
+    /// The pack is not applied on the client
+    pub fn is_failure(self) -> bool {
+        self.is_final() && self != ResourcePackStatus::Loaded
+    }
+}
+
+impl TryFrom<VarInt> for ResourcePackStatus {
+    type Error = anyhow::Error;
+
+    fn try_from(value: VarInt) -> Result<Self, Self::Error> {
+        match value.0 {
+            0 => Ok(ResourcePackStatus::Loaded),
+            1 => Ok(ResourcePackStatus::Declined),
+            2 => Ok(ResourcePackStatus::FailedDownload),
+            3 => Ok(ResourcePackStatus::Accepted),
+            4 => Ok(ResourcePackStatus::Downloaded),
+            5 => Ok(ResourcePackStatus::InvalidUrl),
+            6 => Ok(ResourcePackStatus::FailedReload),
+            7 => Ok(ResourcePackStatus::Discarded),
+            other => Err(anyhow::anyhow!("Invalid resource pack result: {}", other)),
+        }
+    }
+}
+
+impl FieldRead for ResourcePackStatus {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        ResourcePackStatus::try_from(reader.read_field::<VarInt>()?)
+    }
+}
+
+impl PacketWrite for ResourcePackStatus {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        let result = match self {
+            ResourcePackStatus::Loaded => 0,
+            ResourcePackStatus::Declined => 1,
+            ResourcePackStatus::FailedDownload => 2,
+            ResourcePackStatus::Accepted => 3,
+            ResourcePackStatus::Downloaded => 4,
+            ResourcePackStatus::InvalidUrl => 5,
+            ResourcePackStatus::FailedReload => 6,
+            ResourcePackStatus::Discarded => 7,
+        };
+        VarInt(result).write_to(buf);
+    }
+}
+
+/// "Resource pack response - 0x06" in configuration and "Resource pack response - 0x30" in play
+#[derive(Debug, Packet, PacketWrite)]
+pub struct ResourcePackResponseRequestPacket {
+    pub uuid: Uuid,
+    pub result: ResourcePackStatus,
+}
+
+#[async_trait]
+impl PacketHandler for ResourcePackResponseRequestPacket {
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
+        println!("Handling resource pack response...");
+
+        println!("Resource pack {} is now {:?}", self.uuid, self.result);
+
+        let Some(player_uuid) = ctx.player_uuid else {
+            return Err(anyhow::anyhow!("Resource pack response before login"));
+        };
+
+        // Track the state of pushed packs, and check if they are what holds back the finish
+        let finish = match ctx.player_list.get_mut(&player_uuid) {
+            Some(mut player) => {
+                let Some(status) = player.resource_packs.get_mut(&self.uuid) else {
+                    println!("Ignoring resource pack {}, it was never pushed", self.uuid);
+                    return Ok(());
+                };
+                *status = Some(self.result);
+
+                let waiting = !player.known_packs_requested && !player.finish_configuration_sent;
+                waiting && player.resource_packs.values().all(|status| status.is_some_and(ResourcePackStatus::is_final))
+            }
+            None => return Ok(()),
+        };
+
+        let required = ctx.config.resource_packs.iter()
+            .any(|pack| pack.id == self.uuid && pack.required);
+
+        if required && self.result.is_failure() {
+            println!("Required resource pack {} was {:?}, kicking player", self.uuid, self.result);
+            ctx.kick("This server requires its resource pack to play.").await?;
+            return Ok(());
+        }
+
+        // During configuration we hold the "Finish configuration" until the packs are settled
+        if ctx.phase() == ConnectionPhase::Configuration && self.result.is_final() && finish {
+            finish_configuration(ctx).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Labels the client already knows how to translate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltInLinkLabel {
+    BugReport,
+    CommunityGuidelines,
+    Support,
+    Status,
+    Feedback,
+    Community,
+    Website,
+    Forums,
+    News,
+    Announcements,
+}
+
+/// In the config a built-in label is written as its name ("website"),
+/// and a custom one as a text component ({"text": "Discord"})
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ServerLinkLabel {
+    BuiltIn(BuiltInLinkLabel),
+    Custom { text: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ServerLink {
+    pub label: ServerLinkLabel,
+    pub url: String,
+}
+
+impl PacketWrite for ServerLink {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        match &self.label {
+            ServerLinkLabel::BuiltIn(label) => {
+                buf.write_type(true);
+                buf.write_type(VarInt(*label as i32));
+            }
+            ServerLinkLabel::Custom { text } => {
+                buf.write_type(false);
+                buf.write_type(TextComponent::text(text.clone()));
+            }
+        }
+        buf.write_type(self.url.clone());
+    }
+}
+
+/// "Server links - 0x10" in configuration and "Server links - 0x87" in play.
+/// Shown by the client in the pause menu.
+#[derive(PacketWrite)]
+pub struct ServerLinksResponsePacket {
+    pub links: Vec<ServerLink>,
+}
+
+impl ClientboundPacket for ServerLinksResponsePacket {
+    const NAME: &'static str = "minecraft:server_links";
+}
+
+/// Sends the links from the configuration, if there are any
+pub async fn send_configured_server_links(connection: &mut Connection) -> anyhow::Result<()> {
+    if connection.config.server_links.is_empty() {
+        return Ok(());
+    }
+
+    let packet = ServerLinksResponsePacket { links: connection.config.server_links.clone() };
+
+    // Send "Server links - 0x10"
+    connection.send_packet(packet).await
+}
@@ -1,30 +1,41 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::var_int::VarInt;
 use crate::networking::packets::{Packet, PacketHandler};
 use crate::networking::packets::play::login_response::LoginResponsePacket;
 use crate::networking::packets::play::synchronize_player_position_response::SynchronizePlayerPositionResponsePacket;
+use crate::world::chunk::sender::ChunkSender;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct AcknowledgeFinishConfigurationRequestPacket {}
 
-impl Packet for AcknowledgeFinishConfigurationRequestPacket {
-    fn decode(_cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        Ok(AcknowledgeFinishConfigurationRequestPacket { })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for AcknowledgeFinishConfigurationRequestPacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
         println!("Handling acknowledge finished request...");
 
         println!("Switching to PLAY phase");
-        ctx.phase = ConnectionPhase::Play;
+        ctx.switch_phase(ConnectionPhase::Play)?;
+        if let Some(mut player) = ctx.player_mut() {
+            player.finish_configuration_sent = false;
+        }
 
         // Send Packets (Responses)
-        ctx.send_packet(0x30, LoginResponsePacket::nullspace()).await?;
-        ctx.send_packet(0x46, SynchronizePlayerPositionResponsePacket::nullspace()).await?;
+        let view_distance = VarInt(ctx.config.world.view_distance as i32);
+        ctx.send_packet(LoginResponsePacket { view_distance, ..LoginResponsePacket::nullspace() }).await?;
+        ctx.send_packet(SynchronizePlayerPositionResponsePacket::nullspace()).await?;
+
+        // Chunks may only follow the login, the world starts streaming them from here
+        if let Some(mut player) = ctx.player_mut() {
+            player.position = (0.0, 0.0, 0.0);
+            player.chunk_sender = Some(ChunkSender::new());
+        }
 
         Ok(())
     }
+
+    fn is_expected(&self, ctx: &Connection) -> bool {
+        ctx.player_mut().is_some_and(|player| player.finish_configuration_sent)
+    }
 }
\ No newline at end of file
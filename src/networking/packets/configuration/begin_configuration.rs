@@ -0,0 +1,28 @@
+use crate::networking::channels::encode_channel_list;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use crate::networking::packets::configuration::feature_flags_response::FeatureFlagsResponsePacket;
+use crate::networking::packets::configuration::known_packs_response::KnownPacksResponsePacket;
+
+/// First packets of the configuration phase, after login or when a player is reconfigured.
+/// The client answers the known packs with "Known packs - 0x07", which continues the flow.
+pub async fn begin_configuration(connection: &mut Connection) -> anyhow::Result<()> {
+    // Send "Plugin message - 0x01" with our brand
+    connection.send_packet(PluginMessageResponsePacket::nullspace()).await?;
+
+    // Announce the custom channels we listen on
+    let channels = connection.channels.custom_channels();
+    if !channels.is_empty() {
+        connection.send_plugin_message(Identifier::minecraft("register"), encode_channel_list(&channels)).await?;
+    }
+
+    // Send "Feature flags - 0x0C" and "Known packs - 0x0E"
+    connection.send_packet(FeatureFlagsResponsePacket::nullspace()).await?;
+    if let Some(mut player) = connection.player_mut() {
+        player.known_packs_requested = true;
+    }
+    connection.send_packet(KnownPacksResponsePacket::nullspace(connection.protocol)).await?;
+
+    Ok(())
+}
@@ -1,14 +1,14 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::Connection;
-use crate::networking::data_types::BufferReadExt;
 use crate::networking::data_types::i_byte::Byte;
-use crate::networking::data_types::identifier::Identifier;
 use crate::networking::data_types::u_byte::UnsignedByte;
 use crate::networking::data_types::var_int::VarInt;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct ClientInformationRequestPacket {
+    #[field(max_length = 16)]
     pub locale: String,
     pub view_distance: Byte,
     pub chat_mode: VarInt,
@@ -20,25 +20,9 @@ pub struct ClientInformationRequestPacket {
     pub particle_status: VarInt,
 }
 
-impl Packet for ClientInformationRequestPacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let locale: String = cursor.read_field()?;
-        let view_distance: Byte = cursor.read_field()?;
-        let chat_mode: VarInt = cursor.read_field()?;
-        let chat_colors: bool = cursor.read_field()?;
-        let displayed_skin_parts: UnsignedByte = cursor.read_field()?;
-        let main_hand: VarInt = cursor.read_field()?;
-        let enable_text_filtering: bool = cursor.read_field()?;
-        let allow_server_listings: bool = cursor.read_field()?;
-        let particle_status: VarInt = cursor.read_field()?;
-
-        Ok(ClientInformationRequestPacket { locale, view_distance, chat_mode, chat_colors, displayed_skin_parts, main_hand, enable_text_filtering, allow_server_listings, particle_status })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for ClientInformationRequestPacket {
-    async fn handle(&self, _ctx: &mut Connection) -> anyhow::Result<()> {
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
         println!("Handling client information request...");
 
         println!("Locale: {:?}", self.locale);
@@ -51,6 +35,11 @@ impl PacketHandler for ClientInformationRequestPacket {
         println!("Allow server listings: {:?}", self.allow_server_listings);
         println!("Particle status: {:?}", self.particle_status);
 
+        // Also sent in play when the player changes their settings
+        if let Some(mut player) = ctx.player_mut() {
+            player.client_view_distance = Some(self.view_distance.0.clamp(2, 32) as u8);
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,14 +1,14 @@
 use crate::networking::data_types::identifier::Identifier;
-use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct FeatureFlagsResponsePacket {
     pub flags: Vec<Identifier>,
 }
 
-impl PacketWrite for FeatureFlagsResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.flags.clone());
-    }
+impl ClientboundPacket for FeatureFlagsResponsePacket {
+    const NAME: &'static str = "minecraft:update_enabled_features";
 }
 
 impl FeatureFlagsResponsePacket {
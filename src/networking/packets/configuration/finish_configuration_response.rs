@@ -1,7 +1,20 @@
-use crate::networking::data_types::{PacketWrite};
+use crate::networking::connection::Connection;
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct FinishConfigurationResponsePacket {}
 
-impl PacketWrite for FinishConfigurationResponsePacket {
-    fn write_to(&self, _buf: &mut Vec<u8>) {}
-}
\ No newline at end of file
+impl ClientboundPacket for FinishConfigurationResponsePacket {
+    const NAME: &'static str = "minecraft:finish_configuration";
+}
+
+/// Sends "Finish configuration - 0x03", the client answers with "Acknowledge finish configuration - 0x03"
+pub async fn finish_configuration(connection: &mut Connection) -> anyhow::Result<()> {
+    // Flagged first, so a broadcast can't queue a configuration packet behind the finish
+    if let Some(mut player) = connection.player_mut() {
+        player.finish_configuration_sent = true;
+    }
+
+    connection.send_packet(FinishConfigurationResponsePacket { }).await
+}
@@ -1,24 +1,20 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::Connection;
-use crate::networking::data_types::BufferReadExt;
 use crate::networking::data_types::known_pack::KnownPack;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
-use crate::networking::packets::configuration::finish_configuration_response::FinishConfigurationResponsePacket;
+use crate::networking::packets::common::add_resource_pack_response::send_configured_resource_packs;
+use crate::networking::packets::common::custom_report_details_response::send_configured_report_details;
+use crate::networking::packets::common::server_links_response::send_configured_server_links;
+use crate::networking::packets::configuration::finish_configuration_response::finish_configuration;
 use crate::networking::packets::configuration::registry_response::send_all_registries;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct KnownPacksRequestPacket {
+    #[field(max_length = 64)]
     pub known_packs: Vec<KnownPack>,
 }
 
-impl Packet for KnownPacksRequestPacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let known_packs: Vec<KnownPack> = cursor.read_field()?;
-
-        Ok(KnownPacksRequestPacket { known_packs })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for KnownPacksRequestPacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
@@ -26,12 +22,30 @@ impl PacketHandler for KnownPacksRequestPacket {
 
         println!("Known packs: {:?}", self.known_packs);
 
+        if let Some(mut player) = ctx.player_mut() {
+            player.known_packs_requested = false;
+        }
+
+        // Send "Server links - 0x10" and "Custom report details - 0x0F"
+        send_configured_server_links(ctx).await?;
+        send_configured_report_details(ctx).await?;
+
         // Send "Registry data - 0x07" and "Update tags - 0x0D"
         send_all_registries(ctx).await?;
 
+        // Send "Add resource pack - 0x09", "Finish configuration" waits for the client to load them
+        if send_configured_resource_packs(ctx).await? {
+            return Ok(());
+        }
+
         // Send "Finish configuration - 0x03"
-        ctx.send_packet(0x03, FinishConfigurationResponsePacket { }).await?;
+        finish_configuration(ctx).await?;
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Answers "Select known packs", once per configuration round and never after "Finish configuration"
+    fn is_expected(&self, ctx: &Connection) -> bool {
+        ctx.player_mut().is_some_and(|player| player.known_packs_requested && !player.finish_configuration_sent)
+    }
+}
@@ -1,26 +1,29 @@
-use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::{FieldRead, PacketWrite};
 use crate::networking::data_types::known_pack::KnownPack;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::networking::protocol_version::ProtocolVersion;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct KnownPacksResponsePacket {
     pub packs: Vec<KnownPack>,
 }
 
-impl PacketWrite for KnownPacksResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.packs.clone());
-    }
+impl ClientboundPacket for KnownPacksResponsePacket {
+    const NAME: &'static str = "minecraft:select_known_packs";
 }
 
 impl KnownPacksResponsePacket {
-    pub fn nullspace() -> KnownPacksResponsePacket {
+    /// The vanilla core pack of every release sharing the protocol version,
+    /// the client only answers with the one it has
+    pub fn nullspace(version: ProtocolVersion) -> KnownPacksResponsePacket {
         KnownPacksResponsePacket {
-            packs: vec![
-                KnownPack {
+            packs: version.releases().iter()
+                .map(|release| KnownPack {
                     namespace: "minecraft".to_string(),
                     id: "core".to_string(),
-                    version: "1.21.11".to_string(),
-                }
-            ]
+                    version: release.to_string(),
+                })
+                .collect()
         }
     }
 }
\ No newline at end of file
@@ -1,9 +1,8 @@
-pub mod plugin_message_configuration_request;
 pub mod client_information_request;
-mod plugin_message_configuration_response;
-mod feature_flags_response;
-mod known_packs_response;
+pub mod feature_flags_response;
+pub mod known_packs_response;
 pub mod known_packs_request;
-mod registry_response;
-mod finish_configuration_response;
-pub mod acknowledge_finish_configuration_request;
\ No newline at end of file
+pub mod registry_response;
+pub mod finish_configuration_response;
+pub mod acknowledge_finish_configuration_request;
+pub mod begin_configuration;
@@ -1,6 +1,40 @@
 use std::collections::HashSet;
 use crate::networking::connection::Connection;
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::raw_bytes::RawBytes;
 use crate::networking::data_types::registries::RegistryData;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// "Registry data - 0x07", the body comes pre-encoded from the registries folder
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct RegistryDataResponsePacket {
+    pub data: RawBytes,
+}
+
+impl ClientboundPacket for RegistryDataResponsePacket {
+    const NAME: &'static str = "minecraft:registry_data";
+}
+
+/// "Update tags - 0x0D", the body comes pre-encoded from the registries folder
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct UpdateTagsResponsePacket {
+    pub data: RawBytes,
+}
+
+impl ClientboundPacket for UpdateTagsResponsePacket {
+    const NAME: &'static str = "minecraft:update_tags";
+}
+
+/// Registries that 1.21.9 and 1.21.10 clients don't know about, they fail to configure if they get them
+const ADDED_IN_1_21_11: &[&str] = &[
+    "minecraft_zombie_nautilus_variant.bin",
+    "minecraft_timeline.bin",
+];
+
+fn registry_exists(version: ProtocolVersion, name: &str) -> bool {
+    version >= ProtocolVersion::V1_21_11 || !ADDED_IN_1_21_11.contains(&name)
+}
 
 pub async fn send_all_registries(connection: &mut Connection) -> anyhow::Result<()> {
     println!("Sending Registry Data...");
@@ -25,7 +59,7 @@ pub async fn send_all_registries(connection: &mut Connection) -> anyhow::Result<
     for filename in RegistryData::iter() {
         let name = filename.as_ref();
 
-        if !whitelist.contains(name) {
+        if !whitelist.contains(name) || !registry_exists(connection.protocol, name) {
             continue;
         }
 
@@ -34,15 +68,21 @@ pub async fn send_all_registries(connection: &mut Connection) -> anyhow::Result<
         }
 
         if let Some(file) = RegistryData::get(name) {
-            connection.send_raw_packet(0x07, &file.data).await?;
+            connection.send_packet(RegistryDataResponsePacket { data: RawBytes(file.data.to_vec()) }).await?;
             println!("Sent registry: {}", name);
         }
     }
 
-    // Sends the "Update tags - 0x0D" packet
+    // Sends the "Update tags - 0x0D" packet.
+    // Tags refer to entries by numeric id, and those shifted with the 1.21.11 additions,
+    // so older clients keep their own vanilla tags instead
+    if connection.protocol < ProtocolVersion::V1_21_11 {
+        return Ok(());
+    }
+
     if let Some(file) = RegistryData::get("packet_tags.bin") {
         println!("Sending Tag Update...");
-        connection.send_raw_packet(0x0D, &file.data).await?;
+        connection.send_packet(UpdateTagsResponsePacket { data: RawBytes(file.data.to_vec()) }).await?;
     }
 
     Ok(())
@@ -1,45 +1,46 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::{Connection, ConnectionPhase};
-use crate::networking::data_types::BufferReadExt;
 use crate::networking::data_types::var_int::VarInt;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
+use crate::networking::protocol_version::ProtocolVersion;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct HandshakePacket {
     pub protocol_version: VarInt,
+    #[field(max_length = 255)]
     pub server_address: String,
     pub port: u16,
     pub next_state: VarInt
 }
 
-impl Packet for HandshakePacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let protocol_version: VarInt = cursor.read_field()?;
-        let server_address: String = cursor.read_field()?;
-        let port: u16 = cursor.read_field()?;
-        let next_state: VarInt = cursor.read_field()?;
-
-        Ok(HandshakePacket { protocol_version, server_address, port, next_state })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for HandshakePacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
         println!("Handling handshake, protocol {:?} and {:?} as intent...", self.protocol_version, self.next_state);
 
-        if self.protocol_version != VarInt::from(774) {
-            Err(anyhow::anyhow!("Protocol version mismatch"))?
+        // Unsupported clients still get the status, which reports the latest version as the expected one
+        let version = ProtocolVersion::from_protocol(self.protocol_version.0);
+        if let Some(version) = version {
+            ctx.protocol = version;
         }
 
         match self.next_state.0 {
             1 => {
                 println!("Switching to STATUS phase");
-                ctx.phase = ConnectionPhase::Status;
+                ctx.switch_phase(ConnectionPhase::Status)?;
             },
-            2 => {
-                println!("Switching to LOGIN phase");
-                ctx.phase = ConnectionPhase::Login;
+            // A transfer from another server logs in like a fresh connection
+            2 | 3 => {
+                println!("Switching to LOGIN phase{}", if self.next_state.0 == 3 { " (transfer)" } else { "" });
+                ctx.switch_phase(ConnectionPhase::Login)?;
+
+                if version.is_none() {
+                    let reason = format!("Unsupported version, use {}", ProtocolVersion::supported_range());
+                    ctx.kick(reason).await?;
+                } else if self.next_state.0 == 3 && !ctx.config.connection.accept_transfers {
+                    ctx.kick("Server does not accept transfers").await?;
+                }
             },
             _ => return Err(anyhow::anyhow!("Invalid next state intent: {}", self.next_state.0)),
         }
@@ -0,0 +1,114 @@
+//! Describes packets for the debugging tools (capture printer, proxy), in both directions.
+//! Serverbound packets go through the decoders of their [`PacketRegistry`], clientbound ones
+//! through the [`FieldRead`] of the packet types that have it. Anything else is printed as hex.
+
+use std::fmt::Debug;
+use crate::networking::connection::ConnectionPhase;
+use crate::networking::data_types::{ByteReader, FieldRead};
+use crate::networking::data_types::raw_bytes::hex;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::PacketRegistry;
+use crate::networking::packets::clientbound::{clientbound_name, ClientboundPacket};
+use crate::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use crate::networking::packets::common::remove_resource_pack_response::RemoveResourcePackResponsePacket;
+use crate::networking::packets::configuration::feature_flags_response::FeatureFlagsResponsePacket;
+use crate::networking::packets::configuration::finish_configuration_response::FinishConfigurationResponsePacket;
+use crate::networking::packets::configuration::known_packs_response::KnownPacksResponsePacket;
+use crate::networking::packets::configuration::registry_response::{RegistryDataResponsePacket, UpdateTagsResponsePacket};
+use crate::networking::packets::login::login_success_response::LoginSuccessResponsePacket;
+use crate::networking::packets::play::bundle_delimiter_response::BundleDelimiterResponsePacket;
+use crate::networking::packets::play::chunk_batch_finished_response::ChunkBatchFinishedResponsePacket;
+use crate::networking::packets::play::chunk_batch_start_response::ChunkBatchStartResponsePacket;
+use crate::networking::packets::play::game_event_response::GameEventResponsePacket;
+use crate::networking::packets::play::keep_alive_response::KeepAliveResponsePacket;
+use crate::networking::packets::play::login_response::LoginResponsePacket;
+use crate::networking::packets::play::set_center_chunk_response::SetCenterChunkResponsePacket;
+use crate::networking::packets::play::start_configuration_response::StartConfigurationResponsePacket;
+use crate::networking::packets::play::synchronize_player_position_response::SynchronizePlayerPositionResponsePacket;
+use crate::networking::packets::play::unload_chunk_response::UnloadChunkResponsePacket;
+use crate::networking::packets::status::pong_response::PongResponsePacket;
+use crate::networking::packets::status::status_response::StatusResponsePacket;
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// Id, name and decoded fields of a serverbound packet body (ID + Data)
+pub fn describe_serverbound(registry: &PacketRegistry, phase: ConnectionPhase, body: &[u8]) -> String {
+    let mut reader = ByteReader::new(body);
+    let Ok(VarInt(id)) = reader.read_field() else {
+        return format!("invalid packet id {}", hex(body));
+    };
+
+    let description = match registry.decode(phase, id, &mut reader) {
+        Some(Ok(packet)) => with_trailing_bytes(format!("{:?}", packet), body, &reader),
+        Some(Err(e)) => format!("failed to decode: {} {}", e, hex(rest(body, &reader))),
+        None => format!("unknown {}", hex(rest(body, &reader))),
+    };
+
+    format!("0x{:02X} {}", id, description)
+}
+
+/// Id, vanilla name and decoded fields of a clientbound packet body (ID + Data)
+pub fn describe_clientbound(version: ProtocolVersion, phase: ConnectionPhase, body: &[u8]) -> String {
+    let mut reader = ByteReader::new(body);
+    let Ok(VarInt(id)) = reader.read_field() else {
+        return format!("invalid packet id {}", hex(body));
+    };
+
+    let Some(name) = clientbound_name(version, phase, id) else {
+        return format!("0x{:02X} unknown {}", id, hex(rest(body, &reader)));
+    };
+
+    let description = match decode_clientbound(name, &mut reader) {
+        Some(Ok(packet)) => with_trailing_bytes(packet, body, &reader),
+        Some(Err(e)) => format!("failed to decode: {} {}", e, hex(rest(body, &reader))),
+        None => hex(rest(body, &reader)),
+    };
+
+    format!("0x{:02X} {} {}", id, name, description)
+}
+
+/// Decodes the clientbound packets that can be read back, None for the others
+fn decode_clientbound(name: &str, reader: &mut ByteReader<'_>) -> Option<anyhow::Result<String>> {
+    let packet = match name {
+        StatusResponsePacket::NAME => decode::<StatusResponsePacket>(reader),
+        PongResponsePacket::NAME => decode::<PongResponsePacket>(reader),
+        LoginSuccessResponsePacket::NAME => decode::<LoginSuccessResponsePacket>(reader),
+        PluginMessageResponsePacket::NAME => decode::<PluginMessageResponsePacket>(reader),
+        RemoveResourcePackResponsePacket::NAME => decode::<RemoveResourcePackResponsePacket>(reader),
+        FeatureFlagsResponsePacket::NAME => decode::<FeatureFlagsResponsePacket>(reader),
+        KnownPacksResponsePacket::NAME => decode::<KnownPacksResponsePacket>(reader),
+        RegistryDataResponsePacket::NAME => decode::<RegistryDataResponsePacket>(reader),
+        UpdateTagsResponsePacket::NAME => decode::<UpdateTagsResponsePacket>(reader),
+        FinishConfigurationResponsePacket::NAME => decode::<FinishConfigurationResponsePacket>(reader),
+        LoginResponsePacket::NAME => decode::<LoginResponsePacket>(reader),
+        SynchronizePlayerPositionResponsePacket::NAME => decode::<SynchronizePlayerPositionResponsePacket>(reader),
+        KeepAliveResponsePacket::NAME => decode::<KeepAliveResponsePacket>(reader),
+        BundleDelimiterResponsePacket::NAME => decode::<BundleDelimiterResponsePacket>(reader),
+        StartConfigurationResponsePacket::NAME => decode::<StartConfigurationResponsePacket>(reader),
+        GameEventResponsePacket::NAME => decode::<GameEventResponsePacket>(reader),
+        SetCenterChunkResponsePacket::NAME => decode::<SetCenterChunkResponsePacket>(reader),
+        ChunkBatchStartResponsePacket::NAME => decode::<ChunkBatchStartResponsePacket>(reader),
+        ChunkBatchFinishedResponsePacket::NAME => decode::<ChunkBatchFinishedResponsePacket>(reader),
+        UnloadChunkResponsePacket::NAME => decode::<UnloadChunkResponsePacket>(reader),
+        _ => return None,
+    };
+
+    Some(packet)
+}
+
+fn decode<T: FieldRead + Debug>(reader: &mut ByteReader<'_>) -> anyhow::Result<String> {
+    Ok(format!("{:?}", T::read_from(reader)?))
+}
+
+/// A decoder reading less than the whole packet usually means the layout is out of date
+fn with_trailing_bytes(packet: String, body: &[u8], reader: &ByteReader<'_>) -> String {
+    if reader.is_empty() {
+        packet
+    } else {
+        format!("{} with {} trailing bytes {}", packet, reader.remaining(), hex(rest(body, reader)))
+    }
+}
+
+/// Bytes of the packet the reader didn't decode
+fn rest<'a>(body: &'a [u8], reader: &ByteReader<'_>) -> &'a [u8] {
+    &body[body.len() - reader.remaining()..]
+}
@@ -0,0 +1,18 @@
+use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// "Disconnect (login) - 0x00", unlike later phases the reason is sent as JSON text
+pub struct DisconnectLoginResponsePacket {
+    pub reason: TextComponent,
+}
+
+impl ClientboundPacket for DisconnectLoginResponsePacket {
+    const NAME: &'static str = "minecraft:login_disconnect";
+}
+
+impl PacketWrite for DisconnectLoginResponsePacket {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.write_type(self.reason.to_json());
+    }
+}
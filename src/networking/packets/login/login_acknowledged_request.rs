@@ -1,24 +1,27 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
+use crate::networking::packets::configuration::begin_configuration::begin_configuration;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct LoginAcknowledgedRequestPacket {}
 
-impl Packet for LoginAcknowledgedRequestPacket {
-    fn decode(_cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        Ok(LoginAcknowledgedRequestPacket { })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for LoginAcknowledgedRequestPacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
         println!("Handling login acknowledged request...");
 
         println!("Switching to CONFIGURATION phase");
-        ctx.phase = ConnectionPhase::Configuration;
+        ctx.switch_phase(ConnectionPhase::Configuration)?;
+
+        begin_configuration(ctx).await?;
 
         Ok(())
     }
+
+    /// Only valid once the login start was answered
+    fn is_expected(&self, ctx: &Connection) -> bool {
+        ctx.player_uuid.is_some()
+    }
 }
\ No newline at end of file
@@ -1,43 +1,38 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use uuid::Uuid;
 use crate::networking::account::Account;
 use crate::networking::connection::Connection;
-use crate::networking::data_types::BufferReadExt;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
 use crate::networking::packets::login::login_success_response::LoginSuccessResponsePacket;
 use crate::world::entities::player::Player;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct LoginStartRequestPacket {
+    #[field(max_length = 16)]
     pub name: String,
     pub player_uuid: Uuid,
 }
 
-impl Packet for LoginStartRequestPacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let name: String = cursor.read_field()?;
-        let player_uuid: Uuid = cursor.read_field()?;
-
-        Ok(LoginStartRequestPacket { name, player_uuid })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for LoginStartRequestPacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
         println!("Handling login start request...");
 
-        let new_player = Player {
-            account: Account {
+        let new_player = Player::new(
+            Account {
                 uuid: self.player_uuid,
                 username: self.name.clone(),
             },
-            writer: ctx.writer_sender.clone(),
-        };
+            ctx.writer.clone(),
+            ctx.phase(),
+            ctx.protocol,
+        );
         ctx.player_list.insert(self.player_uuid, new_player);
+        ctx.player_uuid = Some(self.player_uuid);
 
         // Send Packet 0x02 (Response)
-        ctx.send_packet(0x02, LoginSuccessResponsePacket::new(
+        ctx.send_packet(LoginSuccessResponsePacket::new(
             self.player_uuid,
             self.name.clone(),
             Vec::new()
@@ -45,4 +40,9 @@ impl PacketHandler for LoginStartRequestPacket {
 
         Ok(())
     }
+
+    /// A connection logs in a single player
+    fn is_expected(&self, ctx: &Connection) -> bool {
+        ctx.player_uuid.is_none()
+    }
 }
\ No newline at end of file
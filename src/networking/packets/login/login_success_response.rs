@@ -1,15 +1,15 @@
 use uuid::Uuid;
-use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::{FieldRead, PacketWrite};
 use crate::networking::data_types::game_profile::{GameProfile, GameProfileProperty};
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct LoginSuccessResponsePacket {
     pub profile: GameProfile,
 }
 
-impl PacketWrite for LoginSuccessResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.profile.clone());
-    }
+impl ClientboundPacket for LoginSuccessResponsePacket {
+    const NAME: &'static str = "minecraft:login_finished";
 }
 
 impl LoginSuccessResponsePacket {
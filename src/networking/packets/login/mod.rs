@@ -1,3 +1,3 @@
 pub mod login_start_request;
-mod login_success_response;
-pub mod login_acknowledged_request;
\ No newline at end of file
+pub mod login_success_response;
+pub mod login_acknowledged_request;pub mod disconnect_login_response;
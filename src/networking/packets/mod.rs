@@ -1,37 +1,79 @@
-pub(crate) mod handshake;
-pub(crate) mod configuration;
-pub(crate) mod status;
-pub(crate) mod login;
-pub(crate) mod play;
-
-use std::collections::HashMap;
-use std::io::Cursor;
-use std::pin::Pin;
-use tokio::io::{AsyncRead, AsyncWriteExt};
-use tokio::net::TcpStream;
-use crate::networking::data_types::{BufferWrite, StreamWrite};
-use crate::networking::data_types::var_int::VarInt;
+pub mod handshake;
+pub mod configuration;
+pub mod status;
+pub mod login;
+pub mod play;
+pub mod common;
+pub mod clientbound;
+pub mod inspect;
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
 use anyhow::Result;
 use async_trait::async_trait;
-use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::connection::{Connection, ConnectionPhase, ProtocolViolation};
+use crate::networking::data_types::ByteReader;
+use crate::networking::protocol_version::ProtocolVersion;
+use crate::networking::packets::common::plugin_message_request::PluginMessageRequestPacket;
+use crate::networking::packets::common::resource_pack_response_request::ResourcePackResponseRequestPacket;
+use crate::networking::packets::configuration::acknowledge_finish_configuration_request::AcknowledgeFinishConfigurationRequestPacket;
+use crate::networking::packets::configuration::client_information_request::ClientInformationRequestPacket;
+use crate::networking::packets::configuration::known_packs_request::KnownPacksRequestPacket;
+use crate::networking::packets::handshake::HandshakePacket;
+use crate::networking::packets::login::login_acknowledged_request::LoginAcknowledgedRequestPacket;
+use crate::networking::packets::login::login_start_request::LoginStartRequestPacket;
+use crate::networking::packets::play::acknowledge_configuration_request::AcknowledgeConfigurationRequestPacket;
+use crate::networking::packets::play::chat_message_request::ChatMessageRequestPacket;
+use crate::networking::packets::play::chunk_batch_received_request::ChunkBatchReceivedRequestPacket;
+use crate::networking::packets::play::client_tick_end_request::ClientTickEndRequestPacket;
+use crate::networking::packets::play::set_player_position_and_rotation_request::SetPlayerPositionAndRotationRequestPacket;
+use crate::networking::packets::play::set_player_position_request::SetPlayerPositionRequestPacket;
+use crate::networking::packets::play::teleport_confirmation_request::TeleportConfirmationRequestPacket;
+use crate::networking::packets::status::ping_request::PingRequestPacket;
+use crate::networking::packets::status::status_request::StatusRequestPacket;
+
+pub use nullspace_macros::Packet;
 
 pub trait Packet: Sized {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> Result<Self>;
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self>;
 }
 
+/// Decoded serverbound packet. Debug prints the decoded fields, like the capture tools do.
 #[async_trait]
-pub trait PacketHandler {
+pub trait PacketHandler: Debug {
     async fn handle(&self, context: &mut Connection) -> Result<()>;
+
+    /// Whether the packet fits the current state of its phase, checked before handling it.
+    /// Packets that are valid at any point of their phase keep the default.
+    fn is_expected(&self, context: &Connection) -> bool {
+        let _ = context;
+        true
+    }
 }
 
-type PacketBuilder = Box<dyn Fn(&mut Cursor<&[u8]>) -> Result<Box<dyn PacketHandler + Send + Sync>> + Send + Sync>;
+type PacketBuilder = Box<dyn Fn(&mut ByteReader<'_>) -> Result<Box<dyn PacketHandler + Send + Sync>> + Send + Sync>;
 
+#[derive(Default)]
 pub struct PacketRegistry {
     handlers: HashMap<(ConnectionPhase, i32), PacketBuilder>,
+    /// Id of each registered packet type, for clients encoding serverbound packets
+    ids: HashMap<(ConnectionPhase, TypeId), i32>,
+    /// Play packet ids counted against the movement rate limit, handled or not
+    movement: HashSet<i32>,
 }
 impl PacketRegistry {
     pub fn new() -> Self {
-        Self { handlers: HashMap::new() }
+        Self { handlers: HashMap::new(), ids: HashMap::new(), movement: HashSet::new() }
+    }
+
+    /// Marks play packet ids as movement, they get their own rate limit budget
+    pub fn mark_movement(&mut self, ids: impl IntoIterator<Item = i32>) {
+        self.movement.extend(ids);
+    }
+
+    pub fn is_movement(&self, phase: ConnectionPhase, id: i32) -> bool {
+        phase == ConnectionPhase::Play && self.movement.contains(&id)
     }
 
     pub fn register<P>(&mut self, phase: ConnectionPhase, id: i32)
@@ -39,9 +81,9 @@ impl PacketRegistry {
         P: Packet + PacketHandler + Send + Sync + 'static,
     {
         // The closure only needs to know how to decode P
-        let builder = Box::new(|cursor: &mut Cursor<&[u8]>| {
+        let builder = Box::new(|reader: &mut ByteReader<'_>| {
             // 1. Decode specific packet (Sync)
-            let packet = P::decode(cursor)?;
+            let packet = P::decode(reader)?;
 
             // 2. Return it as a Trait Object
             // We cast "PluginMessagePacket" into "Box<dyn PacketHandler>"
@@ -49,29 +91,108 @@ impl PacketRegistry {
         });
 
         self.handlers.insert((phase, id), builder);
+        self.ids.insert((phase, TypeId::of::<P>()), id);
     }
 
+    /// Id a packet type is registered with in a phase
+    pub fn id_of<P: 'static>(&self, phase: ConnectionPhase) -> Option<i32> {
+        self.ids.get(&(phase, TypeId::of::<P>())).copied()
+    }
+
+    /// Decodes a packet without handling it, None if the id isn't registered in the phase
+    pub fn decode(&self, phase: ConnectionPhase, id: i32, reader: &mut ByteReader<'_>) -> Option<Result<Box<dyn PacketHandler + Send + Sync>>> {
+        self.handlers.get(&(phase, id)).map(|builder| builder(reader))
+    }
+
+    /// Decodes and handles a packet, the reader must hold exactly the packet body.
+    /// Unknown ids, trailing bytes and out of order packets are protocol violations and never reach a handler.
     pub async fn handle_packet(
         &self,
         phase: ConnectionPhase,
         id: i32,
-        cursor: &mut Cursor<&[u8]>,
+        reader: &mut ByteReader<'_>,
         ctx: &mut Connection
     ) -> Result<()> {
+        let Some(builder) = self.handlers.get(&(phase, id)) else {
+            let details = format!("unknown packet 0x{:02X}", id);
+            return ctx.protocol_violation(ProtocolViolation::UnknownPacket, details).await;
+        };
 
-        if let Some(builder) = self.handlers.get(&(phase, id)) {
-            // 1. Use the builder to decode the packet from bytes
-            // Returns Box<dyn PacketHandler>
-            let packet_handler = builder(cursor)?;
+        // 1. Use the builder to decode the packet from bytes
+        // Returns Box<dyn PacketHandler>
+        let packet_handler = builder(reader)?;
 
-            // 2. Now run the handler logic
-            // Since we are awaiting immediately, the borrow checker knows 'ctx' is safe
-            packet_handler.handle(ctx).await
+        if !reader.is_empty() {
+            let details = format!("packet 0x{:02X} has {} bytes left after decoding", id, reader.remaining());
+            return ctx.protocol_violation(ProtocolViolation::TrailingBytes, details).await;
+        }
 
-        } else {
-            // Handle unknown packet
-            println!("Unknown packet: {:?}", id);
-            Ok(())
+        if !packet_handler.is_expected(ctx) {
+            let details = format!("unexpected packet 0x{:02X}", id);
+            return ctx.protocol_violation(ProtocolViolation::UnexpectedPacket, details).await;
         }
+
+        // 2. Now run the handler logic
+        // Since we are awaiting immediately, the borrow checker knows 'ctx' is safe
+        packet_handler.handle(ctx).await
     }
-}
\ No newline at end of file
+}
+
+/// One [`PacketRegistry`] per supported protocol version, serverbound ids and layouts can differ between them
+pub struct PacketRegistries {
+    registries: HashMap<ProtocolVersion, PacketRegistry>,
+}
+impl PacketRegistries {
+    /// Builds the registry of every supported version with the same registration function
+    pub fn new(register: impl Fn(ProtocolVersion, &mut PacketRegistry)) -> Self {
+        let registries = ProtocolVersion::ALL.into_iter()
+            .map(|version| {
+                let mut registry = PacketRegistry::new();
+                register(version, &mut registry);
+                (version, registry)
+            })
+            .collect();
+
+        Self { registries }
+    }
+
+    pub fn get(&self, version: ProtocolVersion) -> &PacketRegistry {
+        &self.registries[&version]
+    }
+}
+
+/// Serverbound ids are the same in protocols 773 and 774, so every version registers the same packets.
+pub fn register_all(_version: ProtocolVersion, registry: &mut PacketRegistry) {
+    // Handshake
+    registry.register::<HandshakePacket>(ConnectionPhase::Handshaking, 0x00);
+
+    // Status
+    registry.register::<StatusRequestPacket>(ConnectionPhase::Status, 0x00);
+    registry.register::<PingRequestPacket>(ConnectionPhase::Status, 0x01);
+
+    // Login
+    registry.register::<LoginStartRequestPacket>(ConnectionPhase::Login, 0x00);
+    registry.register::<LoginAcknowledgedRequestPacket>(ConnectionPhase::Login, 0x03);
+
+    // Configuration
+    registry.register::<ClientInformationRequestPacket>(ConnectionPhase::Configuration, 0x00);
+    registry.register::<PluginMessageRequestPacket>(ConnectionPhase::Configuration, 0x02);
+    registry.register::<AcknowledgeFinishConfigurationRequestPacket>(ConnectionPhase::Configuration, 0x03);
+    registry.register::<ResourcePackResponseRequestPacket>(ConnectionPhase::Configuration, 0x06);
+    registry.register::<KnownPacksRequestPacket>(ConnectionPhase::Configuration, 0x07);
+
+    // Play
+    registry.register::<TeleportConfirmationRequestPacket>(ConnectionPhase::Play, 0x00);
+    registry.register::<ChatMessageRequestPacket>(ConnectionPhase::Play, 0x08);
+    registry.register::<ChunkBatchReceivedRequestPacket>(ConnectionPhase::Play, 0x0A);
+    registry.register::<ClientTickEndRequestPacket>(ConnectionPhase::Play, 0x0C);
+    registry.register::<ClientInformationRequestPacket>(ConnectionPhase::Play, 0x0D);
+    registry.register::<AcknowledgeConfigurationRequestPacket>(ConnectionPhase::Play, 0x0F);
+    registry.register::<PluginMessageRequestPacket>(ConnectionPhase::Play, 0x15);
+    registry.register::<SetPlayerPositionRequestPacket>(ConnectionPhase::Play, 0x1D);
+    registry.register::<SetPlayerPositionAndRotationRequestPacket>(ConnectionPhase::Play, 0x1E);
+    registry.register::<ResourcePackResponseRequestPacket>(ConnectionPhase::Play, 0x30);
+
+    // Set player position, position and rotation, rotation, movement flags, move vehicle and paddle boat
+    registry.mark_movement(0x1D..=0x22);
+}
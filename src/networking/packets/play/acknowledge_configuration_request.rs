@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::data_types::PacketWrite;
+use crate::networking::packets::{Packet, PacketHandler};
+use crate::networking::packets::configuration::begin_configuration::begin_configuration;
+
+#[derive(Debug, Packet, PacketWrite)]
+pub struct AcknowledgeConfigurationRequestPacket {}
+
+#[async_trait]
+impl PacketHandler for AcknowledgeConfigurationRequestPacket {
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
+        println!("Handling acknowledge configuration request...");
+
+        println!("Switching back to CONFIGURATION phase");
+        ctx.switch_phase(ConnectionPhase::Configuration)?;
+
+        if let Some(mut player) = ctx.player_mut() {
+            player.configuration_requested = false;
+        }
+
+        // Restart the known packs negotiation, from there the regular flow
+        // sends the registries and "Finish configuration - 0x03"
+        begin_configuration(ctx).await?;
+
+        Ok(())
+    }
+
+    /// Only valid after "Start configuration" was sent
+    fn is_expected(&self, ctx: &Connection) -> bool {
+        ctx.player_mut().is_some_and(|player| player.configuration_requested)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Opens and closes a bundle, the client applies the packets in between in the same tick.
+/// Sent through [`crate::networking::writer::PacketBundle`].
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct BundleDelimiterResponsePacket {}
+
+impl ClientboundPacket for BundleDelimiterResponsePacket {
+    const NAME: &'static str = "minecraft:bundle_delimiter";
+}
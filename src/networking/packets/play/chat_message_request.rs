@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::bit_set::FixedBitSet;
+use crate::networking::data_types::i_byte::Byte;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::{Packet, PacketHandler};
+use crate::networking::packets::play::system_chat_response::SystemChatResponsePacket;
+use crate::world;
+
+/// "Chat message - 0x08". Without chat sessions the signature is ignored
+/// and the message is relayed to everyone as system chat.
+#[derive(Debug, Packet, PacketWrite)]
+pub struct ChatMessageRequestPacket {
+    #[field(max_length = 256)]
+    pub message: String,
+    pub timestamp: i64,
+    pub salt: i64,
+    pub signature: Option<[u8; 256]>,
+    pub message_count: VarInt,
+    /// Which of the last 20 messages the client has seen
+    pub acknowledged: FixedBitSet<20>,
+    pub checksum: Byte,
+}
+
+#[async_trait]
+impl PacketHandler for ChatMessageRequestPacket {
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
+        let Some(username) = ctx.player_mut().map(|player| player.account.username.clone()) else {
+            return Ok(());
+        };
+
+        println!("<{}> {}", username, self.message);
+
+        let packet = SystemChatResponsePacket { content: format!("<{}> {}", username, self.message).into(), overlay: false };
+        world::broadcast(&ctx.player_list, &packet, |player| player.phase == ConnectionPhase::Play);
+
+        Ok(())
+    }
+}
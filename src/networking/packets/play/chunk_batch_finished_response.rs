@@ -0,0 +1,13 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Ends a batch, the client answers with "Chunk batch received" and the rate it wants chunks at
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct ChunkBatchFinishedResponsePacket {
+    pub batch_size: VarInt,
+}
+
+impl ClientboundPacket for ChunkBatchFinishedResponsePacket {
+    const NAME: &'static str = "minecraft:chunk_batch_finished";
+}
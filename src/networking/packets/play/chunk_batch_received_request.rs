@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::PacketWrite;
+use crate::networking::packets::{Packet, PacketHandler};
+
+/// "Chunk batch received - 0x0A", the answer to a finished batch with the rate the client can take
+#[derive(Debug, Packet, PacketWrite)]
+pub struct ChunkBatchReceivedRequestPacket {
+    pub chunks_per_tick: f32,
+}
+
+#[async_trait]
+impl PacketHandler for ChunkBatchReceivedRequestPacket {
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
+        if let Some(mut player) = ctx.player_mut()
+            && let Some(sender) = &mut player.chunk_sender {
+            sender.on_batch_received(self.chunks_per_tick);
+        }
+
+        Ok(())
+    }
+}
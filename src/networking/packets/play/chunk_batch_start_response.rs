@@ -0,0 +1,10 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Sent before the chunks of a batch, the client times the batch from it
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct ChunkBatchStartResponsePacket {}
+
+impl ClientboundPacket for ChunkBatchStartResponsePacket {
+    const NAME: &'static str = "minecraft:chunk_batch_start";
+}
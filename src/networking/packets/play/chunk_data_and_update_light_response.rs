@@ -0,0 +1,83 @@
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::bit_set::BitSet;
+use crate::networking::data_types::nbt::Nbt;
+use crate::networking::data_types::u_byte::UnsignedByte;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::world::chunk::light::LightSection;
+
+/// A chunk column and its light, built from a chunk with [`crate::world::chunk::Chunk::to_packet`]
+#[derive(PacketWrite)]
+pub struct ChunkDataAndUpdateLightResponsePacket {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub data: ChunkData,
+    pub light: LightData,
+}
+
+impl ClientboundPacket for ChunkDataAndUpdateLightResponsePacket {
+    const NAME: &'static str = "minecraft:level_chunk_with_light";
+}
+
+pub struct ChunkData {
+    pub heightmaps: Vec<Heightmap>,
+    /// Encoded sections from the bottom of the world up
+    pub sections: Vec<u8>,
+    pub block_entities: Vec<ChunkBlockEntity>,
+}
+
+/// The sections are a length prefixed byte array
+impl PacketWrite for ChunkData {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.heightmaps.write_to(buf);
+        VarInt(self.sections.len() as i32).write_to(buf);
+        buf.extend_from_slice(&self.sections);
+        self.block_entities.write_to(buf);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeightmapType {
+    WorldSurfaceWorldgen,
+    WorldSurface,
+    OceanFloorWorldgen,
+    OceanFloor,
+    MotionBlocking,
+    MotionBlockingNoLeaves,
+}
+
+impl PacketWrite for HeightmapType {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        VarInt(*self as i32).write_to(buf);
+    }
+}
+
+/// Height above the bottom of the world of the block over the highest one matching the type, per column.
+/// Packed like paletted containers, with enough bits for the world height plus one.
+#[derive(Debug, Clone, PartialEq, PacketWrite)]
+pub struct Heightmap {
+    pub heightmap_type: HeightmapType,
+    pub data: Vec<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, PacketWrite)]
+pub struct ChunkBlockEntity {
+    /// Block x in the high 4 bits, z in the low 4 bits
+    pub packed_xz: UnsignedByte,
+    pub y: i16,
+    pub block_entity_type: VarInt,
+    pub data: Nbt,
+}
+
+/// Light of the sections of a chunk column, including the one below and the one above the world.
+/// Bit `i` of a mask is the section `i` from the bottom, sections with a bit in the light masks
+/// have their levels in the arrays in that order, the ones of the empty masks are all 0.
+#[derive(Clone, Default, PartialEq, PacketWrite)]
+pub struct LightData {
+    pub sky_light_mask: BitSet,
+    pub block_light_mask: BitSet,
+    pub empty_sky_light_mask: BitSet,
+    pub empty_block_light_mask: BitSet,
+    pub sky_light: Vec<LightSection>,
+    pub block_light: Vec<LightSection>,
+}
@@ -0,0 +1,23 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::u_byte::UnsignedByte;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Change of the game state, e.g. the weather, the game mode or the credits being shown
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct GameEventResponsePacket {
+    pub event: UnsignedByte,
+    pub value: f32,
+}
+
+impl ClientboundPacket for GameEventResponsePacket {
+    const NAME: &'static str = "minecraft:game_event";
+}
+
+impl GameEventResponsePacket {
+    pub const START_WAITING_FOR_CHUNKS: u8 = 13;
+
+    /// Tells the client the chunks around it are coming, it leaves the loading screen once they're in
+    pub fn start_waiting_for_chunks() -> GameEventResponsePacket {
+        GameEventResponsePacket { event: UnsignedByte(Self::START_WAITING_FOR_CHUNKS), value: 0.0 }
+    }
+}
@@ -1,14 +1,14 @@
 use std::time::{SystemTime, UNIX_EPOCH};
-use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct KeepAliveResponsePacket {
     pub keep_alive_id: i64,
 }
 
-impl PacketWrite for KeepAliveResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.keep_alive_id);
-    }
+impl ClientboundPacket for KeepAliveResponsePacket {
+    const NAME: &'static str = "minecraft:keep_alive";
 }
 
 impl KeepAliveResponsePacket {
@@ -20,4 +20,10 @@ impl KeepAliveResponsePacket {
                 .as_millis() as i64,
         }
     }
-}
\ No newline at end of file
+}
+
+impl Default for KeepAliveResponsePacket {
+    fn default() -> Self {
+        Self::new()
+    }
+}
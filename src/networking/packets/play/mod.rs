@@ -3,4 +3,17 @@ pub mod synchronize_player_position_response;
 pub mod teleport_confirmation_request;
 pub mod set_player_position_and_rotation_request;
 pub mod client_tick_end_request;
-pub mod keep_alive_response;
\ No newline at end of file
+pub mod keep_alive_response;
+pub mod start_configuration_response;
+pub mod acknowledge_configuration_request;pub mod bundle_delimiter_response;
+pub mod chat_message_request;
+pub mod system_chat_response;
+pub mod chunk_data_and_update_light_response;
+pub mod set_center_chunk_response;
+pub mod chunk_batch_start_response;
+pub mod chunk_batch_finished_response;
+pub mod chunk_batch_received_request;
+pub mod unload_chunk_response;
+pub mod game_event_response;
+pub mod set_player_position_request;
+pub mod set_container_slot_response;
@@ -0,0 +1,14 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Chunk the player is in, the client only keeps the chunks within its view distance around it
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct SetCenterChunkResponsePacket {
+    pub chunk_x: VarInt,
+    pub chunk_z: VarInt,
+}
+
+impl ClientboundPacket for SetCenterChunkResponsePacket {
+    const NAME: &'static str = "minecraft:set_chunk_cache_center";
+}
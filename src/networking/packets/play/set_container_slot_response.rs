@@ -0,0 +1,27 @@
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::item_stack::ItemStack;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// Sets an item in a slot of a window, 0 being the player's inventory
+#[derive(Debug, PacketWrite)]
+pub struct SetContainerSlotResponsePacket {
+    pub window_id: VarInt,
+    /// Last state id the server sent for the window
+    pub state_id: VarInt,
+    pub slot: i16,
+    pub item: ItemStack,
+}
+
+impl ClientboundPacket for SetContainerSlotResponsePacket {
+    const NAME: &'static str = "minecraft:container_set_slot";
+
+    /// Data component ids moved in 1.21.11, the item is written with the ones of the client's version
+    fn write_for(&self, version: ProtocolVersion, buf: &mut Vec<u8>) -> anyhow::Result<()> {
+        self.window_id.write_to(buf);
+        self.state_id.write_to(buf);
+        self.slot.write_to(buf);
+        self.item.write_for(version, buf)
+    }
+}
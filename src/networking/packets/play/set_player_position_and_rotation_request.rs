@@ -1,10 +1,11 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::Connection;
-use crate::networking::data_types::BufferReadExt;
 use crate::networking::data_types::i_byte::Byte;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
+use crate::world::entities::player::Player;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct SetPlayerPositionAndRotationRequestPacket {
     pub x: f64,
     pub feet_y: f64,
@@ -14,30 +15,16 @@ pub struct SetPlayerPositionAndRotationRequestPacket {
     pub flags: Byte,
 }
 
-impl Packet for SetPlayerPositionAndRotationRequestPacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let x: f64 = cursor.read_field()?;
-        let feet_y: f64 = cursor.read_field()?;
-        let z: f64 = cursor.read_field()?;
-        let yaw: f32 = cursor.read_field()?;
-        let pitch: f32 = cursor.read_field()?;
-        let flags: Byte = cursor.read_field()?;
-
-        Ok(SetPlayerPositionAndRotationRequestPacket { x, feet_y, z, yaw, pitch, flags })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for SetPlayerPositionAndRotationRequestPacket {
-    async fn handle(&self, _ctx: &mut Connection) -> anyhow::Result<()> {
-        println!("Handling set player position and rotation request...");
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
+        let Some(position) = Player::clamp_position(self.x, self.feet_y, self.z) else {
+            return ctx.kick("Invalid move player packet received").await;
+        };
 
-        println!("X: {:?}", self.x);
-        println!("Feet Y: {:?}", self.feet_y);
-        println!("Z: {:?}", self.z);
-        println!("Yaw: {:?}", self.yaw);
-        println!("Pitch: {:?}", self.pitch);
-        println!("Flags: {:?}", self.flags);
+        if let Some(mut player) = ctx.player_mut() {
+            player.position = position;
+        }
 
         Ok(())
     }
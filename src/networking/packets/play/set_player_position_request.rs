@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use crate::networking::connection::Connection;
+use crate::networking::data_types::i_byte::Byte;
+use crate::networking::data_types::PacketWrite;
+use crate::networking::packets::{Packet, PacketHandler};
+use crate::world::entities::player::Player;
+
+/// "Set player position - 0x1D", movement without turning
+#[derive(Debug, Packet, PacketWrite)]
+pub struct SetPlayerPositionRequestPacket {
+    pub x: f64,
+    pub feet_y: f64,
+    pub z: f64,
+    pub flags: Byte,
+}
+
+#[async_trait]
+impl PacketHandler for SetPlayerPositionRequestPacket {
+    async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
+        let Some(position) = Player::clamp_position(self.x, self.feet_y, self.z) else {
+            return ctx.kick("Invalid move player packet received").await;
+        };
+
+        if let Some(mut player) = ctx.player_mut() {
+            player.position = position;
+        }
+
+        Ok(())
+    }
+}
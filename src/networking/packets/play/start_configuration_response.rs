@@ -0,0 +1,11 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Sent during play to move the client back into the configuration phase.
+/// The client stops sending play packets and answers with "Acknowledge configuration - 0x0F".
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct StartConfigurationResponsePacket {}
+
+impl ClientboundPacket for StartConfigurationResponsePacket {
+    const NAME: &'static str = "minecraft:start_configuration";
+}
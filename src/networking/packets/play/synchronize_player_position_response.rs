@@ -1,6 +1,9 @@
-use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::data_types::teleport_flags::TeleportFlags;
 use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct SynchronizePlayerPositionResponsePacket {
     pub teleport_id: VarInt,
     pub x: f64,
@@ -11,22 +14,11 @@ pub struct SynchronizePlayerPositionResponsePacket {
     pub velocity_z: f64,
     pub yaw: f32,
     pub pitch: f32,
-    pub flags: i32
+    pub flags: TeleportFlags,
 }
 
-impl PacketWrite for SynchronizePlayerPositionResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.teleport_id);
-        buf.write_type(self.x);
-        buf.write_type(self.y);
-        buf.write_type(self.z);
-        buf.write_type(self.velocity_x);
-        buf.write_type(self.velocity_y);
-        buf.write_type(self.velocity_z);
-        buf.write_type(self.yaw);
-        buf.write_type(self.pitch);
-        buf.write_type(self.flags);
-    }
+impl ClientboundPacket for SynchronizePlayerPositionResponsePacket {
+    const NAME: &'static str = "minecraft:player_position";
 }
 
 impl SynchronizePlayerPositionResponsePacket {
@@ -41,7 +33,7 @@ impl SynchronizePlayerPositionResponsePacket {
             velocity_z: 0.0,
             yaw: 0.0,
             pitch: 0.0,
-            flags: 0,
+            flags: TeleportFlags::ABSOLUTE,
         }
     }
 }
\ No newline at end of file
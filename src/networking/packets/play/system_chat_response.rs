@@ -0,0 +1,14 @@
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Unsigned message shown in the chat, or above the hotbar with `overlay`
+#[derive(PacketWrite)]
+pub struct SystemChatResponsePacket {
+    pub content: TextComponent,
+    pub overlay: bool,
+}
+
+impl ClientboundPacket for SystemChatResponsePacket {
+    const NAME: &'static str = "minecraft:system_chat";
+}
@@ -1,22 +1,14 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::Connection;
-use crate::networking::data_types::BufferReadExt;
 use crate::networking::data_types::var_int::VarInt;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct TeleportConfirmationRequestPacket {
     pub teleport_id: VarInt,
 }
 
-impl Packet for TeleportConfirmationRequestPacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let teleport_id: VarInt = cursor.read_field()?;
-
-        Ok(TeleportConfirmationRequestPacket { teleport_id })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for TeleportConfirmationRequestPacket {
     async fn handle(&self, _ctx: &mut Connection) -> anyhow::Result<()> {
@@ -0,0 +1,13 @@
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
+
+/// Chunk the client has to forget. Written as a chunk position long, so z comes before x.
+#[derive(Debug, FieldRead, PacketWrite)]
+pub struct UnloadChunkResponsePacket {
+    pub chunk_z: i32,
+    pub chunk_x: i32,
+}
+
+impl ClientboundPacket for UnloadChunkResponsePacket {
+    const NAME: &'static str = "minecraft:forget_level_chunk";
+}
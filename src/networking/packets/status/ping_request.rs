@@ -1,22 +1,14 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use crate::networking::connection::{Connection};
-use crate::networking::data_types::BufferReadExt;
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
 use crate::networking::packets::status::pong_response::PongResponsePacket;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct PingRequestPacket {
     pub timestamp: i64,
 }
 
-impl Packet for PingRequestPacket {
-    fn decode(cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        let timestamp: i64 = cursor.read_field()?;
-
-        Ok(PingRequestPacket { timestamp })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for PingRequestPacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
@@ -28,7 +20,7 @@ impl PacketHandler for PingRequestPacket {
         };
 
         // Send Packet 0x01 (Response)
-        ctx.send_packet(0x01, response).await?;
+        ctx.send_packet(response).await?;
 
         // Close the connection
         ctx.close().await?;
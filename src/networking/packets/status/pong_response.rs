@@ -1,11 +1,11 @@
-use crate::networking::data_types::{BufferWrite, PacketWrite};
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct PongResponsePacket {
     pub timestamp: i64,
 }
 
-impl PacketWrite for PongResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.timestamp);
-    }
-}
\ No newline at end of file
+impl ClientboundPacket for PongResponsePacket {
+    const NAME: &'static str = "minecraft:pong_response";
+}
@@ -1,18 +1,14 @@
-use std::io::Cursor;
 use async_trait::async_trait;
 use serde_json::json;
 use crate::networking::connection::{Connection};
+use crate::networking::data_types::PacketWrite;
 use crate::networking::packets::{Packet, PacketHandler};
 use crate::networking::packets::status::status_response::StatusResponsePacket;
+use crate::networking::protocol_version::ProtocolVersion;
 
+#[derive(Debug, Packet, PacketWrite)]
 pub struct StatusRequestPacket {}
 
-impl Packet for StatusRequestPacket {
-    fn decode(_cursor: &mut Cursor<&[u8]>) -> anyhow::Result<Self> {
-        Ok(StatusRequestPacket { })
-    }
-}
-
 #[async_trait]
 impl PacketHandler for StatusRequestPacket {
     async fn handle(&self, ctx: &mut Connection) -> anyhow::Result<()> {
@@ -20,8 +16,8 @@ impl PacketHandler for StatusRequestPacket {
 
         let status_response = json!({
             "version": {
-                "name": "1.21.11",
-                "protocol": 774
+                "name": ProtocolVersion::supported_range(),
+                "protocol": ctx.protocol.protocol()
             },
             "players": {
                 "max": 1000,
@@ -45,7 +41,7 @@ impl PacketHandler for StatusRequestPacket {
         };
 
         // Send Packet 0x00 (Response)
-        ctx.send_packet(0x00, response).await?;
+        ctx.send_packet(response).await?;
 
         Ok(())
     }
@@ -1,46 +1,11 @@
-use serde::Serialize;
-use crate::networking::data_types::{BufferWrite, PacketWrite};
-use crate::networking::data_types::var_int::VarInt;
-
-#[derive(Serialize)]
-struct StatusResponsePayload {
-    version: VersionInfo,
-    players: PlayerInfo,
-    description: DescriptionInfo,
-    //favicon: Option<String>, TODO
-    //enforcesSecureChat: Option<bool>, TODO
-}
-
-#[derive(Serialize)]
-struct VersionInfo {
-    name: String,
-    protocol: VarInt,
-}
-
-#[derive(Serialize)]
-struct PlayerInfo {
-    max: VarInt,
-    online: VarInt,
-    sample: Option<Vec<PlayerSample>>,
-}
-
-#[derive(Serialize)]
-struct PlayerSample {
-    name: String,
-    id: String,
-}
-
-#[derive(Serialize)]
-struct DescriptionInfo {
-    text: String,
-}
+use crate::networking::data_types::{FieldRead, PacketWrite};
+use crate::networking::packets::clientbound::ClientboundPacket;
 
+#[derive(Debug, FieldRead, PacketWrite)]
 pub struct StatusResponsePacket {
     pub json_response: String,
 }
 
-impl PacketWrite for StatusResponsePacket {
-    fn write_to(&self, buf: &mut Vec<u8>) {
-        buf.write_type(self.json_response.clone());
-    }
-}
\ No newline at end of file
+impl ClientboundPacket for StatusResponsePacket {
+    const NAME: &'static str = "minecraft:status_response";
+}
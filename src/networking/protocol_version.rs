@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// Protocol versions the server accepts.
+/// The handshake picks one for the whole connection, packet ids and layouts are selected from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProtocolVersion {
+    /// Protocol 773, shared by 1.21.9 and 1.21.10
+    V1_21_9,
+    /// Protocol 774, 1.21.11
+    V1_21_11,
+}
+
+impl ProtocolVersion {
+    /// Every supported version, oldest first
+    pub const ALL: [ProtocolVersion; 2] = [ProtocolVersion::V1_21_9, ProtocolVersion::V1_21_11];
+
+    /// Version the server is built against, the registries folder comes from it
+    pub const LATEST: ProtocolVersion = ProtocolVersion::V1_21_11;
+
+    /// Supported version for a protocol number sent in the handshake
+    pub fn from_protocol(protocol: i32) -> Option<ProtocolVersion> {
+        Self::ALL.into_iter().find(|version| version.protocol() == protocol)
+    }
+
+    pub fn protocol(self) -> i32 {
+        match self {
+            ProtocolVersion::V1_21_9 => 773,
+            ProtocolVersion::V1_21_11 => 774,
+        }
+    }
+
+    /// Release names sharing this protocol number
+    pub fn releases(self) -> &'static [&'static str] {
+        match self {
+            ProtocolVersion::V1_21_9 => &["1.21.9", "1.21.10"],
+            ProtocolVersion::V1_21_11 => &["1.21.11"],
+        }
+    }
+
+    /// Range of releases the server accepts, e.g. "1.21.9-1.21.11"
+    pub fn supported_range() -> String {
+        let oldest = Self::ALL[0].releases()[0];
+        let newest = Self::LATEST.releases().last().copied().unwrap_or_default();
+        format!("{}-{}", oldest, newest)
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.releases().join("/"), self.protocol())
+    }
+}
@@ -0,0 +1,187 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use crate::config::RateLimitConfig;
+
+/// Time source of the limiters. Tests use a manual clock and move it forward instead of sleeping.
+#[derive(Debug, Clone)]
+pub enum Clock {
+    System,
+    Manual(Arc<Mutex<Instant>>),
+}
+
+impl Clock {
+    /// Manual clock starting at the current time
+    pub fn manual() -> Clock {
+        Clock::Manual(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    pub fn now(&self) -> Instant {
+        match self {
+            Clock::System => Instant::now(),
+            Clock::Manual(now) => *now.lock(),
+        }
+    }
+
+    /// Moves a manual clock forward, the system clock can't be moved
+    pub fn advance(&self, duration: Duration) {
+        if let Clock::Manual(now) = self {
+            *now.lock() += duration;
+        }
+    }
+}
+
+/// Allows `per_second` events on average, with bursts of up to `burst` events
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    per_second: f64,
+    last_refill: Instant,
+    clock: Clock,
+}
+
+impl TokenBucket {
+    pub fn new(per_second: f64, burst: f64) -> TokenBucket {
+        TokenBucket::with_clock(per_second, burst, Clock::System)
+    }
+
+    pub fn with_clock(per_second: f64, burst: f64, clock: Clock) -> TokenBucket {
+        TokenBucket { capacity: burst, tokens: burst, per_second, last_refill: clock.now(), clock }
+    }
+
+    /// Takes a token if there is one left
+    pub fn try_take(&mut self) -> bool {
+        self.refill();
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+
+    /// Whether the bucket refilled completely, it then behaves like a new one
+    pub fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.capacity
+    }
+
+    fn refill(&mut self) {
+        let now = self.clock.now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Outcome of a new connection going through the [`IpThrottle`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleResult {
+    Allowed,
+    Throttled,
+    Banned,
+}
+
+/// Connection throttle and temporary bans by client IP,
+/// shared by the accept loop and the connections
+pub struct IpThrottle {
+    per_second: f64,
+    burst: f64,
+    exempt_loopback: bool,
+    buckets: DashMap<IpAddr, TokenBucket>,
+    bans: DashMap<IpAddr, Instant>,
+    clock: Clock,
+}
+
+impl IpThrottle {
+    /// Above this many tracked addresses the idle ones are forgotten
+    const PRUNE_THRESHOLD: usize = 4096;
+
+    pub fn new(config: &RateLimitConfig) -> IpThrottle {
+        IpThrottle::with_clock(config, Clock::System)
+    }
+
+    pub fn with_clock(config: &RateLimitConfig, clock: Clock) -> IpThrottle {
+        IpThrottle {
+            per_second: config.connections_per_second,
+            burst: config.connection_burst,
+            exempt_loopback: config.exempt_loopback,
+            buckets: DashMap::new(),
+            bans: DashMap::new(),
+            clock,
+        }
+    }
+
+    /// Checks a new connection from an address, counting it against its budget
+    pub fn check(&self, ip: IpAddr) -> ThrottleResult {
+        if self.is_banned(ip) {
+            return ThrottleResult::Banned;
+        }
+
+        if self.exempt_loopback && ip.is_loopback() {
+            return ThrottleResult::Allowed;
+        }
+
+        if self.buckets.len() > Self::PRUNE_THRESHOLD {
+            self.buckets.retain(|_, bucket| !bucket.is_full());
+        }
+
+        let allowed = self.buckets.entry(ip)
+            .or_insert_with(|| TokenBucket::with_clock(self.per_second, self.burst, self.clock.clone()))
+            .try_take();
+
+        if allowed { ThrottleResult::Allowed } else { ThrottleResult::Throttled }
+    }
+
+    /// Refuses every connection from an address for a while
+    pub fn ban(&self, ip: IpAddr, duration: Duration) {
+        println!("Banning {} for {} seconds", ip, duration.as_secs());
+        self.bans.insert(ip, self.clock.now() + duration);
+    }
+
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        let Some(until) = self.bans.get(&ip).map(|until| *until) else {
+            return false;
+        };
+
+        if until > self.clock.now() {
+            return true;
+        }
+
+        self.bans.remove(&ip);
+        false
+    }
+}
+
+/// Packet budgets of a single connection. Movement is sent every tick while the player moves,
+/// so it gets its own budget and can't starve the other packets.
+pub struct PacketRateLimiter {
+    movement: TokenBucket,
+    other: TokenBucket,
+}
+
+impl PacketRateLimiter {
+    pub fn new(config: &RateLimitConfig) -> PacketRateLimiter {
+        PacketRateLimiter::with_clock(config, Clock::System)
+    }
+
+    pub fn with_clock(config: &RateLimitConfig, clock: Clock) -> PacketRateLimiter {
+        PacketRateLimiter {
+            movement: TokenBucket::with_clock(config.movement_per_second, config.movement_burst, clock.clone()),
+            other: TokenBucket::with_clock(config.packets_per_second, config.packets_burst, clock),
+        }
+    }
+
+    /// Takes a token from the budget of the packet, false if it ran out
+    pub fn allow(&mut self, is_movement: bool) -> bool {
+        if is_movement {
+            self.movement.try_take()
+        } else {
+            self.other.try_take()
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+use crate::config::{ResourcePackConfig, ResourcePackServerConfig};
+
+/// Biggest request head we accept, the client only sends a GET with a few headers
+const MAX_REQUEST_SIZE: usize = 8192;
+/// Pack bytes written at once, the client has the request timeout to take each chunk
+const WRITE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Zip file served by the embedded HTTP server
+#[derive(Debug, Clone)]
+pub struct HostedPack {
+    /// Percent-encoded file name, used as the request path
+    pub path: String,
+    pub file: PathBuf,
+    /// Derived from the hash, so it only changes when the file does
+    pub id: Uuid,
+    /// SHA-1 of the file as 40 hex characters
+    pub hash: String,
+}
+
+impl HostedPack {
+    /// Builds the pack entry that gets pushed to the players
+    pub fn to_pack_config(&self, config: &ResourcePackServerConfig) -> ResourcePackConfig {
+        ResourcePackConfig {
+            id: self.id,
+            url: format!("{}/{}", config.public_url.trim_end_matches('/'), self.path),
+            hash: self.hash.clone(),
+            required: config.required,
+            prompt: config.prompt.clone(),
+        }
+    }
+}
+
+/// Finds every zip file in the configured directory and computes its SHA-1
+pub fn scan_packs(config: &ResourcePackServerConfig) -> anyhow::Result<Vec<HostedPack>> {
+    let mut packs = Vec::new();
+
+    if !config.directory.exists() {
+        std::fs::create_dir_all(&config.directory)?;
+    }
+
+    for entry in std::fs::read_dir(&config.directory)? {
+        let file = entry?.path();
+
+        let is_zip = file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+        if !file.is_file() || !is_zip {
+            continue;
+        }
+
+        let Some(name) = file.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        let data = std::fs::read(&file)?;
+        let digest = sha1_smol::Sha1::from(&data).digest();
+        let hash = digest.to_string();
+
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&digest.bytes()[..16]);
+
+        println!("Hosting resource pack {} (sha1 {})", name, hash);
+        packs.push(HostedPack { path: percent_encode(name), file, id: Uuid::from_bytes(id), hash });
+    }
+
+    packs.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(packs)
+}
+
+/// Starts the HTTP listener serving the scanned packs, returning the address it's bound to
+pub async fn start(config: &ResourcePackServerConfig, packs: &[HostedPack]) -> anyhow::Result<SocketAddr> {
+    let listener = TcpListener::bind(&config.bind).await?;
+    let address = listener.local_addr()?;
+    println!("Resource pack server running on {}", address);
+    let request_timeout = Duration::from_secs(config.request_timeout_secs);
+
+    let files: Arc<HashMap<String, PathBuf>> = Arc::new(
+        packs.iter().map(|pack| (pack.path.clone(), pack.file.clone())).collect()
+    );
+
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    eprintln!("Resource pack server accept error: {:?}", e);
+                    continue;
+                }
+            };
+
+            let files_ref = files.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_request(socket, &files_ref, request_timeout).await {
+                    eprintln!("Resource pack server request error: {:?}", e);
+                }
+            });
+        }
+    });
+
+    Ok(address)
+}
+
+async fn handle_request(mut socket: TcpStream, files: &HashMap<String, PathBuf>, request_timeout: Duration) -> anyhow::Result<()> {
+    // A client that never finishes its request would hold the task and socket forever
+    let head = tokio::time::timeout(request_timeout, read_head(&mut socket)).await
+        .map_err(|_| anyhow::anyhow!("No request received within {:?}", request_timeout))??;
+    let Some(head) = head else {
+        return Ok(());
+    };
+
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default().trim_start_matches('/');
+
+    if method != "GET" && method != "HEAD" {
+        return write_status(&mut socket, "405 Method Not Allowed", request_timeout).await;
+    }
+
+    let Some(file_path) = files.get(path) else {
+        return write_status(&mut socket, "404 Not Found", request_timeout).await;
+    };
+
+    let mut file = File::open(file_path).await?;
+    let length = file.metadata().await?.len();
+
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/zip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        length
+    );
+    write_timed(&mut socket, header.as_bytes(), request_timeout).await?;
+
+    // Copied a chunk at a time, so a client that stops reading is dropped instead of holding the file open.
+    // A whole response deadline would cut off big packs on slow connections.
+    if method == "GET" {
+        let mut chunk = vec![0u8; WRITE_CHUNK_SIZE];
+        loop {
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            write_timed(&mut socket, &chunk[..read], request_timeout).await?;
+        }
+    }
+
+    tokio::time::timeout(request_timeout, socket.shutdown()).await??;
+    Ok(())
+}
+
+/// Writes to the client, failing if it doesn't take the bytes within the timeout
+async fn write_timed(socket: &mut TcpStream, bytes: &[u8], timeout: Duration) -> anyhow::Result<()> {
+    tokio::time::timeout(timeout, socket.write_all(bytes)).await
+        .map_err(|_| anyhow::anyhow!("Client didn't read the response within {:?}", timeout))??;
+    Ok(())
+}
+
+/// Reads the request head, we don't care about the headers.
+/// None if the client closed the connection or sent a head over the size limit.
+async fn read_head(socket: &mut TcpStream) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut head = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 || head.len() + read > MAX_REQUEST_SIZE {
+            return Ok(None);
+        }
+        head.extend_from_slice(&chunk[..read]);
+    }
+
+    Ok(Some(head))
+}
+
+async fn write_status(socket: &mut TcpStream, status: &str, timeout: Duration) -> anyhow::Result<()> {
+    let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+    write_timed(socket, response.as_bytes(), timeout).await?;
+    tokio::time::timeout(timeout, socket.shutdown()).await??;
+    Ok(())
+}
+
+/// Encodes everything but the URL unreserved characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
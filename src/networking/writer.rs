@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::io::IoSlice;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tokio::io::AsyncWriteExt;
+use tokio::net::tcp::OwnedWriteHalf;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+use crate::networking::buffer_pool::{self, PooledFrame};
+use crate::networking::capture::{Direction, PacketCapture};
+use crate::networking::connection::ConnectionPhase;
+use crate::networking::data_types::BufferWrite;
+use crate::networking::data_types::text_component::TextComponent;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::clientbound::{require_clientbound_id, ClientboundPacket};
+use crate::networking::packets::common::disconnect_response::DisconnectResponsePacket;
+use crate::networking::packets::login::disconnect_login_response::DisconnectLoginResponsePacket;
+use crate::networking::packets::play::bundle_delimiter_response::BundleDelimiterResponsePacket;
+use crate::networking::metrics::{self, Rejection};
+use crate::networking::protocol_version::ProtocolVersion;
+
+/// Frames written in a single vectored write at most
+const MAX_BATCH_FRAMES: usize = 64;
+/// Bytes written in a single vectored write at most, a frame above it is written on its own
+const MAX_BATCH_BYTES: usize = 64 * 1024;
+
+pub enum NetMessage {
+    SendPacket(PooledFrame),
+    /// Frame encoded once and shared by every connection it's broadcast to
+    SendShared(Arc<[u8]>),
+    /// Last frame of a kicked connection, everything queued before it is discarded
+    Kick(PooledFrame),
+    Disconnect,
+}
+
+/// Counters shared by the senders of a connection and its writer task
+struct WriterState {
+    /// Bytes queued and not written to the socket yet
+    queued_bytes: AtomicUsize,
+    /// Set once the connection is kicked or closed, no more packets are accepted or written
+    closing: AtomicBool,
+    /// Notified when the writer task ends and drops the socket
+    finished: Notify,
+}
+
+/// Sending half of a connection, cheap to clone for the player and the world.
+/// Sending never waits for the socket: the bytes queued for a client are bounded
+/// instead, and a client that doesn't read them fast enough is kicked.
+#[derive(Clone)]
+pub struct PacketWriter {
+    sender: mpsc::UnboundedSender<NetMessage>,
+    state: Arc<WriterState>,
+    max_queued_bytes: usize,
+    capture: Option<PacketCapture>,
+}
+
+impl PacketWriter {
+    /// Spawns the writer task of a socket
+    pub fn spawn(stream: OwnedWriteHalf, max_queued_bytes: usize, write_timeout: Duration, capture: Option<PacketCapture>) -> PacketWriter {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let state = Arc::new(WriterState { queued_bytes: AtomicUsize::new(0), closing: AtomicBool::new(false), finished: Notify::new() });
+
+        tokio::spawn(handle_writes(stream, receiver, state.clone(), write_timeout));
+
+        PacketWriter { sender, state, max_queued_bytes, capture }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state.closing.load(Ordering::Relaxed) || self.sender.is_closed()
+    }
+
+    /// Waits for the writer task to end. Only the connection may wait on it, a single waiter is woken.
+    pub async fn closed(&self) {
+        self.state.finished.notified().await
+    }
+
+    /// Whether both writers send to the same socket
+    pub fn same_connection(&self, other: &PacketWriter) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+
+    pub fn queued_bytes(&self) -> usize {
+        self.state.queued_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Queues a packet with the id and layout it has in a version and phase.
+    /// A client over its byte budget is kicked with the disconnect packet of the phase.
+    pub fn send_packet<T: ClientboundPacket>(&self, version: ProtocolVersion, phase: ConnectionPhase, packet: T) -> anyhow::Result<()> {
+        let frame = encode_packet(version, phase, &packet)?;
+        self.check_budget(version, phase, frame.len())?;
+        self.capture(version, phase, &frame);
+        self.send_frame(frame)
+    }
+
+    /// Queues a bundle as a single message, so no other packet can end up between its packets
+    pub fn send_bundle(&self, bundle: PacketBundle) -> anyhow::Result<()> {
+        let version = bundle.version;
+        let frames = bundle.finish()?;
+        self.check_budget(version, ConnectionPhase::Play, frames.len())?;
+        self.capture(version, ConnectionPhase::Play, &frames);
+        self.send_frame(frames)
+    }
+
+    /// Kicks the client if queuing more bytes would go over its budget
+    fn check_budget(&self, version: ProtocolVersion, phase: ConnectionPhase, length: usize) -> anyhow::Result<()> {
+        let queued = self.queued_bytes();
+        if queued + length <= self.max_queued_bytes {
+            return Ok(());
+        }
+
+        metrics::record(Rejection::SlowConsumer);
+        println!("Kicking slow client at phase {:?}, {} bytes queued", phase, queued);
+        self.kick(version, phase, "Too slow to receive packets".into());
+        Err(anyhow::anyhow!("Client too slow, {} bytes queued", queued))
+    }
+
+    /// Queues an encoded frame (or several frames back to back), once the caller checked the budget and captured it
+    fn send_frame(&self, frame: PooledFrame) -> anyhow::Result<()> {
+        let length = frame.len();
+        self.enqueue(NetMessage::SendPacket(frame), length)
+    }
+
+    /// Queues a frame shared with other connections, like the ones of a [`SharedPacket`]
+    pub fn send_shared(&self, version: ProtocolVersion, phase: ConnectionPhase, frame: Arc<[u8]>) -> anyhow::Result<()> {
+        let length = frame.len();
+        self.check_budget(version, phase, length)?;
+        self.capture(version, phase, &frame);
+        self.enqueue(NetMessage::SendShared(frame), length)
+    }
+
+    fn enqueue(&self, message: NetMessage, length: usize) -> anyhow::Result<()> {
+        if self.state.closing.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("Connection is closing"));
+        }
+
+        self.state.queued_bytes.fetch_add(length, Ordering::Relaxed);
+
+        if self.sender.send(message).is_err() {
+            self.state.queued_bytes.fetch_sub(length, Ordering::Relaxed);
+            return Err(anyhow::anyhow!("Connection is closed"));
+        }
+
+        Ok(())
+    }
+
+    /// Discards the queued packets, sends the disconnect packet of the phase if it has one and closes the socket
+    pub fn kick(&self, version: ProtocolVersion, phase: ConnectionPhase, reason: TextComponent) {
+        if self.state.closing.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let frame = match phase {
+            ConnectionPhase::Login => encode_packet(version, phase, &DisconnectLoginResponsePacket { reason }).ok(),
+            ConnectionPhase::Configuration | ConnectionPhase::Play => encode_packet(version, phase, &DisconnectResponsePacket { reason }).ok(),
+            _ => None,
+        };
+
+        let message = match frame {
+            Some(frame) => {
+                self.capture(version, phase, &frame);
+                NetMessage::Kick(frame)
+            }
+            None => NetMessage::Disconnect,
+        };
+        let _ = self.sender.send(message);
+    }
+
+    fn capture(&self, version: ProtocolVersion, phase: ConnectionPhase, frames: &[u8]) {
+        if let Some(capture) = &self.capture {
+            capture.record_frames(Direction::Outbound, version, phase, frames);
+        }
+    }
+
+    /// Writes what is queued and closes the socket
+    pub fn disconnect(&self) {
+        let _ = self.sender.send(NetMessage::Disconnect);
+    }
+}
+
+/// Encodes a packet as a frame (length, id and data) in a pooled buffer
+pub fn encode_packet<T: ClientboundPacket>(version: ProtocolVersion, phase: ConnectionPhase, packet: &T) -> anyhow::Result<PooledFrame> {
+    let packet_id = require_clientbound_id(version, phase, T::NAME)?;
+
+    PooledFrame::encode(|buffer| {
+        buffer.write_type(VarInt(packet_id));
+        packet.write_for(version, buffer)
+    })
+}
+
+/// Like [`encode_packet`], appending the frame to a buffer of frames
+pub fn append_packet<T: ClientboundPacket>(buffer: &mut Vec<u8>, version: ProtocolVersion, phase: ConnectionPhase, packet: &T) -> anyhow::Result<()> {
+    let packet_id = require_clientbound_id(version, phase, T::NAME)?;
+
+    buffer_pool::append_frame(buffer, |buffer| {
+        buffer.write_type(VarInt(packet_id));
+        packet.write_for(version, buffer)
+    })
+}
+
+/// A packet broadcast to many players. Its frame is encoded the first time a version and phase
+/// need it and then shared, so a broadcast costs one encoding per version instead of one per player.
+pub struct SharedPacket<'a, T: ClientboundPacket> {
+    packet: &'a T,
+    frames: HashMap<(ProtocolVersion, ConnectionPhase), Arc<[u8]>>,
+}
+
+impl<'a, T: ClientboundPacket> SharedPacket<'a, T> {
+    pub fn new(packet: &'a T) -> SharedPacket<'a, T> {
+        SharedPacket { packet, frames: HashMap::new() }
+    }
+
+    /// Frame of the packet in a version and phase, encoded on first use
+    pub fn frame(&mut self, version: ProtocolVersion, phase: ConnectionPhase) -> anyhow::Result<Arc<[u8]>> {
+        if let Some(frame) = self.frames.get(&(version, phase)) {
+            return Ok(frame.clone());
+        }
+
+        let frame: Arc<[u8]> = Arc::from(&*encode_packet(version, phase, self.packet)?);
+        self.frames.insert((version, phase), frame.clone());
+        Ok(frame)
+    }
+}
+
+/// Play packets the client applies together in the same tick, between two "Bundle delimiter" packets
+pub struct PacketBundle {
+    version: ProtocolVersion,
+    /// Opening delimiter and the frames of the packets so far
+    frames: Vec<u8>,
+    count: usize,
+}
+
+impl PacketBundle {
+    /// Packets the vanilla client accepts in a single bundle
+    pub const MAX_PACKETS: usize = 4096;
+
+    pub fn new(version: ProtocolVersion) -> anyhow::Result<PacketBundle> {
+        let mut frames = buffer_pool::take();
+        append_packet(&mut frames, version, ConnectionPhase::Play, &BundleDelimiterResponsePacket {})?;
+
+        Ok(PacketBundle { version, frames, count: 0 })
+    }
+
+    pub fn push<T: ClientboundPacket>(&mut self, packet: T) -> anyhow::Result<()> {
+        if self.count == Self::MAX_PACKETS {
+            return Err(anyhow::anyhow!("Bundles hold at most {} packets", Self::MAX_PACKETS));
+        }
+
+        append_packet(&mut self.frames, self.version, ConnectionPhase::Play, &packet)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The frames of the bundle wrapped in delimiters
+    fn finish(mut self) -> anyhow::Result<PooledFrame> {
+        append_packet(&mut self.frames, self.version, ConnectionPhase::Play, &BundleDelimiterResponsePacket {})?;
+        Ok(PooledFrame::from_frames(self.frames))
+    }
+}
+
+/// Writes the queued frames to the socket.
+/// Flush policy: whatever is queued when the task wakes up is written at once with a vectored write,
+/// up to a batch limit, so a burst of packets costs a few syscalls and a single packet isn't delayed.
+/// A write that takes longer than the timeout drops the socket, kicks queued behind it included.
+async fn handle_writes(mut stream: OwnedWriteHalf, mut receiver: mpsc::UnboundedReceiver<NetMessage>, state: Arc<WriterState>, write_timeout: Duration) {
+    let mut batch: Vec<Frame> = Vec::with_capacity(MAX_BATCH_FRAMES);
+
+    while let Some(message) = receiver.recv().await {
+        let mut next = Some(message);
+        let mut batch_bytes = 0;
+        let mut close = None;
+
+        // Gather what is already queued, up to the batch limits
+        while let Some(message) = next.take() {
+            match message {
+                NetMessage::SendPacket(frame) => {
+                    batch_bytes += frame.len();
+                    batch.push(Frame::Owned(frame));
+                }
+                NetMessage::SendShared(frame) => {
+                    batch_bytes += frame.len();
+                    batch.push(Frame::Shared(frame));
+                }
+                NetMessage::Kick(frame) => {
+                    close = Some(Some(frame));
+                    break;
+                }
+                NetMessage::Disconnect => {
+                    close = Some(None);
+                    break;
+                }
+            }
+
+            if batch.len() < MAX_BATCH_FRAMES && batch_bytes < MAX_BATCH_BYTES {
+                next = receiver.try_recv().ok();
+            }
+        }
+
+        // A kicked client doesn't get what was queued before the disconnect packet
+        let kicked = state.closing.load(Ordering::Relaxed);
+        if !kicked && !within_timeout(write_timeout, write_all_vectored(&mut stream, &batch)).await {
+            break; // Client disconnected or stopped reading
+        }
+
+        state.queued_bytes.fetch_sub(batch_bytes, Ordering::Relaxed);
+        batch.clear();
+
+        if let Some(last_frame) = close {
+            if let Some(frame) = last_frame {
+                within_timeout(write_timeout, stream.write_all(&frame)).await;
+            }
+            let _ = tokio::time::timeout(write_timeout, stream.shutdown()).await;
+            break;
+        }
+    }
+
+    state.closing.store(true, Ordering::Relaxed);
+    drop(stream);
+    state.finished.notify_one();
+}
+
+/// Whether a write succeeded before the timeout
+async fn within_timeout(timeout: Duration, write: impl Future<Output = std::io::Result<()>>) -> bool {
+    match tokio::time::timeout(timeout, write).await {
+        Ok(result) => result.is_ok(),
+        Err(_) => {
+            metrics::record(Rejection::WriteTimeout);
+            println!("Dropping client that didn't read its packets for {:?}", timeout);
+            false
+        }
+    }
+}
+
+/// Frame waiting in a batch, either owned by this connection or shared with others
+enum Frame {
+    Owned(PooledFrame),
+    Shared(Arc<[u8]>),
+}
+
+impl Deref for Frame {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Frame::Owned(frame) => frame,
+            Frame::Shared(frame) => frame,
+        }
+    }
+}
+
+async fn write_all_vectored(stream: &mut OwnedWriteHalf, frames: &[Frame]) -> std::io::Result<()> {
+    let mut slices: Vec<IoSlice<'_>> = frames.iter().map(|frame| IoSlice::new(frame)).collect();
+    let mut remaining = &mut slices[..];
+
+    while !remaining.is_empty() {
+        let written = stream.write_vectored(remaining).await?;
+        if written == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+
+        IoSlice::advance_slices(&mut remaining, written);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use dashmap::DashMap;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use crate::PlayerList;
+use crate::config::{LimitAction, ServerConfig};
+use crate::networking::channels::{self, ChannelRegistry};
+use crate::networking::connection::{Connection, ConnectionPhase};
+use crate::networking::metrics::{self, Rejection};
+use crate::networking::packets::{self, PacketRegistries};
+use crate::networking::rate_limit::{IpThrottle, ThrottleResult};
+use crate::world::World;
+
+/// A bound server with everything its connections share.
+/// main.rs runs it on the public port, tests on an ephemeral local one.
+pub struct Server {
+    listener: TcpListener,
+    config: Arc<ServerConfig>,
+    registries: Arc<PacketRegistries>,
+    channels: Arc<ChannelRegistry>,
+    throttle: Arc<IpThrottle>,
+    pub players: PlayerList,
+}
+
+impl Server {
+    pub async fn bind(address: impl ToSocketAddrs, config: Arc<ServerConfig>) -> anyhow::Result<Server> {
+        let listener = TcpListener::bind(address).await?;
+        let throttle = Arc::new(IpThrottle::new(&config.rate_limits));
+
+        // Register packets of every supported version
+        let registries = Arc::new(PacketRegistries::new(packets::register_all));
+
+        // Register plugin channels
+        let mut channels = ChannelRegistry::new();
+        channels::register_all(&mut channels);
+
+        Ok(Server { listener, config, registries, channels: Arc::new(channels), throttle, players: Arc::new(DashMap::new()) })
+    }
+
+    pub fn local_addr(&self) -> anyhow::Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Starts the world and handles each client connection until they close
+    pub async fn run(self) -> anyhow::Result<()> {
+        let mut world = World::new(self.players.clone(), self.config.world.clone());
+        tokio::spawn(async move {
+            world.start_tick_loop().await;
+        });
+
+        loop {
+            let (socket, address) = self.listener.accept().await?;
+
+            match self.throttle.check(address.ip()) {
+                ThrottleResult::Allowed => {}
+                ThrottleResult::Banned => {
+                    metrics::record(Rejection::BannedAddress);
+                    continue;
+                }
+                ThrottleResult::Throttled => {
+                    metrics::record(Rejection::ConnectionThrottled);
+                    // Nothing was exchanged yet, a kick can only close the socket like a drop
+                    if self.config.rate_limits.connection_action == LimitAction::Ban {
+                        self.throttle.ban(address.ip(), Duration::from_secs(self.config.rate_limits.ban_secs));
+                    }
+                    continue;
+                }
+            }
+
+            let mut conn = Connection::new(socket, address, ConnectionPhase::Handshaking, self.players.clone(), self.config.clone(), self.channels.clone(), self.throttle.clone());
+            let registries = self.registries.clone();
+
+            tokio::spawn(async move {
+                match conn.run(&registries).await {
+                    Ok(_) => {
+                        // normal disconnect, no panic
+                        println!("Connection closed cleanly at phase {:?}", conn.phase());
+                    }
+                    Err(e) => {
+                        // only unexpected errors logged
+                        eprintln!("Connection error at phase {:?}: {:?}", conn.phase(), e);
+                    }
+                }
+
+                conn.remove_player();
+            });
+        }
+    }
+}
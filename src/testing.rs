@@ -0,0 +1,425 @@
+//! Headless client for integration tests, driving an in-process server through the real protocol.
+//! Packets are written with the serverbound types and read back with the clientbound ones,
+//! so a test fails on any id, phase or layout the two ends disagree on.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::anyhow;
+use bytes::BytesMut;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::net::tcp::OwnedWriteHalf;
+use uuid::Uuid;
+use crate::PlayerList;
+use crate::config::ServerConfig;
+use crate::networking::buffer_pool::PooledFrame;
+use crate::networking::connection::ConnectionPhase;
+use crate::networking::data_types::{BufferWrite, ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::i_byte::Byte;
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::raw_bytes::RawBytes;
+use crate::networking::data_types::u_byte::UnsignedByte;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::framing::FrameReader;
+use crate::networking::packets::{self, inspect, PacketRegistries};
+use crate::networking::packets::clientbound::{clientbound_name, ClientboundPacket};
+use crate::networking::packets::configuration::acknowledge_finish_configuration_request::AcknowledgeFinishConfigurationRequestPacket;
+use crate::networking::packets::configuration::client_information_request::ClientInformationRequestPacket;
+use crate::networking::packets::configuration::feature_flags_response::FeatureFlagsResponsePacket;
+use crate::networking::packets::configuration::finish_configuration_response::FinishConfigurationResponsePacket;
+use crate::networking::packets::configuration::known_packs_request::KnownPacksRequestPacket;
+use crate::networking::packets::configuration::known_packs_response::KnownPacksResponsePacket;
+use crate::networking::packets::configuration::registry_response::{RegistryDataResponsePacket, UpdateTagsResponsePacket};
+use crate::networking::packets::common::plugin_message_request::PluginMessageRequestPacket;
+use crate::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use crate::networking::packets::handshake::HandshakePacket;
+use crate::networking::packets::login::login_acknowledged_request::LoginAcknowledgedRequestPacket;
+use crate::networking::packets::login::login_start_request::LoginStartRequestPacket;
+use crate::networking::packets::login::login_success_response::LoginSuccessResponsePacket;
+use crate::networking::packets::play::chunk_batch_finished_response::ChunkBatchFinishedResponsePacket;
+use crate::networking::packets::play::chunk_batch_received_request::ChunkBatchReceivedRequestPacket;
+use crate::networking::packets::play::chunk_batch_start_response::ChunkBatchStartResponsePacket;
+use crate::networking::packets::play::chunk_data_and_update_light_response::ChunkDataAndUpdateLightResponsePacket;
+use crate::networking::packets::play::game_event_response::GameEventResponsePacket;
+use crate::networking::packets::play::keep_alive_response::KeepAliveResponsePacket;
+use crate::networking::packets::play::login_response::LoginResponsePacket;
+use crate::networking::packets::play::set_center_chunk_response::SetCenterChunkResponsePacket;
+use crate::networking::packets::play::synchronize_player_position_response::SynchronizePlayerPositionResponsePacket;
+use crate::networking::packets::play::unload_chunk_response::UnloadChunkResponsePacket;
+use crate::networking::protocol_version::ProtocolVersion;
+use crate::server::Server;
+
+/// Longest wait for a packet before a test fails instead of hanging
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest frame a vanilla client accepts, the serverbound limits of [`ConnectionPhase`] don't apply
+const MAX_CLIENTBOUND_FRAME: usize = 8 * 1024 * 1024;
+
+/// Starts a server on an ephemeral local port, it runs until the test runtime shuts down
+pub async fn start_server(config: ServerConfig) -> anyhow::Result<(SocketAddr, PlayerList)> {
+    let server = Server::bind("127.0.0.1:0", Arc::new(config)).await?;
+    let address = server.local_addr()?;
+    let players = server.players.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = server.run().await {
+            eprintln!("Test server stopped: {:?}", e);
+        }
+    });
+
+    Ok((address, players))
+}
+
+/// Handshake intents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    Status = 1,
+    Login = 2,
+    Transfer = 3,
+}
+
+/// Clientbound packet as received, before it is decoded
+#[derive(Debug)]
+pub struct ReceivedPacket {
+    pub phase: ConnectionPhase,
+    pub id: i32,
+    /// Vanilla name of the id in the client's version and phase
+    pub name: Option<&'static str>,
+    /// Packet data after the id
+    pub data: BytesMut,
+}
+
+impl ReceivedPacket {
+    /// Decodes the data as a packet, which has to use all of it
+    pub fn decode<T: FieldRead>(&self) -> anyhow::Result<T> {
+        let mut reader = ByteReader::new(&self.data);
+        let packet = T::read_from(&mut reader)?;
+
+        if !reader.is_empty() {
+            return Err(anyhow!("{} bytes left after decoding {:?} 0x{:02X}", reader.remaining(), self.phase, self.id));
+        }
+
+        Ok(packet)
+    }
+
+    pub fn is<T: ClientboundPacket>(&self) -> bool {
+        self.name == Some(T::NAME)
+    }
+}
+
+/// Client side of a connection, tracking the phase the way a vanilla client does.
+/// The phase moves on the packets the client sends (handshake, acknowledgements),
+/// tests that send them with [`TestClient::send`] switch it with [`TestClient::switch_phase`].
+pub struct TestClient {
+    frames: FrameReader,
+    output: OwnedWriteHalf,
+    registries: PacketRegistries,
+    pub address: SocketAddr,
+    pub version: ProtocolVersion,
+    pub phase: ConnectionPhase,
+    /// Set by [`TestClient::login`]
+    pub uuid: Option<Uuid>,
+    /// Bytes of the frames sent and received, length prefixes included
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// View distance sent in the client information
+    pub view_distance: i8,
+    /// Whether [`TestClient::receive`] tracks and acknowledges chunks instead of returning their packets
+    pub handle_chunks: bool,
+    /// Chunks the server sent and hasn't unloaded
+    pub chunks: HashSet<(i32, i32)>,
+    /// Last "Set center chunk"
+    pub center_chunk: (i32, i32),
+    /// Rate reported in "Chunk batch received", the vanilla client's initial one
+    pub chunks_per_tick: f32,
+}
+
+impl TestClient {
+    pub async fn connect(address: SocketAddr, version: ProtocolVersion) -> anyhow::Result<TestClient> {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+        let (input, output) = stream.into_split();
+
+        Ok(TestClient {
+            frames: FrameReader::new(input),
+            output,
+            registries: PacketRegistries::new(packets::register_all),
+            address,
+            version,
+            phase: ConnectionPhase::Handshaking,
+            uuid: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            view_distance: 10,
+            handle_chunks: true,
+            chunks: HashSet::new(),
+            center_chunk: (0, 0),
+            chunks_per_tick: 9.0,
+        })
+    }
+
+    /// Connects, logs in and configures a player, leaving the client in play
+    pub async fn join(address: SocketAddr, version: ProtocolVersion, name: &str) -> anyhow::Result<TestClient> {
+        let mut client = TestClient::connect(address, version).await?;
+        client.handshake(Intent::Login).await?;
+        client.login(name).await?;
+        client.begin_configuration().await?;
+        client.finish_configuration().await?;
+        Ok(client)
+    }
+
+    /// Sends a serverbound packet with the id the server registered it under for the current phase
+    pub async fn send<P: PacketWrite + 'static>(&mut self, packet: P) -> anyhow::Result<()> {
+        let id = self.registries.get(self.version).id_of::<P>(self.phase)
+            .ok_or_else(|| anyhow!("{} is not a {:?} packet", std::any::type_name::<P>(), self.phase))?;
+
+        let mut data = Vec::new();
+        packet.write_to(&mut data);
+        self.send_raw(id, &data).await
+    }
+
+    /// Sends any id and data, e.g. to test how the server handles invalid packets
+    pub async fn send_raw(&mut self, id: i32, data: &[u8]) -> anyhow::Result<()> {
+        let frame = PooledFrame::encode(|body| {
+            body.write_type(VarInt(id));
+            body.extend_from_slice(data);
+            Ok(())
+        })?;
+
+        self.output.write_all(&frame).await?;
+        self.bytes_sent += frame.len() as u64;
+        Ok(())
+    }
+
+    /// Next clientbound packet, failing if the server closes the connection or stays silent.
+    /// Keep alives are skipped, they come from the world tick at any point of a test,
+    /// and so are the chunk packets unless [`TestClient::handle_chunks`] is off.
+    pub async fn receive(&mut self) -> anyhow::Result<ReceivedPacket> {
+        loop {
+            let packet = self.receive_any().await?;
+            if packet.is::<KeepAliveResponsePacket>() {
+                continue;
+            }
+
+            if self.handle_chunks && self.handle_chunk_packet(&packet).await? {
+                continue;
+            }
+
+            return Ok(packet);
+        }
+    }
+
+    /// Tracks the chunks the way a vanilla client does and acknowledges each batch with
+    /// [`TestClient::chunks_per_tick`]. Returns false for packets that aren't about chunks.
+    pub async fn handle_chunk_packet(&mut self, packet: &ReceivedPacket) -> anyhow::Result<bool> {
+        if packet.phase != ConnectionPhase::Play {
+            return Ok(false);
+        }
+
+        if packet.is::<ChunkDataAndUpdateLightResponsePacket>() {
+            // The packet can't be read back, its coordinates lead the data
+            let mut reader = ByteReader::new(&packet.data);
+            let x: i32 = reader.read_field()?;
+            let z: i32 = reader.read_field()?;
+            self.chunks.insert((x, z));
+        } else if packet.is::<UnloadChunkResponsePacket>() {
+            let unload = packet.decode::<UnloadChunkResponsePacket>()?;
+            self.chunks.remove(&(unload.chunk_x, unload.chunk_z));
+        } else if packet.is::<SetCenterChunkResponsePacket>() {
+            let center = packet.decode::<SetCenterChunkResponsePacket>()?;
+            self.center_chunk = (center.chunk_x.0, center.chunk_z.0);
+        } else if packet.is::<ChunkBatchFinishedResponsePacket>() {
+            packet.decode::<ChunkBatchFinishedResponsePacket>()?;
+            self.send(ChunkBatchReceivedRequestPacket { chunks_per_tick: self.chunks_per_tick }).await?;
+        } else if packet.is::<ChunkBatchStartResponsePacket>() {
+            packet.decode::<ChunkBatchStartResponsePacket>()?;
+        } else if packet.is::<GameEventResponsePacket>() {
+            let event = packet.decode::<GameEventResponsePacket>()?;
+            return Ok(event.event.0 == GameEventResponsePacket::START_WAITING_FOR_CHUNKS);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Handles chunk packets until the loaded chunks match a condition, failing on any other packet
+    pub async fn wait_for_chunks(&mut self, condition: impl Fn(&HashSet<(i32, i32)>) -> bool) -> anyhow::Result<()> {
+        while !condition(&self.chunks) {
+            let packet = self.receive_any().await?;
+            if !packet.is::<KeepAliveResponsePacket>() && !self.handle_chunk_packet(&packet).await? {
+                return Err(anyhow!("Unexpected {:?} 0x{:02X} while waiting for chunks", packet.name, packet.id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Next clientbound packet, including keep alives
+    pub async fn receive_any(&mut self) -> anyhow::Result<ReceivedPacket> {
+        let frame = tokio::time::timeout(RECEIVE_TIMEOUT, self.frames.read_frame(MAX_CLIENTBOUND_FRAME)).await
+            .map_err(|_| anyhow!("No packet received in {:?} at phase {:?}", RECEIVE_TIMEOUT, self.phase))??
+            .ok_or_else(|| anyhow!("Connection closed at phase {:?}", self.phase))?;
+
+        self.bytes_received += (frame.len() + VarInt(frame.len() as i32).encode().1) as u64;
+
+        let mut data = frame;
+        let mut reader = ByteReader::new(&data);
+        let VarInt(id) = reader.read_field()?;
+        let id_size = data.len() - reader.remaining();
+        let data = data.split_off(id_size);
+
+        Ok(ReceivedPacket { phase: self.phase, id, name: clientbound_name(self.version, self.phase, id), data })
+    }
+
+    /// Receives the next packet and decodes it as `T`, failing if the server sent another one
+    pub async fn expect<T: ClientboundPacket + FieldRead>(&mut self) -> anyhow::Result<T> {
+        let packet = self.expect_named(T::NAME).await?;
+        packet.decode()
+    }
+
+    /// Receives the next packet, failing unless it has the given vanilla name
+    pub async fn expect_named(&mut self, name: &str) -> anyhow::Result<ReceivedPacket> {
+        let packet = self.receive().await?;
+
+        if packet.name != Some(name) {
+            let mut body = Vec::new();
+            body.write_type(VarInt(packet.id));
+            body.extend_from_slice(&packet.data);
+            return Err(anyhow!("Expected {} at phase {:?}, got {}", name, self.phase, inspect::describe_clientbound(self.version, self.phase, &body)));
+        }
+
+        Ok(packet)
+    }
+
+    /// Expects the disconnect packet of the current phase, after which the server closes the connection
+    pub async fn expect_disconnect(&mut self) -> anyhow::Result<ReceivedPacket> {
+        let name = if self.phase == ConnectionPhase::Login { "minecraft:login_disconnect" } else { "minecraft:disconnect" };
+        let packet = self.expect_named(name).await?;
+        self.expect_closed().await?;
+        Ok(packet)
+    }
+
+    /// Fails if the server sends anything else before closing the connection
+    pub async fn expect_closed(&mut self) -> anyhow::Result<()> {
+        let frame = tokio::time::timeout(RECEIVE_TIMEOUT, self.frames.read_frame(MAX_CLIENTBOUND_FRAME)).await
+            .map_err(|_| anyhow!("Connection still open after {:?}", RECEIVE_TIMEOUT))?;
+
+        match frame {
+            Ok(Some(frame)) => Err(anyhow!("Expected the connection to close, got {}", inspect::describe_clientbound(self.version, self.phase, &frame))),
+            // A reset after the last packet counts as closed
+            Ok(None) | Err(_) => Ok(()),
+        }
+    }
+
+    pub fn switch_phase(&mut self, phase: ConnectionPhase) {
+        self.phase = phase;
+    }
+
+    /// Sends the handshake for the client's version and switches to the phase of the intent
+    pub async fn handshake(&mut self, intent: Intent) -> anyhow::Result<()> {
+        self.send(HandshakePacket {
+            protocol_version: VarInt(self.version.protocol()),
+            server_address: self.address.ip().to_string(),
+            port: self.address.port(),
+            next_state: VarInt(intent as i32),
+        }).await?;
+
+        self.switch_phase(if intent == Intent::Status { ConnectionPhase::Status } else { ConnectionPhase::Login });
+        Ok(())
+    }
+
+    /// Logs in with an offline account and acknowledges the login, switching to configuration
+    pub async fn login(&mut self, name: &str) -> anyhow::Result<LoginSuccessResponsePacket> {
+        let uuid = Uuid::new_v4();
+        self.send(LoginStartRequestPacket { name: name.to_string(), player_uuid: uuid }).await?;
+
+        let success = self.expect::<LoginSuccessResponsePacket>().await?;
+        if success.profile.uuid != uuid || success.profile.username != name {
+            return Err(anyhow!("Login success for {} {}, expected {} {}", success.profile.username, success.profile.uuid, name, uuid));
+        }
+
+        self.uuid = Some(uuid);
+        self.send(LoginAcknowledgedRequestPacket {}).await?;
+        self.switch_phase(ConnectionPhase::Configuration);
+        Ok(success)
+    }
+
+    /// Receives the packets that open the configuration phase and answers them like a vanilla client:
+    /// brand, client information and the known packs the server offered
+    pub async fn begin_configuration(&mut self) -> anyhow::Result<()> {
+        let brand = self.expect::<PluginMessageResponsePacket>().await?;
+        if brand.channel != Identifier::minecraft("brand") {
+            return Err(anyhow!("Expected the server brand first, got a message on {}", brand.channel));
+        }
+
+        // Custom channels are announced before the feature flags when the server has any
+        let mut packet = self.receive().await?;
+        if packet.is::<PluginMessageResponsePacket>() {
+            packet = self.receive().await?;
+        }
+        if !packet.is::<FeatureFlagsResponsePacket>() {
+            return Err(anyhow!("Expected the feature flags, got {:?}", packet.name));
+        }
+        packet.decode::<FeatureFlagsResponsePacket>()?;
+
+        let known_packs = self.expect::<KnownPacksResponsePacket>().await?;
+
+        self.send(PluginMessageRequestPacket {
+            channel: Identifier::minecraft("brand"),
+            data: RawBytes(brand_payload("vanilla")),
+        }).await?;
+        self.send(client_information(self.view_distance)).await?;
+        self.send(KnownPacksRequestPacket { known_packs: known_packs.packs }).await?;
+        Ok(())
+    }
+
+    /// Receives the registries up to "Finish configuration", acknowledges it and switches to play.
+    /// Returns the number of registries received.
+    pub async fn finish_configuration(&mut self) -> anyhow::Result<usize> {
+        let mut registries = 0;
+
+        loop {
+            let packet = self.receive().await?;
+
+            if packet.is::<RegistryDataResponsePacket>() {
+                registries += 1;
+            } else if packet.is::<FinishConfigurationResponsePacket>() {
+                break;
+            } else if !packet.is::<UpdateTagsResponsePacket>() && packet.name != Some("minecraft:server_links") && packet.name != Some("minecraft:custom_report_details") {
+                return Err(anyhow!("Unexpected {:?} 0x{:02X} before finish configuration", packet.name, packet.id));
+            }
+        }
+
+        self.send(AcknowledgeFinishConfigurationRequestPacket {}).await?;
+        self.switch_phase(ConnectionPhase::Play);
+
+        self.expect::<LoginResponsePacket>().await?;
+        self.expect::<SynchronizePlayerPositionResponsePacket>().await?;
+        Ok(registries)
+    }
+}
+
+/// Client information as sent by a vanilla client with default settings
+pub fn client_information(view_distance: i8) -> ClientInformationRequestPacket {
+    ClientInformationRequestPacket {
+        locale: "en_us".to_string(),
+        view_distance: Byte(view_distance),
+        chat_mode: VarInt(0),
+        chat_colors: true,
+        displayed_skin_parts: UnsignedByte(0x7F),
+        main_hand: VarInt(1),
+        enable_text_filtering: false,
+        allow_server_listings: true,
+        particle_status: VarInt(0),
+    }
+}
+
+/// "minecraft:brand" carries the brand as a string
+pub fn brand_payload(brand: &str) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.write_type(brand.to_string());
+    data
+}
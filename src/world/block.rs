@@ -0,0 +1,53 @@
+//! Blocks and their states, generated by build.rs from the vanilla blocks report in reports/.
+
+use crate::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::var_int::VarInt;
+
+include!(concat!(env!("OUT_DIR"), "/blocks.rs"));
+
+impl Block {
+    pub fn from_identifier(identifier: &Identifier) -> Option<Block> {
+        Block::from_name(&identifier.to_string())
+    }
+
+    pub fn identifier(self) -> Identifier {
+        Identifier::from(self.name())
+    }
+}
+
+impl BlockState {
+    pub const AIR: BlockState = BlockState::Air;
+
+    pub fn is_air(self) -> bool {
+        self == BlockState::Air
+    }
+}
+
+impl Default for BlockState {
+    fn default() -> Self {
+        BlockState::AIR
+    }
+}
+
+impl From<Block> for BlockState {
+    fn from(block: Block) -> Self {
+        block.default_state()
+    }
+}
+
+/// Block states are sent as their id in a VarInt
+impl FieldRead for BlockState {
+    fn read_from(reader: &mut ByteReader<'_>) -> anyhow::Result<Self> {
+        let id = reader.read_field::<VarInt>()?.0;
+        u16::try_from(id).ok()
+            .and_then(BlockState::from_id)
+            .ok_or_else(|| anyhow::anyhow!("Invalid block state id {}", id))
+    }
+}
+
+impl PacketWrite for BlockState {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        VarInt(self.id() as i32).write_to(buf);
+    }
+}
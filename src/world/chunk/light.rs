@@ -0,0 +1,91 @@
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::var_int::VarInt;
+
+/// Bytes of the sections with every block at the same level, shared by all of them
+static UNIFORM_LEVELS: [[u8; LightSection::BYTES]; 16] = {
+    let mut levels = [[0; LightSection::BYTES]; 16];
+    let mut level = 0;
+    while level < 16 {
+        levels[level] = [(level as u8) << 4 | level as u8; LightSection::BYTES];
+        level += 1;
+    }
+    levels
+};
+
+/// Light levels of a 16x16x16 section, 4 bits per block with the even indices in the low half of a byte.
+/// Sections with the same level everywhere, like the sky above the ground, don't take an array of their own.
+#[derive(Debug, Clone)]
+pub struct LightSection(Levels);
+
+#[derive(Debug, Clone)]
+enum Levels {
+    Uniform(u8),
+    Mixed(Box<[u8; LightSection::BYTES]>),
+}
+
+impl LightSection {
+    pub const BYTES: usize = 2048;
+
+    /// Section with every block at `level`
+    pub fn filled(level: u8) -> Self {
+        Self(Levels::Uniform(level.min(15)))
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        match &self.0 {
+            Levels::Uniform(level) => *level,
+            Levels::Mixed(bytes) => {
+                let index = (y * 16 + z) * 16 + x;
+                (bytes[index / 2] >> ((index % 2) * 4)) & 0x0F
+            }
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, level: u8) {
+        let level = level.min(15);
+        if let Levels::Uniform(uniform) = self.0 {
+            if uniform == level {
+                return;
+            }
+            self.0 = Levels::Mixed(Box::new(UNIFORM_LEVELS[uniform as usize]));
+        }
+        let Levels::Mixed(bytes) = &mut self.0 else { unreachable!() };
+
+        let index = (y * 16 + z) * 16 + x;
+        let shift = (index % 2) * 4;
+        let byte = &mut bytes[index / 2];
+        *byte = (*byte & !(0x0F << shift)) | (level << shift);
+    }
+
+    /// Whether every level is 0, such sections are sent as a bit of the empty masks instead
+    pub fn is_empty(&self) -> bool {
+        match &self.0 {
+            Levels::Uniform(level) => *level == 0,
+            Levels::Mixed(bytes) => bytes.iter().all(|byte| *byte == 0),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8; Self::BYTES] {
+        match &self.0 {
+            Levels::Uniform(level) => &UNIFORM_LEVELS[*level as usize],
+            Levels::Mixed(bytes) => bytes,
+        }
+    }
+}
+
+/// Sections are equal when their levels are, however they're stored
+impl PartialEq for LightSection {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for LightSection {}
+
+/// Sent as a length prefixed byte array, which is always 2048 long
+impl PacketWrite for LightSection {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        VarInt(Self::BYTES as i32).write_to(buf);
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
@@ -0,0 +1,217 @@
+//! Chunk columns: sections of paletted block states and biomes, their light, and their encoding into
+//! the Chunk Data and Update Light packet.
+
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::bit_set::BitSet;
+use crate::networking::packets::play::chunk_data_and_update_light_response::{ChunkData, ChunkDataAndUpdateLightResponsePacket, Heightmap, HeightmapType, LightData};
+use crate::world::block::BlockState;
+use crate::world::chunk::light::LightSection;
+use crate::world::chunk::paletted_container::{ceil_log2, packed_long_count, set_packed};
+use crate::world::chunk::section::ChunkSection;
+
+pub mod light;
+pub mod paletted_container;
+pub mod section;
+pub mod sender;
+
+/// Biomes of the `minecraft:worldgen/biome` registry sent during configuration
+/// (registries/minecraft_worldgen_biome.bin), direct biome palettes take enough bits for all of them
+pub const BIOME_COUNT: u32 = 65;
+/// Id of `minecraft:plains` in that registry
+pub const PLAINS_BIOME: u32 = 40;
+
+/// Bottom and height of `minecraft:overworld`, the dimension type players log into
+pub const OVERWORLD_MIN_Y: i32 = -64;
+pub const OVERWORLD_HEIGHT: u32 = 384;
+
+/// Column of 16x16 blocks from the bottom to the top of the world. Block coordinates are relative to
+/// the chunk for x and z, from 0 to 15, and absolute for y.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub x: i32,
+    pub z: i32,
+    min_y: i32,
+    sections: Vec<ChunkSection>,
+    /// Light of the sections, plus the one below and the one above the world. None isn't sent.
+    sky_light: Vec<Option<LightSection>>,
+    block_light: Vec<Option<LightSection>>,
+}
+
+impl Chunk {
+    /// Chunk of air in plains. There's no light engine, so the sky lights every block fully.
+    pub fn new(x: i32, z: i32, min_y: i32, height: u32) -> Self {
+        assert!(height.is_multiple_of(16) && min_y % 16 == 0, "chunks are made of whole sections");
+        let section_count = (height / 16) as usize;
+
+        Self {
+            x,
+            z,
+            min_y,
+            sections: vec![ChunkSection::new(PLAINS_BIOME); section_count],
+            sky_light: vec![Some(LightSection::filled(15)); section_count + 2],
+            block_light: vec![Some(LightSection::filled(0)); section_count + 2],
+        }
+    }
+
+    pub fn overworld(x: i32, z: i32) -> Self {
+        Self::new(x, z, OVERWORLD_MIN_Y, OVERWORLD_HEIGHT)
+    }
+
+    pub fn min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    pub fn height(&self) -> u32 {
+        self.sections.len() as u32 * 16
+    }
+
+    /// Sections from the bottom up
+    pub fn sections(&self) -> &[ChunkSection] {
+        &self.sections
+    }
+
+    pub fn sections_mut(&mut self) -> &mut [ChunkSection] {
+        &mut self.sections
+    }
+
+    /// Index of the section holding a y, None outside the world
+    fn section_index(&self, y: i32) -> Option<usize> {
+        let index = (y - self.min_y).div_euclid(16);
+        (0..self.sections.len() as i32).contains(&index).then_some(index as usize)
+    }
+
+    /// Air outside the world
+    pub fn get_block(&self, x: usize, y: i32, z: usize) -> BlockState {
+        match self.section_index(y) {
+            Some(index) => self.sections[index].get_block(x, local_y(y), z),
+            None => BlockState::AIR,
+        }
+    }
+
+    /// Sets a block and returns the one it replaced, blocks outside the world are ignored
+    pub fn set_block(&mut self, x: usize, y: i32, z: usize, state: BlockState) -> BlockState {
+        match self.section_index(y) {
+            Some(index) => self.sections[index].set_block(x, local_y(y), z, state),
+            None => BlockState::AIR,
+        }
+    }
+
+    /// Biome id at a block, biomes are stored per 4x4x4 blocks. Outside the world it's the biome
+    /// of the closest cell, like vanilla.
+    pub fn get_biome(&self, x: usize, y: i32, z: usize) -> u32 {
+        let y = y.clamp(self.min_y, self.min_y + self.height() as i32 - 1);
+        let index = self.section_index(y).unwrap_or_default();
+        self.sections[index].get_biome(x / 4, local_y(y) / 4, z / 4)
+    }
+
+    /// Sets the biome of the 4x4x4 cell holding a block
+    pub fn set_biome(&mut self, x: usize, y: i32, z: usize, biome: u32) {
+        if let Some(index) = self.section_index(y) {
+            self.sections[index].set_biome(x / 4, local_y(y) / 4, z / 4, biome);
+        }
+    }
+
+    /// Index in the light sections, which start one section below the world
+    fn light_index(&self, y: i32) -> Option<usize> {
+        let index = (y - self.min_y).div_euclid(16) + 1;
+        (0..self.sky_light.len() as i32).contains(&index).then_some(index as usize)
+    }
+
+    pub fn sky_light(&self, x: usize, y: i32, z: usize) -> u8 {
+        light_at(&self.sky_light, self.light_index(y), x, y, z)
+    }
+
+    pub fn set_sky_light(&mut self, x: usize, y: i32, z: usize, level: u8) {
+        let index = self.light_index(y);
+        set_light_at(&mut self.sky_light, index, x, y, z, level);
+    }
+
+    pub fn block_light(&self, x: usize, y: i32, z: usize) -> u8 {
+        light_at(&self.block_light, self.light_index(y), x, y, z)
+    }
+
+    pub fn set_block_light(&mut self, x: usize, y: i32, z: usize, level: u8) {
+        let index = self.light_index(y);
+        set_light_at(&mut self.block_light, index, x, y, z, level);
+    }
+
+    /// Height of the top non-air block of each column above the bottom of the world, 0 for empty columns.
+    /// Packed with enough bits for the world height plus one, columns are ordered by z then x.
+    pub fn surface_heights(&self) -> Vec<i64> {
+        let bits = ceil_log2(self.height() + 1);
+        let mut data = vec![0; packed_long_count(16 * 16, bits)];
+
+        for z in 0..16 {
+            for x in 0..16 {
+                let top = self.sections.iter().enumerate().rev()
+                    .filter(|(_, section)| !section.is_empty())
+                    .find_map(|(index, section)| {
+                        (0..16).rev()
+                            .find(|y| !section.get_block(x, *y, z).is_air())
+                            .map(|y| index * 16 + y + 1)
+                    });
+
+                set_packed(&mut data, bits, z * 16 + x, top.unwrap_or(0) as u64);
+            }
+        }
+
+        data.into_iter().map(|long| long as i64).collect()
+    }
+
+    /// Chunk Data and Update Light for the chunk. Without collision data every non-air block
+    /// counts as motion blocking, so the heightmaps the client uses are all the surface heights.
+    pub fn to_packet(&self) -> ChunkDataAndUpdateLightResponsePacket {
+        let surface = self.surface_heights();
+        let heightmaps = [HeightmapType::WorldSurface, HeightmapType::MotionBlocking, HeightmapType::MotionBlockingNoLeaves]
+            .into_iter()
+            .map(|heightmap_type| Heightmap { heightmap_type, data: surface.clone() })
+            .collect();
+
+        let mut sections = Vec::new();
+        for section in &self.sections {
+            section.write_to(&mut sections);
+        }
+
+        let mut light = LightData::default();
+        light_masks(&self.sky_light, &mut light.sky_light_mask, &mut light.empty_sky_light_mask, &mut light.sky_light);
+        light_masks(&self.block_light, &mut light.block_light_mask, &mut light.empty_block_light_mask, &mut light.block_light);
+
+        ChunkDataAndUpdateLightResponsePacket {
+            chunk_x: self.x,
+            chunk_z: self.z,
+            data: ChunkData { heightmaps, sections, block_entities: Vec::new() },
+            light,
+        }
+    }
+}
+
+fn local_y(y: i32) -> usize {
+    y.rem_euclid(16) as usize
+}
+
+fn light_at(sections: &[Option<LightSection>], index: Option<usize>, x: usize, y: i32, z: usize) -> u8 {
+    match index.and_then(|index| sections[index].as_ref()) {
+        Some(section) => section.get(x, local_y(y), z),
+        None => 0,
+    }
+}
+
+fn set_light_at(sections: &mut [Option<LightSection>], index: Option<usize>, x: usize, y: i32, z: usize, level: u8) {
+    if let Some(index) = index {
+        sections[index].get_or_insert_with(|| LightSection::filled(0)).set(x, local_y(y), z, level);
+    }
+}
+
+/// Sets the mask bits of the sent light sections and collects the ones that aren't all 0
+fn light_masks(sections: &[Option<LightSection>], mask: &mut BitSet, empty_mask: &mut BitSet, arrays: &mut Vec<LightSection>) {
+    for (index, section) in sections.iter().enumerate() {
+        match section {
+            Some(section) if section.is_empty() => empty_mask.set(index, true),
+            Some(section) => {
+                mask.set(index, true);
+                arrays.push(section.clone());
+            }
+            None => {}
+        }
+    }
+}
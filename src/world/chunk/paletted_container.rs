@@ -0,0 +1,205 @@
+use crate::networking::data_types::PacketWrite;
+use crate::networking::data_types::var_int::VarInt;
+use crate::world::chunk::BIOME_COUNT;
+
+/// Bits per entry of direct block state palettes. The client computes them from the size of its block state
+/// registry, between 2^14 and 2^15 states in 1.21.11, not from the states reports/ generates.
+pub const DIRECT_BLOCK_STATE_BITS: u8 = 15;
+
+/// What a container stores, which sets its amount of entries and the palettes the client expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// 16x16x16 block state ids
+    BlockStates,
+    /// 4x4x4 biome ids, each covering 4x4x4 blocks
+    Biomes,
+}
+
+impl ContainerKind {
+    /// Entries along each axis
+    pub fn size(self) -> usize {
+        match self {
+            ContainerKind::BlockStates => 16,
+            ContainerKind::Biomes => 4,
+        }
+    }
+
+    pub fn entries(self) -> usize {
+        self.size() * self.size() * self.size()
+    }
+
+    /// Index of an entry, x is the fastest changing axis and y the slowest
+    pub fn index(self, x: usize, y: usize, z: usize) -> usize {
+        (y * self.size() + z) * self.size() + x
+    }
+
+    /// Bits per entry of an indirect palette holding `ids` ids, None if it takes a direct palette.
+    /// Block state palettes use at least 4 bits, the client reads fewer as 4.
+    fn indirect_bits(self, ids: usize) -> Option<u8> {
+        let (min, max) = match self {
+            ContainerKind::BlockStates => (4, 8),
+            ContainerKind::Biomes => (1, 3),
+        };
+
+        let bits = ceil_log2(ids as u32).max(min);
+        (bits <= max).then_some(bits)
+    }
+
+    /// Bits per entry of a direct palette, enough for every id of the registry as the client computes them
+    fn direct_bits(self) -> u8 {
+        match self {
+            ContainerKind::BlockStates => DIRECT_BLOCK_STATE_BITS,
+            ContainerKind::Biomes => ceil_log2(BIOME_COUNT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Palette {
+    /// Every entry is this id, no data is stored
+    Single(u32),
+    /// Entries are indices into these ids
+    Indirect(Vec<u32>),
+    /// Entries are the ids themselves
+    Direct,
+}
+
+/// Registry ids of a section's block states or biomes, packed into longs with as few bits per entry
+/// as the palette allows. Setting an id the palette doesn't have grows it transparently, from a single
+/// value to an indirect palette with more and more bits, then to a direct one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PalettedContainer {
+    kind: ContainerKind,
+    palette: Palette,
+    bits: u8,
+    data: Vec<u64>,
+}
+
+impl PalettedContainer {
+    /// Container with every entry set to `id`
+    pub fn new(kind: ContainerKind, id: u32) -> Self {
+        Self { kind, palette: Palette::Single(id), bits: 0, data: Vec::new() }
+    }
+
+    pub fn kind(&self) -> ContainerKind {
+        self.kind
+    }
+
+    /// Bits per entry of the data, 0 when every entry is the same id
+    pub fn bits_per_entry(&self) -> u8 {
+        self.bits
+    }
+
+    pub fn get(&self, index: usize) -> u32 {
+        match &self.palette {
+            Palette::Single(id) => *id,
+            Palette::Indirect(ids) => ids[get_packed(&self.data, self.bits, index) as usize],
+            Palette::Direct => get_packed(&self.data, self.bits, index) as u32,
+        }
+    }
+
+    /// Sets an entry and returns the id it had
+    pub fn set(&mut self, index: usize, id: u32) -> u32 {
+        assert!(index < self.kind.entries(), "entry {} out of a container of {}", index, self.kind.entries());
+
+        let previous = self.get(index);
+        if previous != id {
+            let entry = self.entry_for(id);
+            set_packed(&mut self.data, self.bits, index, entry);
+        }
+        previous
+    }
+
+    /// Sets every entry to `id`, which drops the palette and data
+    pub fn fill(&mut self, id: u32) {
+        self.palette = Palette::Single(id);
+        self.bits = 0;
+        self.data = Vec::new();
+    }
+
+    /// Value stored in the data for an id, growing the palette if it doesn't have the id yet
+    fn entry_for(&mut self, id: u32) -> u64 {
+        let mut ids = match &self.palette {
+            Palette::Direct => return id as u64,
+            Palette::Indirect(ids) => match ids.iter().position(|existing| *existing == id) {
+                Some(entry) => return entry as u64,
+                None => ids.clone(),
+            },
+            Palette::Single(existing) => vec![*existing],
+        };
+
+        let entries: Vec<u64> = (0..self.kind.entries())
+            .map(|index| get_packed(&self.data, self.bits, index))
+            .collect();
+        ids.push(id);
+
+        // Ids are only appended, so the entries of an indirect palette keep their index
+        let (palette, bits, entries) = match self.kind.indirect_bits(ids.len()) {
+            Some(bits) => (Palette::Indirect(ids), bits, entries),
+            None => {
+                let entries = entries.iter().map(|entry| ids[*entry as usize] as u64).collect();
+                (Palette::Direct, self.kind.direct_bits(), entries)
+            }
+        };
+
+        self.data = vec![0; packed_long_count(self.kind.entries(), bits)];
+        for (index, entry) in entries.into_iter().enumerate() {
+            set_packed(&mut self.data, bits, index, entry);
+        }
+        self.palette = palette;
+        self.bits = bits;
+
+        self.entry_for(id)
+    }
+}
+
+/// Bits per entry, the palette and the data longs.
+/// Since 1.21.5 the amount of longs isn't sent, the client derives it from the bits per entry.
+impl PacketWrite for PalettedContainer {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.push(self.bits);
+
+        match &self.palette {
+            Palette::Single(id) => VarInt(*id as i32).write_to(buf),
+            Palette::Indirect(ids) => {
+                VarInt(ids.len() as i32).write_to(buf);
+                for id in ids {
+                    VarInt(*id as i32).write_to(buf);
+                }
+            }
+            Palette::Direct => {}
+        }
+
+        for long in &self.data {
+            buf.extend_from_slice(&long.to_be_bytes());
+        }
+    }
+}
+
+/// Smallest amount of bits holding `n` different values
+pub(crate) fn ceil_log2(n: u32) -> u8 {
+    if n <= 1 { 0 } else { (u32::BITS - (n - 1).leading_zeros()) as u8 }
+}
+
+/// Longs holding `entries` entries of `bits` bits, entries don't span across two longs
+pub(crate) fn packed_long_count(entries: usize, bits: u8) -> usize {
+    if bits == 0 { 0 } else { entries.div_ceil(64 / bits as usize) }
+}
+
+pub(crate) fn get_packed(data: &[u64], bits: u8, index: usize) -> u64 {
+    if bits == 0 {
+        return 0;
+    }
+
+    let per_long = 64 / bits as usize;
+    let shift = (index % per_long) * bits as usize;
+    (data[index / per_long] >> shift) & ((1 << bits) - 1)
+}
+
+pub(crate) fn set_packed(data: &mut [u64], bits: u8, index: usize, value: u64) {
+    let per_long = 64 / bits as usize;
+    let shift = (index % per_long) * bits as usize;
+    let mask = ((1 << bits) - 1) << shift;
+    let long = &mut data[index / per_long];
+    *long = (*long & !mask) | ((value << shift) & mask);
+}
@@ -0,0 +1,86 @@
+use crate::networking::data_types::PacketWrite;
+use crate::world::block::BlockState;
+use crate::world::chunk::paletted_container::{ContainerKind, PalettedContainer};
+
+/// 16x16x16 blocks of a chunk and their biomes, coordinates are relative to the section
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkSection {
+    /// Blocks that aren't air, the client skips rendering sections without any
+    block_count: u16,
+    block_states: PalettedContainer,
+    biomes: PalettedContainer,
+}
+
+impl ChunkSection {
+    /// Section of air in a biome
+    pub fn new(biome: u32) -> Self {
+        Self {
+            block_count: 0,
+            block_states: PalettedContainer::new(ContainerKind::BlockStates, BlockState::AIR.id() as u32),
+            biomes: PalettedContainer::new(ContainerKind::Biomes, biome),
+        }
+    }
+
+    pub fn block_count(&self) -> u16 {
+        self.block_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.block_count == 0
+    }
+
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> BlockState {
+        let id = self.block_states.get(ContainerKind::BlockStates.index(x, y, z));
+        // Only ids of BlockState::id are stored
+        BlockState::from_id(id as u16).unwrap_or_default()
+    }
+
+    /// Sets a block and returns the one it replaced
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, state: BlockState) -> BlockState {
+        let index = ContainerKind::BlockStates.index(x, y, z);
+        let previous = BlockState::from_id(self.block_states.set(index, state.id() as u32) as u16).unwrap_or_default();
+
+        match (previous.is_air(), state.is_air()) {
+            (true, false) => self.block_count += 1,
+            (false, true) => self.block_count -= 1,
+            _ => {}
+        }
+        previous
+    }
+
+    /// Sets every block of the section to a state
+    pub fn fill(&mut self, state: BlockState) {
+        self.block_states.fill(state.id() as u32);
+        self.block_count = if state.is_air() { 0 } else { ContainerKind::BlockStates.entries() as u16 };
+    }
+
+    /// Biome id of a 4x4x4 cell, coordinates are from 0 to 3
+    pub fn get_biome(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.biomes.get(ContainerKind::Biomes.index(x, y, z))
+    }
+
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, biome: u32) -> u32 {
+        self.biomes.set(ContainerKind::Biomes.index(x, y, z), biome)
+    }
+
+    pub fn fill_biome(&mut self, biome: u32) {
+        self.biomes.fill(biome);
+    }
+
+    pub fn block_states(&self) -> &PalettedContainer {
+        &self.block_states
+    }
+
+    pub fn biomes(&self) -> &PalettedContainer {
+        &self.biomes
+    }
+}
+
+/// Block count, block states and biomes, as sections are laid out in the data of Chunk Data
+impl PacketWrite for ChunkSection {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        (self.block_count as i16).write_to(buf);
+        self.block_states.write_to(buf);
+        self.biomes.write_to(buf);
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+/// Rate the client asks for at first, vanilla's
+const INITIAL_CHUNKS_PER_TICK: f32 = 9.0;
+const MIN_CHUNKS_PER_TICK: f32 = 0.01;
+const MAX_CHUNKS_PER_TICK: f32 = 64.0;
+/// Batches sent ahead of the acknowledgements once the client answered one, a single one before
+const MAX_UNACKNOWLEDGED_BATCHES: u32 = 10;
+
+/// Chunks a player has loaded and the pace new ones are sent at, like vanilla's player chunk sender.
+/// Chunks within the view distance of the center are sent nearest first, in batches sized by the rate
+/// the client reports in "Chunk batch received" after each batch.
+#[derive(Debug, Clone)]
+pub struct ChunkSender {
+    /// Chunk the view is centered on, None until the first update
+    center: Option<(i32, i32)>,
+    view_distance: u8,
+    /// Chunks sent and not unloaded
+    loaded: HashSet<(i32, i32)>,
+    desired_chunks_per_tick: f32,
+    batch_quota: f32,
+    unacknowledged_batches: u32,
+    max_unacknowledged_batches: u32,
+}
+
+impl ChunkSender {
+    pub fn new() -> Self {
+        ChunkSender {
+            center: None,
+            view_distance: 0,
+            loaded: HashSet::new(),
+            desired_chunks_per_tick: INITIAL_CHUNKS_PER_TICK,
+            batch_quota: 0.0,
+            unacknowledged_batches: 0,
+            max_unacknowledged_batches: 1,
+        }
+    }
+
+    pub fn center(&self) -> Option<(i32, i32)> {
+        self.center
+    }
+
+    pub fn loaded(&self) -> &HashSet<(i32, i32)> {
+        &self.loaded
+    }
+
+    pub fn is_in_view(&self, chunk: (i32, i32)) -> bool {
+        self.center.is_some_and(|center| {
+            let distance = self.view_distance as i32;
+            (chunk.0 - center.0).abs() <= distance && (chunk.1 - center.1).abs() <= distance
+        })
+    }
+
+    /// Moves the view, returning None if neither the center nor the distance changed,
+    /// else the loaded chunks that left it, which the client has to unload
+    pub fn move_view(&mut self, center: (i32, i32), view_distance: u8) -> Option<Vec<(i32, i32)>> {
+        if self.center == Some(center) && self.view_distance == view_distance {
+            return None;
+        }
+
+        self.center = Some(center);
+        self.view_distance = view_distance;
+
+        let left: Vec<(i32, i32)> = self.loaded.iter().copied().filter(|chunk| !self.is_in_view(*chunk)).collect();
+        for chunk in &left {
+            self.loaded.remove(chunk);
+        }
+
+        Some(left)
+    }
+
+    /// Chunks to send this tick, nearest first, which count as loaded from now on.
+    /// Empty while the client hasn't acknowledged enough batches or the rate allows less than one chunk.
+    pub fn next_batch(&mut self) -> Vec<(i32, i32)> {
+        let Some(center) = self.center else {
+            return Vec::new();
+        };
+
+        if self.unacknowledged_batches >= self.max_unacknowledged_batches {
+            return Vec::new();
+        }
+
+        // Slow rates build the quota up over several ticks, without going over one tick's worth
+        self.batch_quota = (self.batch_quota + self.desired_chunks_per_tick).min(self.desired_chunks_per_tick.max(1.0));
+        if self.batch_quota < 1.0 {
+            return Vec::new();
+        }
+
+        let distance = self.view_distance as i32;
+        let mut missing: Vec<(i32, i32)> = (-distance..=distance)
+            .flat_map(|dx| (-distance..=distance).map(move |dz| (center.0 + dx, center.1 + dz)))
+            .filter(|chunk| !self.loaded.contains(chunk))
+            .collect();
+        missing.sort_by_key(|(x, z)| (x - center.0).pow(2) + (z - center.1).pow(2));
+        missing.truncate(self.batch_quota as usize);
+
+        if missing.is_empty() {
+            return missing;
+        }
+
+        self.loaded.extend(missing.iter().copied());
+        self.unacknowledged_batches += 1;
+        self.batch_quota -= missing.len() as f32;
+        missing
+    }
+
+    /// Applies the rate the client reported for a batch it finished receiving
+    pub fn on_batch_received(&mut self, chunks_per_tick: f32) {
+        self.unacknowledged_batches = self.unacknowledged_batches.saturating_sub(1);
+        self.desired_chunks_per_tick = if chunks_per_tick.is_nan() {
+            MIN_CHUNKS_PER_TICK
+        } else {
+            chunks_per_tick.clamp(MIN_CHUNKS_PER_TICK, MAX_CHUNKS_PER_TICK)
+        };
+
+        if self.unacknowledged_batches == 0 {
+            self.batch_quota = 1.0;
+        }
+        self.max_unacknowledged_batches = MAX_UNACKNOWLEDGED_BATCHES;
+    }
+}
+
+impl Default for ChunkSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,15 +1,147 @@
-use tokio::sync::mpsc;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use crate::config::ResourcePackConfig;
 use crate::networking::account::Account;
-use crate::networking::connection::{send_packet, NetMessage};
-use crate::networking::data_types::PacketWrite;
+use crate::networking::connection::ConnectionPhase;
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::raw_bytes::RawBytes;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::networking::packets::common::add_resource_pack_response::AddResourcePackResponsePacket;
+use crate::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use crate::networking::packets::common::remove_resource_pack_response::RemoveResourcePackResponsePacket;
+use crate::networking::packets::common::resource_pack_response_request::ResourcePackStatus;
+use crate::networking::protocol_version::ProtocolVersion;
+use crate::networking::writer::{PacketWriter, SharedPacket};
+use crate::networking::packets::play::start_configuration_response::StartConfigurationResponsePacket;
+use crate::world::chunk::sender::ChunkSender;
+
+/// Bounds of the coordinates a client may report, like vanilla's
+const MAX_HORIZONTAL_POSITION: f64 = 3.0e7;
+const MAX_VERTICAL_POSITION: f64 = 2.0e7;
 
 pub struct Player {
     pub account: Account,
-    pub writer: mpsc::Sender<NetMessage>,
+    pub writer: PacketWriter,
+    pub phase: ConnectionPhase,
+    pub protocol: ProtocolVersion,
+    /// Pushed packs and the last status their client reported, None until it answers
+    pub resource_packs: HashMap<Uuid, Option<ResourcePackStatus>>,
+    /// Sent by the client on "minecraft:brand"
+    pub brand: Option<String>,
+    /// Custom channels the client declared with "minecraft:register"
+    pub channels: HashSet<Identifier>,
+    /// "Start configuration" was sent and the client has yet to acknowledge it
+    pub configuration_requested: bool,
+    /// "Select known packs" was sent in this configuration round and the client has yet to answer it
+    pub known_packs_requested: bool,
+    /// "Finish configuration" was sent and the client has yet to acknowledge it.
+    /// The client already reads play packets, so nothing of the configuration phase may be sent.
+    pub finish_configuration_sent: bool,
+    /// Feet position as last reported by the client
+    pub position: (f64, f64, f64),
+    /// Sent in the client information, None until then
+    pub client_view_distance: Option<u8>,
+    /// Set once the play login sequence was sent, the world streams chunks to the player from then on
+    pub chunk_sender: Option<ChunkSender>,
 }
 
 impl Player {
-    pub async fn send_packet<T: PacketWrite>(&mut self, packet_id: i32, packet: T) -> anyhow::Result<()> {
-        send_packet(&self.writer, packet_id, packet).await
+    pub fn new(account: Account, writer: PacketWriter, phase: ConnectionPhase, protocol: ProtocolVersion) -> Self {
+        Player {
+            account,
+            writer,
+            phase,
+            protocol,
+            resource_packs: HashMap::new(),
+            brand: None,
+            channels: HashSet::new(),
+            configuration_requested: false,
+            known_packs_requested: false,
+            finish_configuration_sent: false,
+            position: (0.0, 0.0, 0.0),
+            client_view_distance: None,
+            chunk_sender: None,
+        }
+    }
+
+    /// Position a client reported, clamped to the bounds vanilla accepts.
+    /// None if a coordinate isn't a finite number, vanilla kicks such clients.
+    pub fn clamp_position(x: f64, y: f64, z: f64) -> Option<(f64, f64, f64)> {
+        if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+            return None;
+        }
+
+        Some((
+            x.clamp(-MAX_HORIZONTAL_POSITION, MAX_HORIZONTAL_POSITION),
+            y.clamp(-MAX_VERTICAL_POSITION, MAX_VERTICAL_POSITION),
+            z.clamp(-MAX_HORIZONTAL_POSITION, MAX_HORIZONTAL_POSITION),
+        ))
     }
-}
\ No newline at end of file
+
+    /// Chunks sent around the player: the server's view distance, or the client's if it's smaller
+    pub fn view_distance(&self, server_view_distance: u8) -> u8 {
+        self.client_view_distance.map_or(server_view_distance, |distance| distance.min(server_view_distance))
+    }
+
+    /// Sends a packet with the id and layout it has in the player's version and phase
+    pub async fn send_packet<T: ClientboundPacket>(&mut self, packet: T) -> anyhow::Result<()> {
+        self.writer.send_packet(self.protocol, self.phase, packet)
+    }
+
+    /// Sends a broadcast packet, reusing its frame if another player of the same version and phase got it
+    pub fn send_shared<T: ClientboundPacket>(&self, packet: &mut SharedPacket<'_, T>) -> anyhow::Result<()> {
+        let frame = packet.frame(self.protocol, self.phase)?;
+        self.writer.send_shared(self.protocol, self.phase, frame)
+    }
+
+    /// Sends a payload on a custom channel, only if the client registered it.
+    /// Returns whether the message was sent.
+    pub async fn send_plugin_message(&mut self, channel: &Identifier, data: Vec<u8>) -> anyhow::Result<bool> {
+        if !matches!(self.phase, ConnectionPhase::Configuration | ConnectionPhase::Play) {
+            return Ok(false);
+        }
+
+        if !self.channels.contains(channel) {
+            return Ok(false);
+        }
+
+        let packet = PluginMessageResponsePacket { channel: channel.clone(), data: RawBytes(data) };
+        self.send_packet(packet).await?;
+
+        Ok(true)
+    }
+
+    /// Moves a player in play back into the configuration phase, so new registries,
+    /// resource packs or data pack changes can be pushed before a fresh login sequence.
+    /// Only queues the packet, so it can be called while holding the player list entry.
+    pub fn start_configuration(&mut self) -> anyhow::Result<()> {
+        if self.phase != ConnectionPhase::Play {
+            return Err(anyhow::anyhow!("Player {} is not in the PLAY phase", self.account.username));
+        }
+
+        self.writer.send_packet(self.protocol, self.phase, StartConfigurationResponsePacket { })?;
+        self.configuration_requested = true;
+        // The client drops its chunks when it leaves play
+        self.chunk_sender = None;
+        Ok(())
+    }
+
+    /// Pushes a resource pack, the client answers with its progress through "Resource pack response"
+    pub async fn push_resource_pack(&mut self, pack: &ResourcePackConfig) -> anyhow::Result<()> {
+        self.send_packet(AddResourcePackResponsePacket::from(pack)).await?;
+        self.resource_packs.insert(pack.id, None);
+        Ok(())
+    }
+
+    /// Removes a pushed resource pack, or all of them when no uuid is given
+    pub async fn remove_resource_pack(&mut self, uuid: Option<Uuid>) -> anyhow::Result<()> {
+        self.send_packet(RemoveResourcePackResponsePacket { uuid }).await?;
+
+        match uuid {
+            Some(uuid) => { self.resource_packs.remove(&uuid); }
+            None => self.resource_packs.clear(),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+//! Terrain of the chunks the world generates when a player first needs them.
+
+use crate::world::block::Block;
+use crate::world::chunk::{Chunk, OVERWORLD_MIN_Y};
+
+/// Layers of the flat world from the bottom of the overworld up
+const FLAT_LAYERS: [Block; 4] = [Block::Stone, Block::Dirt, Block::Dirt, Block::GrassBlock];
+
+/// Overworld chunk of flat layers of stone, dirt and grass at the bottom of the world, air above
+pub fn flat_chunk(x: i32, z: i32) -> Chunk {
+    let mut chunk = Chunk::overworld(x, z);
+
+    for (layer, block) in FLAT_LAYERS.iter().enumerate() {
+        let y = OVERWORLD_MIN_Y + layer as i32;
+        for block_z in 0..16 {
+            for block_x in 0..16 {
+                chunk.set_block(block_x, y, block_z, block.default_state());
+            }
+        }
+    }
+
+    chunk
+}
@@ -0,0 +1,29 @@
+//! Item types, generated by build.rs from the vanilla items report in reports/.
+
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::data_types::item_stack::ItemStack;
+
+include!(concat!(env!("OUT_DIR"), "/items.rs"));
+
+impl Item {
+    pub fn id(self) -> i32 {
+        self as i32
+    }
+
+    pub fn from_id(id: i32) -> Option<Item> {
+        usize::try_from(id).ok().and_then(|id| Item::ALL.get(id).copied())
+    }
+
+    pub fn from_identifier(identifier: &Identifier) -> Option<Item> {
+        Item::from_name(&identifier.to_string())
+    }
+
+    pub fn identifier(self) -> Identifier {
+        Identifier::from(self.name())
+    }
+
+    /// Stack of the item without changes to its default components
+    pub fn stack(self, count: i32) -> ItemStack {
+        ItemStack::new(self.id(), count)
+    }
+}
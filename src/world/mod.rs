@@ -1,21 +1,51 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::{interval, MissedTickBehavior};
+use uuid::Uuid;
+use crate::config::WorldConfig;
+use crate::networking::connection::ConnectionPhase;
+use crate::networking::data_types::identifier::Identifier;
+use crate::networking::metrics;
+use crate::networking::data_types::raw_bytes::RawBytes;
+use crate::networking::packets::clientbound::ClientboundPacket;
+use crate::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use crate::networking::data_types::var_int::VarInt;
+use crate::networking::packets::play::chunk_batch_finished_response::ChunkBatchFinishedResponsePacket;
+use crate::networking::packets::play::chunk_batch_start_response::ChunkBatchStartResponsePacket;
+use crate::networking::packets::play::game_event_response::GameEventResponsePacket;
 use crate::networking::packets::play::keep_alive_response::KeepAliveResponsePacket;
+use crate::networking::packets::play::set_center_chunk_response::SetCenterChunkResponsePacket;
+use crate::networking::packets::play::unload_chunk_response::UnloadChunkResponsePacket;
+use crate::networking::writer::SharedPacket;
+use crate::world::chunk::Chunk;
+use crate::world::entities::player::Player;
 use crate::PlayerList;
 
+pub mod block;
+pub mod chunk;
 pub mod entities;
+pub mod generator;
+pub mod item;
+
+/// Ticks between two sweeps of the chunks no player has loaded
+const CHUNK_EVICTION_INTERVAL: u64 = 20;
 
 pub struct World {
     pub players: PlayerList,
     pub tick_count: AtomicU64,
+    pub config: WorldConfig,
+    /// Generated chunks that players have loaded, the others are dropped every [`CHUNK_EVICTION_INTERVAL`] ticks
+    pub chunks: HashMap<(i32, i32), Chunk>,
 }
 
 impl World {
-    pub fn new(player_list: PlayerList) -> Self {
+    pub fn new(player_list: PlayerList, config: WorldConfig) -> Self {
         World {
             players: player_list,
             tick_count: AtomicU64::new(0),
+            config,
+            chunks: HashMap::new(),
         }
     }
 
@@ -25,7 +55,10 @@ impl World {
 
         loop {
             interval.tick().await;
+
+            let start = Instant::now();
             self.tick().await;
+            metrics::record_tick(start.elapsed());
         }
     }
 
@@ -33,22 +66,144 @@ impl World {
         let current_tick = self.tick_count.load(Ordering::Relaxed);
 
         // Keep Alive (Every 15 seconds = 300 ticks)
-        if current_tick % 300 == 0 {
+        if current_tick.is_multiple_of(300) {
             self.broadcast_keep_alive().await;
         }
 
+        self.send_chunks();
+        if current_tick.is_multiple_of(CHUNK_EVICTION_INTERVAL) {
+            self.evict_unused_chunks();
+        }
+
         self.tick_count.fetch_add(1, Ordering::Relaxed);
     }
 
     async fn broadcast_keep_alive(&mut self) {
+        // Keep alive only exists once the player is being configured, and the id it has in
+        // configuration means something else to a client that got "Finish configuration"
+        let sent = self.broadcast(&KeepAliveResponsePacket::new(), |player| {
+            matches!(player.phase, ConnectionPhase::Configuration | ConnectionPhase::Play)
+                && !player.finish_configuration_sent
+        });
+
+        if sent > 0 {
+            println!("Sent keep alive to {} players", sent);
+        }
+    }
+
+    /// Moves the view of every player in play to where they are and sends them the chunks
+    /// the client's acknowledgements allow for this tick
+    fn send_chunks(&mut self) {
         for mut entry in self.players.iter_mut() {
             let player = entry.value_mut();
-            let keep_alive_packet = KeepAliveResponsePacket::new();
+            if player.phase != ConnectionPhase::Play || player.chunk_sender.is_none() {
+                continue;
+            }
 
-            print!("Sending keep alive packet for {}", player.account.username);
+            if let Err(e) = stream_chunks(player, &mut self.chunks, self.config.view_distance) {
+                eprintln!("Error sending chunks to player ({}, {}): {:?}", player.account.username, player.account.uuid, e);
+            }
+        }
+    }
 
-            player.send_packet(0x2B, keep_alive_packet).await
-                .expect(format!("Error sending keepalive to player ({}, {})", player.account.username.as_str(), player.account.uuid.to_string()).as_str());
+    /// Drops the generated chunks that no player has loaded, they are generated again when needed.
+    /// Returns the amount of chunks dropped.
+    pub fn evict_unused_chunks(&mut self) -> usize {
+        let mut used = HashSet::new();
+        for entry in self.players.iter() {
+            if let Some(sender) = &entry.value().chunk_sender {
+                used.extend(sender.loaded().iter().copied());
+            }
         }
+
+        let before = self.chunks.len();
+        self.chunks.retain(|position, _| used.contains(position));
+        before - self.chunks.len()
     }
+
+    /// See [`broadcast`]
+    pub fn broadcast<T: ClientboundPacket>(&self, packet: &T, filter: impl Fn(&Player) -> bool) -> usize {
+        broadcast(&self.players, packet, filter)
+    }
+
+    /// Sends a payload on a custom channel to every player that registered it.
+    /// Returns the amount of players that received it.
+    pub fn broadcast_plugin_message(&self, channel: &Identifier, data: &[u8]) -> usize {
+        let packet = PluginMessageResponsePacket { channel: channel.clone(), data: RawBytes(data.to_vec()) };
+
+        self.broadcast(&packet, |player| {
+            matches!(player.phase, ConnectionPhase::Configuration | ConnectionPhase::Play)
+                && player.channels.contains(channel)
+        })
+    }
+
+    /// Sends a player in play back to the configuration phase, see [`entities::player::Player::start_configuration`]
+    pub fn start_configuration(&self, uuid: &Uuid) -> anyhow::Result<()> {
+        let mut player = self.players.get_mut(uuid)
+            .ok_or_else(|| anyhow::anyhow!("Player {} is not online", uuid))?;
+
+        player.start_configuration()
+    }
+}
+
+/// Chunk holding a block coordinate
+pub fn chunk_coordinate(block: f64) -> i32 {
+    (block.floor() as i32) >> 4
+}
+
+/// Sends one player the chunk updates of a tick, generating the chunks it's the first to need
+fn stream_chunks(player: &mut Player, chunks: &mut HashMap<(i32, i32), Chunk>, server_view_distance: u8) -> anyhow::Result<()> {
+    let view_distance = player.view_distance(server_view_distance);
+    let center = (chunk_coordinate(player.position.0), chunk_coordinate(player.position.2));
+    let (protocol, phase) = (player.protocol, player.phase);
+    let Some(sender) = player.chunk_sender.as_mut() else {
+        return Ok(());
+    };
+
+    // Until its first chunks come in the client shows the loading screen
+    if sender.center().is_none() {
+        player.writer.send_packet(protocol, phase, GameEventResponsePacket::start_waiting_for_chunks())?;
+    }
+
+    if let Some(forgotten) = sender.move_view(center, view_distance) {
+        player.writer.send_packet(protocol, phase, SetCenterChunkResponsePacket { chunk_x: VarInt(center.0), chunk_z: VarInt(center.1) })?;
+        for (chunk_x, chunk_z) in forgotten {
+            player.writer.send_packet(protocol, phase, UnloadChunkResponsePacket { chunk_z, chunk_x })?;
+        }
+    }
+
+    let batch = sender.next_batch();
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    player.writer.send_packet(protocol, phase, ChunkBatchStartResponsePacket {})?;
+    for (x, z) in &batch {
+        let chunk = chunks.entry((*x, *z)).or_insert_with(|| generator::flat_chunk(*x, *z));
+        player.writer.send_packet(protocol, phase, chunk.to_packet())?;
+    }
+    player.writer.send_packet(protocol, phase, ChunkBatchFinishedResponsePacket { batch_size: VarInt(batch.len() as i32) })?;
+
+    Ok(())
+}
+
+/// Sends a packet to every player matching the filter, encoding it once per version and phase.
+/// Returns the amount of players that received it.
+pub fn broadcast<T: ClientboundPacket>(players: &PlayerList, packet: &T, filter: impl Fn(&Player) -> bool) -> usize {
+    let mut shared = SharedPacket::new(packet);
+    let mut sent = 0;
+
+    for entry in players.iter() {
+        let player = entry.value();
+        if !filter(player) {
+            continue;
+        }
+
+        match player.send_shared(&mut shared) {
+            Ok(()) => sent += 1,
+            Err(e) => eprintln!("Error broadcasting {} to player ({}, {}): {:?}", T::NAME, player.account.username, player.account.uuid, e),
+        }
+    }
+
+    sent
 }
@@ -0,0 +1,112 @@
+//! Frames encoded into pooled buffers: the length prefix back-patched in front of the body, and buffers reused.
+
+use std::sync::Mutex;
+use nullspace::networking::buffer_pool::{self, PooledFrame};
+use nullspace::networking::data_types::ByteReader;
+use nullspace::networking::data_types::var_int::VarInt;
+
+/// The pool is shared by the whole process, tests that take buffers from it run one at a time
+static POOL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Body lengths around the limits of 1, 2 and 3 byte length prefixes, with their prefix size
+const BOUNDARIES: [(usize, usize); 6] = [(0, 1), (127, 1), (128, 2), (16383, 2), (16384, 3), (2097151, 3)];
+
+fn body(length: usize) -> Vec<u8> {
+    (0..length).map(|index| index as u8).collect()
+}
+
+/// Reads one frame back, returns its prefix size and body
+fn read_frame<'a>(reader: &mut ByteReader<'a>) -> (usize, &'a [u8]) {
+    let before = reader.remaining();
+    let length = reader.read_field::<VarInt>().unwrap().0 as usize;
+    (before - reader.remaining(), reader.read_bytes(length).unwrap())
+}
+
+#[test]
+fn single_frames_take_the_shortest_length_prefix() {
+    let _lock = POOL_LOCK.lock().unwrap();
+
+    for (length, prefix_size) in BOUNDARIES {
+        let expected = body(length);
+        let frame = PooledFrame::encode(|buffer| {
+            buffer.extend_from_slice(&expected);
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(frame.len(), prefix_size + length, "body of {} bytes", length);
+        let mut reader = ByteReader::new(&frame);
+        assert_eq!(read_frame(&mut reader), (prefix_size, &expected[..]));
+        assert!(reader.is_empty());
+    }
+}
+
+#[test]
+fn appended_frames_are_moved_against_the_previous_one() {
+    let _lock = POOL_LOCK.lock().unwrap();
+
+    let mut buffer = vec![0xAA];
+    for (length, _) in BOUNDARIES {
+        buffer_pool::append_frame(&mut buffer, |buffer| {
+            buffer.extend_from_slice(&body(length));
+            Ok(())
+        }).unwrap();
+    }
+
+    let mut reader = ByteReader::new(&buffer[1..]);
+    for (length, prefix_size) in BOUNDARIES {
+        assert_eq!(read_frame(&mut reader), (prefix_size, &body(length)[..]));
+    }
+    assert!(reader.is_empty());
+    assert_eq!(buffer[0], 0xAA);
+}
+
+#[test]
+fn frames_over_the_limit_are_refused() {
+    let _lock = POOL_LOCK.lock().unwrap();
+    let too_long = body(2097152);
+
+    assert!(PooledFrame::encode(|buffer| {
+        buffer.extend_from_slice(&too_long);
+        Ok(())
+    }).is_err());
+
+    // The frames already in the buffer are kept, the failed one leaves nothing behind
+    let mut buffer = vec![0x01, 0x00];
+    assert!(buffer_pool::append_frame(&mut buffer, |buffer| {
+        buffer.extend_from_slice(&too_long);
+        Ok(())
+    }).is_err());
+    assert!(buffer_pool::append_frame(&mut buffer, |buffer| {
+        buffer.push(0x05);
+        Err(anyhow::anyhow!("Body can't be written"))
+    }).is_err());
+    assert_eq!(buffer, [0x01, 0x00]);
+}
+
+#[test]
+fn dropped_frames_return_their_buffer_to_the_pool() {
+    let _lock = POOL_LOCK.lock().unwrap();
+
+    let frame = PooledFrame::encode(|buffer| {
+        buffer.extend_from_slice(&body(1000));
+        Ok(())
+    }).unwrap();
+    let address = frame.as_ptr() as usize;
+    drop(frame);
+
+    // The last released buffer is handed out first, emptied but with its memory
+    let buffer = buffer_pool::take();
+    assert!(buffer.is_empty());
+    assert!(buffer.capacity() >= 1003);
+    // The frame started after the unused prefix byte
+    assert_eq!(buffer.as_ptr() as usize + 1, address);
+    drop(buffer);
+
+    // Buffers that grew too big are freed instead
+    let frame = PooledFrame::encode(|buffer| {
+        buffer.extend_from_slice(&body(100_000));
+        Ok(())
+    }).unwrap();
+    drop(frame);
+    assert!(buffer_pool::take().capacity() < 100_000);
+}
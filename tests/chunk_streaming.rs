@@ -0,0 +1,193 @@
+//! Chunks sent around players: the pace set by the batch acknowledgements, the effective view distance
+//! and the chunks unloaded as players move.
+
+use nullspace::PlayerList;
+use nullspace::config::{ServerConfig, WorldConfig};
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::packets::play::chunk_batch_finished_response::ChunkBatchFinishedResponsePacket;
+use nullspace::networking::packets::play::chunk_batch_received_request::ChunkBatchReceivedRequestPacket;
+use nullspace::networking::packets::play::chunk_batch_start_response::ChunkBatchStartResponsePacket;
+use nullspace::networking::packets::play::game_event_response::GameEventResponsePacket;
+use nullspace::networking::packets::play::set_center_chunk_response::SetCenterChunkResponsePacket;
+use nullspace::networking::packets::play::set_player_position_request::SetPlayerPositionRequestPacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+use nullspace::testing::{self, TestClient};
+use nullspace::world::chunk::sender::ChunkSender;
+use nullspace::world::{chunk_coordinate, generator, World};
+use nullspace::world::entities::player::Player;
+
+fn config(view_distance: u8) -> ServerConfig {
+    ServerConfig { world: WorldConfig { view_distance }, ..ServerConfig::default() }
+}
+
+fn square(center: (i32, i32), distance: i32) -> Vec<(i32, i32)> {
+    (-distance..=distance)
+        .flat_map(|dx| (-distance..=distance).map(move |dz| (center.0 + dx, center.1 + dz)))
+        .collect()
+}
+
+#[test]
+fn first_batch_is_the_nearest_9_chunks() {
+    let mut sender = ChunkSender::new();
+    assert_eq!(sender.move_view((0, 0), 10), Some(Vec::new()));
+
+    let batch = sender.next_batch();
+    assert_eq!(batch.len(), 9);
+    assert_eq!(batch[0], (0, 0));
+    assert!(batch.iter().all(|(x, z)| x.abs() <= 1 && z.abs() <= 1));
+
+    // A single batch is in flight until the client answers the first one
+    assert!(sender.next_batch().is_empty());
+    sender.on_batch_received(9.0);
+    assert_eq!(sender.next_batch().len(), 9);
+}
+
+#[test]
+fn batches_follow_the_reported_rate() {
+    let mut sender = ChunkSender::new();
+    sender.move_view((0, 0), 10);
+    sender.next_batch();
+
+    sender.on_batch_received(1.0);
+    assert_eq!(sender.next_batch().len(), 1);
+
+    // Up to 10 batches wait for an acknowledgement once the client answered one
+    for _ in 0..9 {
+        assert_eq!(sender.next_batch().len(), 1);
+    }
+    assert!(sender.next_batch().is_empty());
+
+    // Slow rates take several ticks per chunk
+    for _ in 0..10 {
+        sender.on_batch_received(0.5);
+    }
+    assert_eq!(sender.next_batch().len(), 1);
+    assert!(sender.next_batch().is_empty());
+    assert_eq!(sender.next_batch().len(), 1);
+
+    // Out of range rates are clamped, NaN counts as the slowest one
+    sender.on_batch_received(f32::NAN);
+    sender.on_batch_received(f32::NAN);
+    assert_eq!(sender.next_batch().len(), 1);
+    assert!(sender.next_batch().is_empty());
+}
+
+#[test]
+fn moving_forgets_chunks_out_of_view() {
+    let mut sender = ChunkSender::new();
+    sender.move_view((0, 0), 1);
+    sender.next_batch();
+    assert_eq!(sender.loaded().len(), 9);
+
+    assert_eq!(sender.move_view((0, 0), 1), None);
+
+    let mut forgotten = sender.move_view((1, 0), 1).unwrap();
+    forgotten.sort();
+    assert_eq!(forgotten, [(-1, -1), (-1, 0), (-1, 1)]);
+    assert_eq!(sender.loaded().len(), 6);
+    assert!(sender.is_in_view((2, 1)));
+    assert!(!sender.is_in_view((-1, 0)));
+}
+
+#[test]
+fn chunk_coordinates_round_down() {
+    assert_eq!(chunk_coordinate(0.0), 0);
+    assert_eq!(chunk_coordinate(15.9), 0);
+    assert_eq!(chunk_coordinate(16.0), 1);
+    assert_eq!(chunk_coordinate(-0.5), -1);
+    assert_eq!(chunk_coordinate(-16.0), -1);
+    assert_eq!(chunk_coordinate(-16.5), -2);
+}
+
+#[tokio::test]
+async fn chunks_are_sent_in_batches_after_login() {
+    let (address, _) = testing::start_server(config(2)).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::V1_21_11, "Steve").await.unwrap();
+    client.handle_chunks = false;
+
+    let event = client.expect::<GameEventResponsePacket>().await.unwrap();
+    assert_eq!(event.event.0, GameEventResponsePacket::START_WAITING_FOR_CHUNKS);
+    let center = client.expect::<SetCenterChunkResponsePacket>().await.unwrap();
+    assert_eq!((center.chunk_x, center.chunk_z), (VarInt(0), VarInt(0)));
+
+    client.expect::<ChunkBatchStartResponsePacket>().await.unwrap();
+    for _ in 0..9 {
+        let packet = client.expect_named("minecraft:level_chunk_with_light").await.unwrap();
+        client.handle_chunk_packet(&packet).await.unwrap();
+    }
+    assert!(client.chunks.contains(&(0, 0)));
+    assert!(client.chunks.iter().all(|(x, z)| x.abs() <= 1 && z.abs() <= 1));
+
+    let finished = client.expect::<ChunkBatchFinishedResponsePacket>().await.unwrap();
+    assert_eq!(finished.batch_size, VarInt(9));
+
+    // The rest of the server's view distance, the client asked for 10
+    client.send(ChunkBatchReceivedRequestPacket { chunks_per_tick: 64.0 }).await.unwrap();
+    client.wait_for_chunks(|chunks| chunks.len() == 25).await.unwrap();
+    assert!(square((0, 0), 2).iter().all(|chunk| client.chunks.contains(chunk)));
+}
+
+#[tokio::test]
+async fn client_view_distance_caps_the_server_one() {
+    let (address, _) = testing::start_server(config(10)).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::V1_21_11).await.unwrap();
+    client.view_distance = 3;
+    client.handshake(testing::Intent::Login).await.unwrap();
+    client.login("Alex").await.unwrap();
+    client.begin_configuration().await.unwrap();
+    client.finish_configuration().await.unwrap();
+
+    client.wait_for_chunks(|chunks| chunks.len() == 49).await.unwrap();
+    assert!(square((0, 0), 3).iter().all(|chunk| client.chunks.contains(chunk)));
+
+    // The client lowering its view distance in play unloads the chunks past it
+    client.send(testing::client_information(2)).await.unwrap();
+    client.wait_for_chunks(|chunks| chunks.len() == 25).await.unwrap();
+    assert!(square((0, 0), 2).iter().all(|chunk| client.chunks.contains(chunk)));
+}
+
+#[tokio::test]
+async fn moving_loads_and_unloads_chunks() {
+    let (address, _) = testing::start_server(config(2)).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::V1_21_11, "Steve").await.unwrap();
+    client.wait_for_chunks(|chunks| chunks.len() == 25).await.unwrap();
+
+    client.send(SetPlayerPositionRequestPacket { x: 48.0, feet_y: -60.0, z: 8.0, flags: Byte(1) }).await.unwrap();
+    client.wait_for_chunks(|chunks| chunks.iter().all(|(x, _)| *x >= 1) && chunks.len() == 25).await.unwrap();
+
+    assert_eq!(client.center_chunk, (3, 0));
+    assert!(square((3, 0), 2).iter().all(|chunk| client.chunks.contains(chunk)));
+}
+
+#[test]
+fn chunks_no_player_has_loaded_are_evicted() {
+    let mut world = World::new(PlayerList::default(), WorldConfig::default());
+    for position in square((0, 0), 2) {
+        world.chunks.insert(position, generator::flat_chunk(position.0, position.1));
+    }
+
+    assert_eq!(world.evict_unused_chunks(), 25);
+    assert!(world.chunks.is_empty());
+}
+
+#[test]
+fn reported_positions_are_clamped() {
+    assert_eq!(Player::clamp_position(1.5, -60.0, 8.0), Some((1.5, -60.0, 8.0)));
+    assert_eq!(Player::clamp_position(1e12, -1e12, -1e12), Some((3.0e7, -2.0e7, -3.0e7)));
+    assert_eq!(Player::clamp_position(f64::NAN, 0.0, 0.0), None);
+    assert_eq!(Player::clamp_position(0.0, 0.0, f64::INFINITY), None);
+}
+
+#[tokio::test]
+async fn invalid_positions_are_kicked() {
+    let (address, players) = testing::start_server(config(2)).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::V1_21_11, "Steve").await.unwrap();
+
+    client.send(SetPlayerPositionRequestPacket { x: 1e12, feet_y: -60.0, z: 0.0, flags: Byte(1) }).await.unwrap();
+    client.wait_for_chunks(|chunks| chunks.contains(&(chunk_coordinate(3.0e7), 0))).await.unwrap();
+    assert_eq!(players.get(&client.uuid.unwrap()).unwrap().position, (3.0e7, -60.0, 0.0));
+
+    client.send(SetPlayerPositionRequestPacket { x: f64::NAN, feet_y: -60.0, z: 0.0, flags: Byte(1) }).await.unwrap();
+    client.expect_disconnect().await.unwrap();
+}
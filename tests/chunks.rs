@@ -0,0 +1,296 @@
+//! Chunk storage: palettes grow as blocks are set, and sections and chunks encode to the bytes of the
+//! 1.21.5+ Chunk Data and Update Light layout, written out by hand from the protocol documentation.
+
+use std::collections::HashMap;
+use proptest::prelude::*;
+use nullspace::networking::data_types::PacketWrite;
+use nullspace::world::block::{Block, BlockState};
+use nullspace::world::chunk::{Chunk, BIOME_COUNT, PLAINS_BIOME};
+use nullspace::world::chunk::light::LightSection;
+use nullspace::world::chunk::paletted_container::{ContainerKind, PalettedContainer, DIRECT_BLOCK_STATE_BITS};
+use nullspace::world::chunk::section::ChunkSection;
+
+fn encode(value: &impl PacketWrite) -> Vec<u8> {
+    let mut buf = Vec::new();
+    value.write_to(&mut buf);
+    buf
+}
+
+fn longs(values: &[u64]) -> Vec<u8> {
+    values.iter().flat_map(|long| long.to_be_bytes()).collect()
+}
+
+fn stone() -> BlockState {
+    Block::Stone.default_state()
+}
+
+#[test]
+fn empty_section_is_single_valued() {
+    let section = ChunkSection::new(PLAINS_BIOME);
+    // Block count, then 0 bits with the air id, then 0 bits with the plains id
+    assert_eq!(encode(&section), [0x00, 0x00, 0x00, 0x00, 0x00, 40]);
+}
+
+#[test]
+fn one_block_takes_an_indirect_palette_of_4_bits() {
+    let mut section = ChunkSection::new(PLAINS_BIOME);
+    section.set_block(0, 0, 0, stone());
+    section.set_block(15, 15, 15, stone());
+
+    let mut data = vec![0; 256];
+    data[0] = 1;
+    data[255] = 1 << 60;
+
+    let mut expected = vec![0x00, 0x02, 4, 2, 0x00, 0x01];
+    expected.extend(longs(&data));
+    expected.extend([0x00, 40]);
+    assert_eq!(encode(&section), expected);
+}
+
+#[test]
+fn entries_are_ordered_by_y_then_z_then_x() {
+    let mut section = ChunkSection::new(PLAINS_BIOME);
+    section.set_block(1, 0, 0, stone());
+    section.set_block(0, 0, 1, stone());
+    section.set_block(0, 1, 0, stone());
+
+    let blocks = encode(section.block_states());
+    let data = &blocks[4..];
+    assert_eq!(&data[..8], &longs(&[1 << 4])[..]);
+    assert_eq!(&data[8..16], &longs(&[1])[..]);
+    assert_eq!(&data[8 * 16..8 * 17], &longs(&[1])[..]);
+}
+
+#[test]
+fn palette_grows_to_5_bits_without_spanning_longs() {
+    let mut container = PalettedContainer::new(ContainerKind::BlockStates, 0);
+    for id in 0..17 {
+        container.set(id as usize, id);
+    }
+    assert_eq!(container.bits_per_entry(), 5);
+
+    // 12 entries of 5 bits per long, the top 4 bits are left empty
+    let mut data = vec![0u64; 4096usize.div_ceil(12)];
+    for id in 0..17u64 {
+        data[id as usize / 12] |= id << (id % 12 * 5);
+    }
+
+    let mut expected = vec![5, 17];
+    expected.extend(0..17);
+    expected.extend(longs(&data));
+    assert_eq!(encode(&container), expected);
+
+    for id in 0..17 {
+        assert_eq!(container.get(id as usize), id);
+    }
+    assert_eq!(container.get(4095), 0);
+}
+
+#[test]
+fn block_states_past_256_take_the_15_bit_direct_palette() {
+    let mut container = PalettedContainer::new(ContainerKind::BlockStates, 0);
+    // Ids up to the top of a 1.21.11 registry, past the 8 bits of indirect palettes
+    let ids: Vec<u32> = (0..300).map(|index| index * 97).collect();
+    for (index, id) in ids.iter().enumerate() {
+        container.set(index, *id);
+    }
+    assert_eq!(DIRECT_BLOCK_STATE_BITS, 15);
+    assert_eq!(container.bits_per_entry(), 15);
+
+    // 4 entries of 15 bits per long, the top 4 bits are left empty, and no palette
+    let mut data = vec![0u64; 1024];
+    for (index, id) in ids.iter().enumerate() {
+        data[index / 4] |= (*id as u64) << (index % 4 * 15);
+    }
+
+    let mut expected = vec![15];
+    expected.extend(longs(&data));
+    assert_eq!(encode(&container), expected);
+
+    assert_eq!(container.get(299), 299 * 97);
+    assert_eq!(container.get(300), 0);
+}
+
+#[test]
+fn biomes_go_from_single_to_indirect_to_direct() {
+    let mut container = PalettedContainer::new(ContainerKind::Biomes, PLAINS_BIOME);
+    container.set(63, 0);
+
+    // 1 bit, 64 entries in a single long
+    let mut expected = vec![1, 2, 40, 0];
+    expected.extend(longs(&[1 << 63]));
+    assert_eq!(encode(&container), expected);
+
+    for biome in 1..7 {
+        container.set(biome as usize, biome);
+    }
+    assert_eq!(container.bits_per_entry(), 3);
+
+    // A 9th biome goes past the 3 bits biome palettes can have
+    container.set(8, 57);
+    let direct_bits = (u32::BITS - (BIOME_COUNT - 1).leading_zeros()) as u8;
+    assert_eq!(container.bits_per_entry(), direct_bits);
+
+    let per_long = 64 / direct_bits as usize;
+    let mut data = vec![0u64; 64usize.div_ceil(per_long)];
+    for index in 0..64 {
+        let biome = match index {
+            0 | 7 | 9..=62 => PLAINS_BIOME,
+            8 => 57,
+            63 => 0,
+            other => other as u32,
+        };
+        data[index / per_long] |= (biome as u64) << (index % per_long * direct_bits as usize);
+    }
+
+    let mut expected = vec![direct_bits];
+    expected.extend(longs(&data));
+    assert_eq!(encode(&container), expected);
+}
+
+#[test]
+fn block_count_follows_non_air_blocks() {
+    let mut section = ChunkSection::new(PLAINS_BIOME);
+    assert_eq!(section.set_block(3, 4, 5, stone()), BlockState::AIR);
+    assert_eq!(section.set_block(3, 4, 5, Block::Dirt.default_state()), stone());
+    assert_eq!(section.block_count(), 1);
+
+    section.set_block(3, 4, 5, BlockState::AIR);
+    assert!(section.is_empty());
+
+    section.fill(stone());
+    assert_eq!(section.block_count(), 4096);
+    assert_eq!(encode(section.block_states()), [0x00, 0x01]);
+}
+
+#[test]
+fn chunk_encodes_to_chunk_data_and_update_light() {
+    let mut chunk = Chunk::new(3, -2, 0, 16);
+    chunk.set_block(0, 0, 0, stone());
+
+    let mut expected = Vec::new();
+    expected.extend(3i32.to_be_bytes());
+    expected.extend((-2i32).to_be_bytes());
+
+    // World surface, motion blocking and motion blocking no leaves, 5 bits for heights 0 to 16
+    expected.push(3);
+    for heightmap_type in [1, 4, 5] {
+        expected.extend([heightmap_type, 22]);
+        let mut data = vec![0; 22];
+        data[0] = 1;
+        expected.extend(longs(&data));
+    }
+
+    let section = encode(&chunk.sections()[0]);
+    assert_eq!(section.len(), 2056);
+    expected.extend([0x88, 0x10]);
+    expected.extend(section);
+    // No block entities
+    expected.push(0);
+
+    // Sky light in the section and the ones below and above it, no block light
+    expected.extend([1]);
+    expected.extend(longs(&[0b111]));
+    expected.extend([0, 0, 1]);
+    expected.extend(longs(&[0b111]));
+    expected.push(3);
+    for _ in 0..3 {
+        expected.extend([0x80, 0x10]);
+        expected.extend([0xFF; 2048]);
+    }
+    expected.push(0);
+
+    assert_eq!(encode(&chunk.to_packet()), expected);
+}
+
+#[test]
+fn overworld_chunks_cover_24_sections() {
+    let mut chunk = Chunk::overworld(0, 0);
+    assert_eq!(chunk.sections().len(), 24);
+
+    chunk.set_block(5, -64, 7, stone());
+    chunk.set_block(5, 319, 7, stone());
+    chunk.set_block(5, 320, 7, stone());
+    assert_eq!(chunk.get_block(5, 319, 7), stone());
+    assert_eq!(chunk.get_block(5, 320, 7), BlockState::AIR);
+    assert_eq!(chunk.sections()[0].block_count(), 1);
+
+    // 9 bits for heights 0 to 384, 7 per long
+    let heights = chunk.surface_heights();
+    assert_eq!(heights.len(), 37);
+    let column = 7 * 16 + 5;
+    assert_eq!((heights[column / 7] >> (column % 7 * 9)) & 0x1FF, 384);
+
+    let packet = chunk.to_packet();
+    assert_eq!(packet.light.sky_light_mask.0, [(1 << 26) - 1]);
+    assert_eq!(packet.light.empty_block_light_mask.0, [(1 << 26) - 1]);
+
+    chunk.set_block_light(5, 100, 7, 14);
+    assert_eq!(chunk.block_light(5, 100, 7), 14);
+    assert_eq!(chunk.block_light(4, 100, 7), 0);
+    // y 100 is in section 10, light section 11
+    assert_eq!(chunk.to_packet().light.block_light_mask.0, [1 << 11]);
+}
+
+#[test]
+fn uniform_light_sections_change_like_stored_ones() {
+    let mut section = LightSection::filled(15);
+    assert_eq!(section.as_bytes(), &[0xFF; LightSection::BYTES]);
+    assert_eq!(section, LightSection::filled(15));
+
+    section.set(3, 2, 1, 15);
+    assert_eq!(section, LightSection::filled(15));
+
+    section.set(1, 0, 0, 4);
+    assert_eq!(section.get(1, 0, 0), 4);
+    assert_eq!(section.get(0, 0, 0), 15);
+    assert_eq!(section.as_bytes()[0], 0x4F);
+    assert_ne!(section, LightSection::filled(15));
+
+    section.set(1, 0, 0, 15);
+    assert_eq!(section, LightSection::filled(15));
+    assert!(LightSection::filled(0).is_empty());
+}
+
+#[test]
+fn biomes_are_stored_per_4_blocks() {
+    let mut chunk = Chunk::overworld(0, 0);
+    chunk.set_biome(5, 70, 9, 3);
+    assert_eq!(chunk.get_biome(4, 68, 8), 3);
+    assert_eq!(chunk.get_biome(7, 71, 11), 3);
+    assert_eq!(chunk.get_biome(8, 70, 9), PLAINS_BIOME);
+    assert_eq!(chunk.get_biome(0, -1000, 0), PLAINS_BIOME);
+}
+
+proptest! {
+    /// Any sequence of sets reads back like a map, whichever palettes the container went through
+    #[test]
+    fn containers_read_back_what_was_set(sets in proptest::collection::vec((0usize..4096, 0u32..BlockState::COUNT as u32), 0..600)) {
+        let mut container = PalettedContainer::new(ContainerKind::BlockStates, 0);
+        let mut expected = HashMap::new();
+
+        for (index, id) in sets {
+            let previous = container.set(index, id);
+            prop_assert_eq!(previous, expected.insert(index, id).unwrap_or(0));
+        }
+
+        for index in 0..4096 {
+            prop_assert_eq!(container.get(index), expected.get(&index).copied().unwrap_or(0));
+        }
+    }
+
+    #[test]
+    fn biome_containers_read_back_what_was_set(sets in proptest::collection::vec((0usize..64, 0..BIOME_COUNT), 0..100)) {
+        let mut container = PalettedContainer::new(ContainerKind::Biomes, PLAINS_BIOME);
+        let mut expected = [PLAINS_BIOME; 64];
+
+        for (index, id) in sets {
+            container.set(index, id);
+            expected[index] = id;
+        }
+
+        for (index, id) in expected.into_iter().enumerate() {
+            prop_assert_eq!(container.get(index), id);
+        }
+    }
+}
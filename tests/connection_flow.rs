@@ -0,0 +1,418 @@
+//! Drives the server through every phase with the headless test client and checks each answer.
+
+use std::time::Duration;
+use uuid::Uuid;
+use nullspace::config::{ConnectionConfig, ResourcePackConfig, ServerConfig};
+use nullspace::networking::channels::encode_channel_list;
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::metrics::{self, Rejection};
+use nullspace::networking::data_types::bit_set::FixedBitSet;
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::identifier::Identifier;
+use nullspace::networking::data_types::raw_bytes::RawBytes;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::packets::common::plugin_message_request::PluginMessageRequestPacket;
+use nullspace::networking::packets::common::plugin_message_response::PluginMessageResponsePacket;
+use nullspace::networking::packets::common::resource_pack_response_request::{ResourcePackResponseRequestPacket, ResourcePackStatus};
+use nullspace::networking::packets::configuration::acknowledge_finish_configuration_request::AcknowledgeFinishConfigurationRequestPacket;
+use nullspace::networking::packets::configuration::finish_configuration_response::FinishConfigurationResponsePacket;
+use nullspace::networking::packets::configuration::known_packs_request::KnownPacksRequestPacket;
+use nullspace::networking::packets::handshake::HandshakePacket;
+use nullspace::networking::packets::login::login_acknowledged_request::LoginAcknowledgedRequestPacket;
+use nullspace::networking::packets::login::login_start_request::LoginStartRequestPacket;
+use nullspace::networking::packets::login::login_success_response::LoginSuccessResponsePacket;
+use nullspace::networking::packets::clientbound::ClientboundPacket;
+use nullspace::networking::packets::play::acknowledge_configuration_request::AcknowledgeConfigurationRequestPacket;
+use nullspace::networking::packets::play::chat_message_request::ChatMessageRequestPacket;
+use nullspace::networking::packets::play::client_tick_end_request::ClientTickEndRequestPacket;
+use nullspace::networking::packets::play::login_response::LoginResponsePacket;
+use nullspace::networking::packets::play::set_player_position_and_rotation_request::SetPlayerPositionAndRotationRequestPacket;
+use nullspace::networking::packets::play::start_configuration_response::StartConfigurationResponsePacket;
+use nullspace::networking::packets::play::system_chat_response::SystemChatResponsePacket;
+use nullspace::networking::packets::play::teleport_confirmation_request::TeleportConfirmationRequestPacket;
+use nullspace::networking::packets::status::ping_request::PingRequestPacket;
+use nullspace::networking::packets::status::pong_response::PongResponsePacket;
+use nullspace::networking::packets::status::status_request::StatusRequestPacket;
+use nullspace::networking::packets::status::status_response::StatusResponsePacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+use nullspace::networking::writer::SharedPacket;
+use nullspace::testing::{self, Intent, TestClient};
+
+#[tokio::test]
+async fn status_and_ping() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::V1_21_9).await.unwrap();
+    client.handshake(Intent::Status).await.unwrap();
+
+    client.send(StatusRequestPacket {}).await.unwrap();
+    let status = client.expect::<StatusResponsePacket>().await.unwrap();
+    let json: serde_json::Value = serde_json::from_str(&status.json_response).unwrap();
+    assert_eq!(json["version"]["protocol"], ProtocolVersion::V1_21_9.protocol());
+    assert_eq!(json["version"]["name"], ProtocolVersion::supported_range().to_string());
+
+    client.send(PingRequestPacket { timestamp: 1234567890 }).await.unwrap();
+    let pong = client.expect::<PongResponsePacket>().await.unwrap();
+    assert_eq!(pong.timestamp, 1234567890);
+
+    client.expect_closed().await.unwrap();
+}
+
+#[tokio::test]
+async fn join_1_21_11() {
+    let (address, players) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::V1_21_11).await.unwrap();
+    client.handshake(Intent::Login).await.unwrap();
+    client.login("Steve").await.unwrap();
+    client.begin_configuration().await.unwrap();
+    assert_eq!(client.finish_configuration().await.unwrap(), 13);
+
+    let player = players.get(&client.uuid.unwrap()).unwrap();
+    assert_eq!(player.phase, ConnectionPhase::Play);
+    assert_eq!(player.account.username, "Steve");
+    assert_eq!(player.brand.as_deref(), Some("vanilla"));
+}
+
+#[tokio::test]
+async fn join_1_21_9() {
+    let (address, players) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::V1_21_9).await.unwrap();
+    client.handshake(Intent::Login).await.unwrap();
+    client.login("Alex").await.unwrap();
+    client.begin_configuration().await.unwrap();
+    // No zombie nautilus variants or timelines before 1.21.11
+    assert_eq!(client.finish_configuration().await.unwrap(), 11);
+
+    let player = players.get(&client.uuid.unwrap()).unwrap();
+    assert_eq!(player.phase, ConnectionPhase::Play);
+    assert_eq!(player.protocol, ProtocolVersion::V1_21_9);
+}
+
+#[tokio::test]
+async fn transfer_logs_in_when_accepted() {
+    let mut config = ServerConfig::default();
+    config.connection.accept_transfers = true;
+    let (address, _) = testing::start_server(config).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Transfer).await.unwrap();
+    client.login("Steve").await.unwrap();
+    client.begin_configuration().await.unwrap();
+    client.finish_configuration().await.unwrap();
+}
+
+#[tokio::test]
+async fn transfer_is_disconnected_by_default() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Transfer).await.unwrap();
+
+    // The login disconnect reason is JSON text
+    let reason: String = client.expect_disconnect().await.unwrap().decode().unwrap();
+    assert!(reason.contains("Server does not accept transfers"), "{}", reason);
+}
+
+#[tokio::test]
+async fn unsupported_version_is_disconnected() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    // 1.21.8, the server answers with the ids of its latest version
+    client.send(HandshakePacket {
+        protocol_version: VarInt(772),
+        server_address: "localhost".to_string(),
+        port: address.port(),
+        next_state: VarInt(Intent::Login as i32),
+    }).await.unwrap();
+    client.switch_phase(ConnectionPhase::Login);
+
+    client.expect_disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn login_acknowledged_before_login_start_is_kicked() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Login).await.unwrap();
+
+    client.send(LoginAcknowledgedRequestPacket {}).await.unwrap();
+    client.expect_disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn finish_acknowledged_too_early_is_kicked() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Login).await.unwrap();
+    client.login("Steve").await.unwrap();
+
+    client.send(AcknowledgeFinishConfigurationRequestPacket {}).await.unwrap();
+    client.expect_disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn known_packs_after_finish_configuration_is_kicked() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Login).await.unwrap();
+    client.login("Steve").await.unwrap();
+    client.begin_configuration().await.unwrap();
+    while !client.receive().await.unwrap().is::<FinishConfigurationResponsePacket>() {}
+
+    client.send(KnownPacksRequestPacket { known_packs: Vec::new() }).await.unwrap();
+    client.expect_disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn reconfiguration() {
+    let (address, players) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+
+    players.get_mut(&client.uuid.unwrap()).unwrap().start_configuration().unwrap();
+    client.expect::<StartConfigurationResponsePacket>().await.unwrap();
+
+    client.send(AcknowledgeConfigurationRequestPacket {}).await.unwrap();
+    client.switch_phase(ConnectionPhase::Configuration);
+    client.begin_configuration().await.unwrap();
+    assert_eq!(client.finish_configuration().await.unwrap(), 13);
+}
+
+#[tokio::test]
+async fn acknowledge_configuration_without_request_is_kicked() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+
+    client.send(AcknowledgeConfigurationRequestPacket {}).await.unwrap();
+    client.expect_disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn required_resource_pack_declined_is_kicked() {
+    let pack = resource_pack();
+    let (address, _) = testing::start_server(config_with_pack(&pack)).await.unwrap();
+    let mut client = configure_until_pack_pushed(address).await;
+
+    client.send(ResourcePackResponseRequestPacket { uuid: pack.id, result: ResourcePackStatus::Declined }).await.unwrap();
+    client.expect_disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn required_resource_pack_loaded_finishes_configuration() {
+    let pack = resource_pack();
+    let (address, players) = testing::start_server(config_with_pack(&pack)).await.unwrap();
+    let mut client = configure_until_pack_pushed(address).await;
+
+    // Packs that were never pushed are neither tracked nor settle the pushed one
+    let unknown = Uuid::new_v4();
+    client.send(ResourcePackResponseRequestPacket { uuid: unknown, result: ResourcePackStatus::Loaded }).await.unwrap();
+
+    for result in [ResourcePackStatus::Accepted, ResourcePackStatus::Downloaded, ResourcePackStatus::Loaded] {
+        client.send(ResourcePackResponseRequestPacket { uuid: pack.id, result }).await.unwrap();
+    }
+
+    client.expect::<FinishConfigurationResponsePacket>().await.unwrap();
+    // A repeated final status doesn't send the finish again
+    client.send(ResourcePackResponseRequestPacket { uuid: pack.id, result: ResourcePackStatus::Loaded }).await.unwrap();
+    client.send(AcknowledgeFinishConfigurationRequestPacket {}).await.unwrap();
+    client.switch_phase(ConnectionPhase::Play);
+    client.expect::<LoginResponsePacket>().await.unwrap();
+
+    let player = players.get(&client.uuid.unwrap()).unwrap();
+    assert_eq!(player.resource_packs.get(&pack.id), Some(&Some(ResourcePackStatus::Loaded)));
+    assert!(!player.resource_packs.contains_key(&unknown));
+}
+
+#[tokio::test]
+async fn play_packets_and_plugin_channels() {
+    let (address, players) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+    let uuid = client.uuid.unwrap();
+
+    client.send(TeleportConfirmationRequestPacket { teleport_id: VarInt(0) }).await.unwrap();
+    client.send(SetPlayerPositionAndRotationRequestPacket { x: 1.0, feet_y: 64.0, z: -1.0, yaw: 90.0, pitch: 0.0, flags: Byte(1) }).await.unwrap();
+    client.send(ClientTickEndRequestPacket {}).await.unwrap();
+
+    let channel = Identifier::new("nullspace", "test");
+    client.send(PluginMessageRequestPacket {
+        channel: Identifier::minecraft("register"),
+        data: RawBytes(encode_channel_list(std::slice::from_ref(&channel))),
+    }).await.unwrap();
+
+    // The registration is handled in the order it was sent, after the play packets
+    let mut registered = false;
+    for _ in 0..100 {
+        if players.get(&uuid).unwrap().channels.contains(&channel) {
+            registered = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(registered, "channel was never registered");
+
+    let sent = players.get_mut(&uuid).unwrap().send_plugin_message(&channel, b"hello".to_vec()).await.unwrap();
+    assert!(sent);
+
+    let message = client.expect::<PluginMessageResponsePacket>().await.unwrap();
+    assert_eq!(message.channel, channel);
+    assert_eq!(message.data.0, b"hello");
+}
+
+#[tokio::test]
+async fn plugin_channels_and_payloads_are_limited() {
+    let (address, players) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+    let uuid = client.uuid.unwrap();
+
+    let long_name = Identifier::new("nullspace", "a".repeat(60));
+    let mut channels = vec![long_name.clone()];
+    channels.extend((0..200).map(|index| Identifier::new("nullspace", format!("channel_{}", index))));
+    client.send(PluginMessageRequestPacket { channel: Identifier::minecraft("register"), data: RawBytes(encode_channel_list(&channels)) }).await.unwrap();
+
+    // A payload of the largest size is still accepted
+    client.send(PluginMessageRequestPacket { channel: Identifier::new("nullspace", "unknown"), data: RawBytes(vec![0; 32767]) }).await.unwrap();
+
+    let mut registered = 0;
+    for _ in 0..100 {
+        registered = players.get(&uuid).unwrap().channels.len();
+        if registered > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(registered, 128);
+    assert!(!players.get(&uuid).unwrap().channels.contains(&long_name));
+
+    // One byte over closes the connection
+    client.send(PluginMessageRequestPacket { channel: Identifier::new("nullspace", "unknown"), data: RawBytes(vec![0; 32768]) }).await.unwrap();
+    loop {
+        if let Err(e) = client.receive_any().await {
+            assert!(!e.to_string().starts_with("No packet received"), "connection still open: {}", e);
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn chat_is_relayed_to_every_player() {
+    let (address, _) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let mut steve = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+    let mut alex = TestClient::join(address, ProtocolVersion::V1_21_9, "Alex").await.unwrap();
+
+    steve.send(ChatMessageRequestPacket {
+        message: "Hello".to_string(),
+        timestamp: 0,
+        salt: 0,
+        signature: None,
+        message_count: VarInt(0),
+        acknowledged: FixedBitSet::new(),
+        checksum: Byte(0),
+    }).await.unwrap();
+
+    for client in [&mut steve, &mut alex] {
+        let message = client.expect_named(SystemChatResponsePacket::NAME).await.unwrap();
+        let text = String::from_utf8_lossy(&message.data);
+        assert!(text.contains("<Steve> Hello"), "unexpected system chat {:?}", text);
+    }
+}
+
+#[tokio::test]
+async fn players_are_removed_when_their_connection_ends() {
+    let (address, players) = testing::start_server(ServerConfig::default()).await.unwrap();
+    let steve = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+    let uuid = steve.uuid.unwrap();
+
+    // A second login with the same uuid takes over the entry, the first connection ending keeps it
+    let mut second = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    second.handshake(Intent::Login).await.unwrap();
+    second.send(LoginStartRequestPacket { name: "Steve".to_string(), player_uuid: uuid }).await.unwrap();
+    second.expect::<LoginSuccessResponsePacket>().await.unwrap();
+
+    drop(steve);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(players.contains_key(&uuid));
+
+    drop(second);
+    for _ in 0..50 {
+        if !players.contains_key(&uuid) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("the player is still listed after their connection ended");
+}
+
+#[tokio::test]
+async fn client_that_stops_reading_is_dropped() {
+    let connection = ConnectionConfig { write_timeout_secs: 1, max_queued_bytes: 128 * 1024 * 1024, ..ConnectionConfig::default() };
+    let (address, players) = testing::start_server(ServerConfig { connection, ..ServerConfig::default() }).await.unwrap();
+    let mut client = TestClient::join(address, ProtocolVersion::LATEST, "Steve").await.unwrap();
+    let timeouts = metrics::count(Rejection::WriteTimeout);
+
+    // More than the socket buffers hold, until the queue goes over its budget and the client is kicked.
+    // The kick waits behind a write the client never lets finish.
+    let (writer, protocol) = {
+        let player = players.get(&client.uuid.unwrap()).unwrap();
+        (player.writer.clone(), player.protocol)
+    };
+    let message = PluginMessageResponsePacket { channel: Identifier::new("nullspace", "test"), data: RawBytes(vec![0; 1024 * 1024]) };
+    let frame = SharedPacket::new(&message).frame(protocol, ConnectionPhase::Play).unwrap();
+    for _ in 0..64 {
+        writer.send_shared(protocol, ConnectionPhase::Play, frame.clone()).unwrap();
+    }
+    // Lets the writer fill the socket buffers and block on the next write
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut sent = 0;
+    while writer.send_shared(protocol, ConnectionPhase::Play, frame.clone()).is_ok() {
+        sent += 1;
+        assert!(sent < 256, "client was never kicked");
+    }
+
+    let mut dropped = false;
+    for _ in 0..50 {
+        if metrics::count(Rejection::WriteTimeout) > timeouts {
+            dropped = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(dropped, "the write to the client never timed out");
+
+    // What made it into the socket buffers is still delivered, then the connection is closed
+    loop {
+        match client.receive_any().await {
+            Ok(packet) => assert_ne!(packet.name, Some("minecraft:disconnect"), "the kick was written after the timeout"),
+            Err(e) => {
+                assert!(!e.to_string().starts_with("No packet received"), "connection still open: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+fn resource_pack() -> ResourcePackConfig {
+    ResourcePackConfig {
+        id: Uuid::new_v4(),
+        url: "http://127.0.0.1:8080/pack.zip".to_string(),
+        hash: "0".repeat(40),
+        required: true,
+        prompt: Some("Required".to_string()),
+    }
+}
+
+fn config_with_pack(pack: &ResourcePackConfig) -> ServerConfig {
+    ServerConfig { resource_packs: vec![pack.clone()], ..ServerConfig::default() }
+}
+
+/// Logs in and reads the configuration up to the pushed resource pack, which holds back the finish
+async fn configure_until_pack_pushed(address: std::net::SocketAddr) -> TestClient {
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Login).await.unwrap();
+    client.login("Steve").await.unwrap();
+    client.begin_configuration().await.unwrap();
+
+    loop {
+        let packet = client.receive().await.unwrap();
+        assert!(!packet.is::<FinishConfigurationResponsePacket>(), "configuration finished before the pack was settled");
+        if packet.name == Some("minecraft:resource_pack_push") {
+            return client;
+        }
+    }
+}
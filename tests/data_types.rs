@@ -0,0 +1,604 @@
+//! Property tests of the protocol data types: every value survives an encode/decode round trip,
+//! and decoding arbitrary bytes fails with an error instead of panicking.
+
+use std::fmt::Debug;
+use proptest::prelude::*;
+use uuid::Uuid;
+use nullspace::networking::data_types::{BoundedFieldRead, ByteReader, FieldRead, PacketWrite};
+use nullspace::networking::data_types::angle::Angle;
+use nullspace::networking::data_types::bit_set::{BitSet, FixedBitSet};
+use nullspace::networking::data_types::data_component::{data_component_id, DataComponent, Enchantment, Rarity};
+use nullspace::networking::data_types::game_profile::{GameProfile, GameProfileProperty};
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::id_or::{IdOr, IdSet};
+use nullspace::networking::data_types::identifier::Identifier;
+use nullspace::networking::data_types::item_stack::{HashedComponent, HashedItemStack, ItemStack};
+use nullspace::networking::data_types::known_pack::KnownPack;
+use nullspace::networking::data_types::lp_vec3::LpVec3;
+use nullspace::networking::data_types::nbt::Nbt;
+use nullspace::networking::data_types::position::Position;
+use nullspace::networking::data_types::raw_bytes::RawBytes;
+use nullspace::networking::data_types::sound_event::SoundEvent;
+use nullspace::networking::data_types::teleport_flags::TeleportFlags;
+use nullspace::networking::data_types::text_component::TextComponent;
+use nullspace::networking::data_types::u_byte::UnsignedByte;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::data_types::var_long::VarLong;
+use nullspace::networking::protocol_version::ProtocolVersion;
+
+fn encode<T: PacketWrite>(value: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    value.write_to(&mut buf);
+    buf
+}
+
+/// Decodes a value that has to use the whole buffer
+fn decode<T: FieldRead>(bytes: &[u8]) -> T {
+    let mut reader = ByteReader::new(bytes);
+    let value = T::read_from(&mut reader).unwrap();
+    assert!(reader.is_empty(), "{} bytes left after decoding", reader.remaining());
+    value
+}
+
+fn assert_round_trip<T: FieldRead + PacketWrite + PartialEq + Debug>(value: T) {
+    assert_eq!(decode::<T>(&encode(&value)), value);
+}
+
+/// Types without PartialEq are compared through their Debug output
+fn assert_debug_round_trip<T: FieldRead + PacketWrite + Debug>(value: T) {
+    assert_eq!(format!("{:?}", decode::<T>(&encode(&value))), format!("{:?}", value));
+}
+
+/// Decoding never reads past the buffer, and either fails or stops inside it
+fn decode_arbitrary<T: FieldRead>(bytes: &[u8]) {
+    let mut reader = ByteReader::new(bytes);
+    let _ = T::read_from(&mut reader);
+    assert!(reader.position() <= bytes.len());
+}
+
+fn identifier() -> impl Strategy<Value = Identifier> {
+    ("[a-z0-9._-]{1,16}", "[a-z0-9._/-]{1,32}").prop_map(|(namespace, value)| Identifier::new(namespace, value))
+}
+
+fn game_profile() -> impl Strategy<Value = GameProfile> {
+    let property = ("[a-z]{1,64}", any::<String>(), proptest::option::of("[A-Za-z0-9+/=]{0,1024}"))
+        .prop_map(|(name, value, signature)| GameProfileProperty { name, value, signature });
+
+    (any::<u128>(), "[A-Za-z0-9_]{3,16}", proptest::collection::vec(property, 0..4))
+        .prop_map(|(uuid, username, properties)| GameProfile { uuid: Uuid::from_u128(uuid), username, properties })
+}
+
+/// Any tag, nested a few levels. Lists hold a single element type like NBT requires.
+fn nbt() -> impl Strategy<Value = Nbt> {
+    let leaf = prop_oneof![
+        any::<i8>().prop_map(Nbt::Byte),
+        any::<i16>().prop_map(Nbt::Short),
+        any::<i32>().prop_map(Nbt::Int),
+        any::<i64>().prop_map(Nbt::Long),
+        any::<f32>().prop_filter("NaN isn't equal to itself", |value| !value.is_nan()).prop_map(Nbt::Float),
+        any::<f64>().prop_filter("NaN isn't equal to itself", |value| !value.is_nan()).prop_map(Nbt::Double),
+        proptest::collection::vec(any::<i8>(), 0..16).prop_map(Nbt::ByteArray),
+        "\\PC{0,32}".prop_map(Nbt::String),
+        proptest::collection::vec(any::<i32>(), 0..16).prop_map(Nbt::IntArray),
+        proptest::collection::vec(any::<i64>(), 0..16).prop_map(Nbt::LongArray),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| prop_oneof![
+        proptest::collection::vec(any::<i32>(), 0..8).prop_map(|values| Nbt::List(values.into_iter().map(Nbt::Int).collect())),
+        proptest::collection::vec(inner.clone(), 0..2).prop_map(Nbt::List),
+        proptest::collection::vec(("\\PC{0,8}", inner), 0..8).prop_map(Nbt::Compound),
+    ])
+}
+
+fn data_component() -> impl Strategy<Value = DataComponent> {
+    let enchantments = || proptest::collection::vec((0i32..64, 1i32..256), 0..4)
+        .prop_map(|values| values.into_iter().map(|(id, level)| Enchantment { id: VarInt(id), level: VarInt(level) }).collect::<Vec<_>>());
+
+    prop_oneof![
+        proptest::collection::vec(("[a-z]{1,8}", any::<i32>()), 0..4)
+            .prop_map(|entries| DataComponent::CustomData(Nbt::Compound(entries.into_iter().map(|(key, value)| (key, Nbt::Int(value))).collect()))),
+        (1i32..=99).prop_map(DataComponent::MaxStackSize),
+        any::<i32>().prop_map(DataComponent::MaxDamage),
+        any::<i32>().prop_map(DataComponent::Damage),
+        Just(DataComponent::Unbreakable),
+        "\\PC{0,32}".prop_map(|text| DataComponent::CustomName(TextComponent::text(text))),
+        "\\PC{0,32}".prop_map(|text| DataComponent::ItemName(TextComponent::text(text))),
+        proptest::collection::vec("\\PC{0,16}", 0..4).prop_map(|lines| DataComponent::Lore(lines.into_iter().map(TextComponent::text).collect())),
+        prop_oneof![Just(Rarity::Common), Just(Rarity::Uncommon), Just(Rarity::Rare), Just(Rarity::Epic)].prop_map(DataComponent::Rarity),
+        enchantments().prop_map(DataComponent::Enchantments),
+        any::<i32>().prop_map(DataComponent::RepairCost),
+        any::<bool>().prop_map(DataComponent::EnchantmentGlintOverride),
+        enchantments().prop_map(DataComponent::StoredEnchantments),
+        any::<i32>().prop_map(DataComponent::DyedColor),
+    ]
+}
+
+fn item_stack() -> impl Strategy<Value = ItemStack> {
+    let removed = prop_oneof![Just("minecraft:damage"), Just("minecraft:rarity"), Just("minecraft:enchantments"), Just("minecraft:lore")];
+    (0i32..1500, 1i32..=99, proptest::collection::vec(data_component(), 0..6), proptest::collection::vec(removed, 0..3))
+        .prop_map(|(item_id, count, added, removed)| {
+            let mut stack = ItemStack::new(item_id, count);
+            stack.components.added = added;
+            stack.components.removed = removed;
+            stack
+        })
+}
+
+proptest! {
+    #[test]
+    fn var_int_round_trip(value in any::<i32>()) {
+        let bytes = encode(&VarInt(value));
+        prop_assert!(bytes.len() <= VarInt::MAX_SIZE);
+        prop_assert_eq!(VarInt(value).encode().1, bytes.len());
+        prop_assert_eq!(VarInt::peek(&bytes).unwrap(), Some((VarInt(value), bytes.len())));
+        assert_round_trip(VarInt(value));
+    }
+
+    #[test]
+    fn var_long_round_trip(value in any::<i64>()) {
+        prop_assert!(encode(&VarLong(value)).len() <= VarLong::MAX_SIZE);
+        assert_round_trip(VarLong(value));
+    }
+
+    #[test]
+    fn primitives_round_trip(b in any::<bool>(), u in any::<u8>(), i in any::<i8>(), s in any::<i16>(), us in any::<u16>(), n in any::<i32>(), l in any::<i64>()) {
+        assert_round_trip(b);
+        assert_round_trip(UnsignedByte(u));
+        assert_round_trip(Byte(i));
+        assert_round_trip(s);
+        assert_round_trip(us);
+        assert_round_trip(n);
+        assert_round_trip(l);
+    }
+
+    #[test]
+    fn floats_round_trip(f in any::<f32>(), d in any::<f64>()) {
+        // Compared bit for bit, NaN included
+        prop_assert_eq!(decode::<f32>(&encode(&f)).to_bits(), f.to_bits());
+        prop_assert_eq!(decode::<f64>(&encode(&d)).to_bits(), d.to_bits());
+    }
+
+    #[test]
+    fn string_round_trip(value in "\\PC{0,256}") {
+        assert_round_trip(value.clone());
+
+        let length = value.encode_utf16().count();
+        let bytes = encode(&value);
+        prop_assert_eq!(String::read_bounded(&mut ByteReader::new(&bytes), length.max(1)).unwrap(), value.clone());
+        if length > 0 {
+            prop_assert!(String::read_bounded(&mut ByteReader::new(&bytes), length - 1).is_err());
+        }
+    }
+
+    #[test]
+    fn uuid_round_trip(value in any::<u128>()) {
+        assert_round_trip(Uuid::from_u128(value));
+    }
+
+    #[test]
+    fn identifier_round_trip(value in identifier()) {
+        prop_assert_eq!(value.to_string().parse::<Identifier>().unwrap(), value.clone());
+        assert_round_trip(value);
+    }
+
+    #[test]
+    fn position_round_trip(x in -(1i32 << 25)..(1 << 25), y in -2048i16..2048, z in -(1i32 << 25)..(1 << 25)) {
+        assert_round_trip(Position::new(x, y, z));
+    }
+
+    #[test]
+    fn collections_round_trip(items in proptest::collection::vec(any::<i32>(), 0..64), optional in proptest::option::of(any::<i64>())) {
+        let var_ints: Vec<VarInt> = items.iter().copied().map(VarInt).collect();
+        assert_round_trip(var_ints);
+        assert_round_trip(items);
+        assert_round_trip(optional);
+    }
+
+    #[test]
+    fn byte_arrays_round_trip(array in any::<[u8; 32]>(), raw in proptest::collection::vec(any::<u8>(), 0..512)) {
+        assert_round_trip(array);
+        prop_assert_eq!(decode::<RawBytes>(&encode(&RawBytes(raw.clone()))).0, raw);
+    }
+
+    #[test]
+    fn bit_sets_round_trip(bits in proptest::collection::vec(0usize..256, 0..32)) {
+        let mut set = BitSet::new();
+        let mut fixed = FixedBitSet::<256>::new();
+        for &bit in &bits {
+            set.set(bit, true);
+            fixed.set(bit, true);
+        }
+
+        for bit in 0..256 {
+            prop_assert_eq!(set.get(bit), bits.contains(&bit));
+            prop_assert_eq!(fixed.get(bit), bits.contains(&bit));
+        }
+        assert_round_trip(set);
+        assert_round_trip(fixed);
+    }
+
+    #[test]
+    fn registry_references_round_trip(id in 0i32..i32::MAX, sound in identifier(), range in proptest::option::of(any::<f32>()), ids in proptest::collection::vec(any::<i32>(), 0..16)) {
+        assert_round_trip(IdOr::<SoundEvent>::Id(id));
+        assert_debug_round_trip(IdOr::Inline(SoundEvent { sound_name: sound.clone(), fixed_range: range }));
+        assert_round_trip(IdSet::Tag(sound));
+        assert_round_trip(IdSet::Ids(ids.into_iter().map(VarInt).collect()));
+    }
+
+    #[test]
+    fn angle_and_teleport_flags_round_trip(angle in any::<u8>(), flags in any::<i32>()) {
+        assert_round_trip(Angle(angle));
+        prop_assert_eq!(Angle::from_degrees(Angle(angle).to_degrees()), Angle(angle));
+        assert_round_trip(TeleportFlags(flags));
+    }
+
+    /// Components lose precision, each is off by at most half a step of 1/32766 of the scale
+    #[test]
+    fn lp_vec3_round_trip(x in -1e6f64..1e6, y in -1e6f64..1e6, z in -1e6f64..1e6) {
+        let decoded = decode::<LpVec3>(&encode(&LpVec3::new(x, y, z)));
+        let scale = x.abs().max(y.abs()).max(z.abs()).ceil().max(1.0);
+        let tolerance = scale / 32766.0 + 1e-9;
+        prop_assert!((decoded.x - x).abs() <= tolerance, "{} decoded as {}", x, decoded.x);
+        prop_assert!((decoded.y - y).abs() <= tolerance, "{} decoded as {}", y, decoded.y);
+        prop_assert!((decoded.z - z).abs() <= tolerance, "{} decoded as {}", z, decoded.z);
+    }
+
+    #[test]
+    fn nbt_round_trip(value in nbt()) {
+        assert_round_trip(value);
+    }
+
+    /// Every supported version reads back what it writes, with its own component ids
+    #[test]
+    fn item_stack_round_trip(stack in item_stack()) {
+        for version in ProtocolVersion::ALL {
+            let mut bytes = Vec::new();
+            stack.write_for(version, &mut bytes).unwrap();
+            let mut reader = ByteReader::new(&bytes);
+            prop_assert_eq!(&ItemStack::read_for(version, &mut reader).unwrap(), &stack);
+            prop_assert!(reader.is_empty());
+        }
+        assert_round_trip(stack);
+    }
+
+    #[test]
+    fn hashed_slot_round_trip(item_id in any::<i32>(), count in any::<i32>(), hashes in proptest::collection::vec(any::<(i32, i32)>(), 0..8), removed in proptest::collection::vec(any::<i32>(), 0..8)) {
+        let stack = HashedItemStack {
+            item_id: VarInt(item_id),
+            count: VarInt(count),
+            added: hashes.into_iter().map(|(component_type, hash)| HashedComponent { component_type: VarInt(component_type), hash }).collect(),
+            removed: removed.into_iter().map(VarInt).collect(),
+        };
+        assert_round_trip(Some(stack));
+        assert_round_trip(None::<HashedItemStack>);
+    }
+
+    #[test]
+    fn compound_types_round_trip(profile in game_profile(), pack in ("[a-z]{1,16}", "[a-z]{1,16}", "[0-9.]{1,8}")) {
+        assert_debug_round_trip(profile);
+        assert_round_trip(KnownPack { namespace: pack.0, id: pack.1, version: pack.2 });
+    }
+
+    #[test]
+    fn arbitrary_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..64)) {
+        decode_arbitrary::<VarInt>(&bytes);
+        decode_arbitrary::<bool>(&bytes);
+        decode_arbitrary::<i64>(&bytes);
+        decode_arbitrary::<f64>(&bytes);
+        decode_arbitrary::<String>(&bytes);
+        decode_arbitrary::<Uuid>(&bytes);
+        decode_arbitrary::<Identifier>(&bytes);
+        decode_arbitrary::<Position>(&bytes);
+        decode_arbitrary::<Vec<String>>(&bytes);
+        decode_arbitrary::<Vec<Vec<VarInt>>>(&bytes);
+        decode_arbitrary::<Option<Identifier>>(&bytes);
+        decode_arbitrary::<Option<[u8; 16]>>(&bytes);
+        decode_arbitrary::<KnownPack>(&bytes);
+        decode_arbitrary::<GameProfile>(&bytes);
+        decode_arbitrary::<VarLong>(&bytes);
+        decode_arbitrary::<BitSet>(&bytes);
+        decode_arbitrary::<FixedBitSet<20>>(&bytes);
+        decode_arbitrary::<IdOr<SoundEvent>>(&bytes);
+        decode_arbitrary::<IdSet>(&bytes);
+        decode_arbitrary::<LpVec3>(&bytes);
+        decode_arbitrary::<Nbt>(&bytes);
+        decode_arbitrary::<TextComponent>(&bytes);
+        decode_arbitrary::<ItemStack>(&bytes);
+        decode_arbitrary::<Option<HashedItemStack>>(&bytes);
+        let _ = VarInt::peek(&bytes);
+        let _ = String::read_bounded(&mut ByteReader::new(&bytes), 16);
+        let _ = Vec::<Identifier>::read_bounded(&mut ByteReader::new(&bytes), 4);
+    }
+
+    /// Long runs of continuation bits, the part of a VarInt random bytes rarely reach
+    #[test]
+    fn var_int_continuation_bytes_never_panic(length in 0usize..12, last in any::<u8>()) {
+        let mut bytes = vec![0xFF; length];
+        bytes.push(last);
+        decode_arbitrary::<VarInt>(&bytes);
+        let _ = VarInt::peek(&bytes);
+    }
+}
+
+// Encodings given by the protocol specification
+
+fn assert_encoding<T: FieldRead + PacketWrite + PartialEq + Debug>(value: T, bytes: &[u8]) {
+    assert_eq!(encode(&value), bytes, "encoding of {:?}", value);
+    assert_eq!(decode::<T>(bytes), value);
+}
+
+#[test]
+fn var_int_encodings() {
+    assert_encoding(VarInt(0), &[0x00]);
+    assert_encoding(VarInt(1), &[0x01]);
+    assert_encoding(VarInt(127), &[0x7f]);
+    assert_encoding(VarInt(128), &[0x80, 0x01]);
+    assert_encoding(VarInt(255), &[0xff, 0x01]);
+    assert_encoding(VarInt(25565), &[0xdd, 0xc7, 0x01]);
+    assert_encoding(VarInt(2097151), &[0xff, 0xff, 0x7f]);
+    assert_encoding(VarInt(2147483647), &[0xff, 0xff, 0xff, 0xff, 0x07]);
+    assert_encoding(VarInt(-1), &[0xff, 0xff, 0xff, 0xff, 0x0f]);
+    assert_encoding(VarInt(-2147483648), &[0x80, 0x80, 0x80, 0x80, 0x08]);
+}
+
+#[test]
+fn var_long_encodings() {
+    assert_encoding(VarLong(0), &[0x00]);
+    assert_encoding(VarLong(1), &[0x01]);
+    assert_encoding(VarLong(127), &[0x7f]);
+    assert_encoding(VarLong(128), &[0x80, 0x01]);
+    assert_encoding(VarLong(255), &[0xff, 0x01]);
+    assert_encoding(VarLong(2147483647), &[0xff, 0xff, 0xff, 0xff, 0x07]);
+    assert_encoding(VarLong(9223372036854775807), &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+    assert_encoding(VarLong(-1), &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+    assert_encoding(VarLong(-2147483648), &[0x80, 0x80, 0x80, 0x80, 0xf8, 0xff, 0xff, 0xff, 0xff, 0x01]);
+    assert_encoding(VarLong(-9223372036854775808), &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+
+    // An 11th byte doesn't fit in 64 bits
+    assert!(ByteReader::new(&[0x80; 11]).read_field::<VarLong>().is_err());
+}
+
+#[test]
+fn position_encoding() {
+    assert_encoding(Position::new(18357644, 831, -20882616), &0x4607632C15B4833Fu64.to_be_bytes());
+}
+
+#[test]
+fn angle_encodings() {
+    assert_encoding(Angle(64), &[0x40]);
+    assert_eq!(Angle::from_degrees(90.0), Angle(64));
+    assert_eq!(Angle::from_degrees(-90.0), Angle(192));
+    assert_eq!(Angle::from_degrees(360.0), Angle(0));
+    assert_eq!(Angle::from_degrees(540.0), Angle(128));
+    assert_eq!(Angle(32).to_degrees(), 45.0);
+}
+
+#[test]
+fn bit_set_encodings() {
+    let mut set = BitSet::new();
+    set.set(0, true);
+    set.set(65, true);
+    assert_encoding(set.clone(), &[0x02, 0, 0, 0, 0, 0, 0, 0, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x02]);
+
+    // Clearing a bit keeps the longs, the length only grows
+    set.set(65, false);
+    set.set(1000, false);
+    assert_encoding(set, &[0x02, 0, 0, 0, 0, 0, 0, 0, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x00]);
+    assert_encoding(BitSet::new(), &[0x00]);
+
+    // More longs than bytes left is rejected before allocating
+    assert!(ByteReader::new(&[0xff, 0xff, 0xff, 0xff, 0x07]).read_field::<BitSet>().is_err());
+}
+
+#[test]
+fn fixed_bit_set_encodings() {
+    // Fixed BitSet (20) is ceil(20 / 8) bytes, like the acknowledged messages of a chat message
+    assert_eq!(FixedBitSet::<20>::SIZE, 3);
+    assert_encoding(FixedBitSet::<20>::new(), &[0x00, 0x00, 0x00]);
+
+    let mut set = FixedBitSet::<20>::new();
+    set.set(0, true);
+    set.set(9, true);
+    set.set(19, true);
+    assert_encoding(set.clone(), &[0x01, 0x02, 0x08]);
+    assert!(!set.get(20));
+    assert!(ByteReader::new(&[0x01, 0x02]).read_field::<FixedBitSet<20>>().is_err());
+}
+
+#[test]
+fn id_or_encodings() {
+    // Registry ids are shifted by one, 0 means the value follows inline
+    assert_encoding(IdOr::<SoundEvent>::Id(0), &[0x01]);
+    assert_encoding(IdOr::<SoundEvent>::Id(5), &[0x06]);
+
+    let sound = SoundEvent { sound_name: Identifier::minecraft("a"), fixed_range: Some(16.0) };
+    let mut expected = vec![0x00, 0x0b];
+    expected.extend_from_slice(b"minecraft:a");
+    expected.extend_from_slice(&[0x01, 0x41, 0x80, 0x00, 0x00]);
+    assert_encoding(IdOr::Inline(sound), &expected);
+
+    assert!(ByteReader::new(&[0xff, 0xff, 0xff, 0xff, 0x0f]).read_field::<IdOr<SoundEvent>>().is_err());
+    // i32::MIN, shifting it back by one overflowed
+    assert!(ByteReader::new(&[0x80, 0x80, 0x80, 0x80, 0x08]).read_field::<IdOr<SoundEvent>>().is_err());
+    assert_encoding(IdOr::<SoundEvent>::Id(i32::MAX - 1), &[0xff, 0xff, 0xff, 0xff, 0x07]);
+}
+
+#[test]
+#[should_panic(expected = "can't be sent shifted by one")]
+#[cfg(debug_assertions)]
+fn id_or_id_past_the_var_int_range_is_a_bug() {
+    encode(&IdOr::<SoundEvent>::Id(i32::MAX));
+}
+
+#[test]
+fn id_set_encodings() {
+    // The type is 0 for a tag, otherwise the number of ids + 1
+    let mut expected = vec![0x00, 0x0e];
+    expected.extend_from_slice(b"minecraft:logs");
+    assert_encoding(IdSet::Tag(Identifier::minecraft("logs")), &expected);
+    assert_encoding(IdSet::Ids(vec![]), &[0x01]);
+    assert_encoding(IdSet::Ids(vec![VarInt(1), VarInt(300)]), &[0x03, 0x01, 0xac, 0x02]);
+
+    assert!(ByteReader::new(&[0x10, 0x01]).read_field::<IdSet>().is_err());
+}
+
+#[test]
+fn lp_vec3_encodings() {
+    assert_encoding(LpVec3::ZERO, &[0x00]);
+    assert_encoding(LpVec3::new(1.0, 0.0, 0.0), &[0xf1, 0xff, 0x7f, 0xfe, 0xff, 0xff]);
+    assert_encoding(LpVec3::new(0.0, -1.0, 0.0), &[0xf9, 0xff, 0x7f, 0xfe, 0x00, 0x01]);
+    // Scales over 3 continue in a VarInt, 10 is 0b10 in the marker byte and 0b10 in the VarInt
+    assert_encoding(LpVec3::new(10.0, 0.0, 0.0), &[0xf6, 0xff, 0x7f, 0xfe, 0xff, 0xff, 0x02]);
+
+    // Components are rounded to 15 bits of the largest one
+    let decoded = decode::<LpVec3>(&[0x53, 0x55, 0xff, 0xfc, 0xea, 0xaa]);
+    assert_eq!(encode(&LpVec3::new(0.5, -0.25, 3.0)), [0x53, 0x55, 0xff, 0xfc, 0xea, 0xaa]);
+    assert!((decoded.x - 0.5).abs() < 1e-4 && (decoded.y + 0.25).abs() < 1e-4 && decoded.z == 3.0);
+
+    // Too small to send and not a number are both zero
+    assert_eq!(encode(&LpVec3::new(1e-6, 0.0, -1e-6)), [0x00]);
+    assert_eq!(encode(&LpVec3::new(f64::NAN, 0.0, 0.0)), [0x00]);
+}
+
+#[test]
+fn teleport_flags_encodings() {
+    let flags = TeleportFlags::RELATIVE_POSITION | TeleportFlags::ROTATE_VELOCITY;
+    assert_encoding(flags, &[0x00, 0x00, 0x01, 0x07]);
+    assert!(flags.contains(TeleportFlags::RELATIVE_Y));
+    assert!(!flags.contains(TeleportFlags::RELATIVE_ROTATION));
+    assert_encoding(TeleportFlags::RELATIVE_ROTATION | TeleportFlags::RELATIVE_VELOCITY, &[0x00, 0x00, 0x00, 0xf8]);
+    assert_encoding(TeleportFlags::ABSOLUTE, &[0x00, 0x00, 0x00, 0x00]);
+}
+
+#[test]
+fn nbt_encodings() {
+    let compound = Nbt::Compound(vec![
+        ("name".to_string(), Nbt::from("Bananrama")),
+        ("list".to_string(), Nbt::List(vec![])),
+    ]);
+    let mut expected = vec![0x0a];
+    expected.extend_from_slice(&[0x08, 0x00, 0x04]);
+    expected.extend_from_slice(b"name");
+    expected.extend_from_slice(&[0x00, 0x09]);
+    expected.extend_from_slice(b"Bananrama");
+    expected.extend_from_slice(&[0x09, 0x00, 0x04]);
+    expected.extend_from_slice(b"list");
+    expected.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_encoding(compound, &expected);
+
+    // Null and characters outside the BMP use Java's modified UTF-8
+    assert_encoding(Nbt::from("\0"), &[0x08, 0x00, 0x02, 0xc0, 0x80]);
+    assert_encoding(Nbt::from("😀"), &[0x08, 0x00, 0x06, 0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]);
+
+    // Strings past the u16 length are cut after the last whole character that fits
+    let text = format!("a{}", "€".repeat(21844));
+    assert_eq!(decode::<Nbt>(&encode(&Nbt::String(format!("{}€", text)))), Nbt::String(text));
+    let text = "a".repeat(65530);
+    assert_eq!(decode::<Nbt>(&encode(&Nbt::String(format!("{}😀", text)))), Nbt::String(text));
+
+    // A list of elements without a type and nesting past vanilla's limit are both rejected
+    assert!(ByteReader::new(&[0x09, 0x00, 0x00, 0x00, 0x00, 0x01]).read_field::<Nbt>().is_err());
+    let mut nested = vec![0x09];
+    for _ in 0..600 {
+        nested.extend_from_slice(&[0x09, 0x00, 0x00, 0x00, 0x01]);
+    }
+    assert!(ByteReader::new(&nested).read_field::<Nbt>().is_err());
+}
+
+#[test]
+fn text_component_decoding() {
+    assert_encoding(TextComponent::text("hi"), &[0x08, 0x00, 0x02, b'h', b'i']);
+
+    // A compound keeps its styling and children, and is written back byte for byte
+    let styled = Nbt::Compound(vec![
+        ("italic".to_string(), Nbt::Byte(0)),
+        ("text".to_string(), Nbt::from("hi")),
+        ("extra".to_string(), Nbt::List(vec![Nbt::from("!")])),
+    ]);
+    let component = decode::<TextComponent>(&encode(&styled));
+    assert_eq!(component.plain_text(), "hi");
+    assert_eq!(encode(&component), encode(&styled));
+    assert!(ByteReader::new(&encode(&Nbt::Int(1))).read_field::<TextComponent>().is_err());
+}
+
+#[test]
+fn item_stack_encodings() {
+    // An empty slot is only its count
+    assert_encoding(ItemStack::EMPTY, &[0x00]);
+    assert_eq!(decode::<ItemStack>(&[0xff, 0xff, 0xff, 0xff, 0x0f]), ItemStack::EMPTY);
+
+    // Count, item id, number of added and removed components, then the components
+    let stack = ItemStack::new(1, 64)
+        .with(DataComponent::CustomName(TextComponent::text("a")))
+        .with(DataComponent::Damage(3));
+    assert_encoding(stack.clone(), &[0x40, 0x01, 0x02, 0x00, 0x06, 0x08, 0x00, 0x01, b'a', 0x03, 0x03]);
+
+    let mut bytes = Vec::new();
+    stack.write_for(ProtocolVersion::V1_21_9, &mut bytes).unwrap();
+    assert_eq!(bytes, [0x40, 0x01, 0x02, 0x00, 0x05, 0x08, 0x00, 0x01, b'a', 0x03, 0x03]);
+
+    let mut stack = ItemStack::new(900, 1).with(DataComponent::DyedColor(0xFF0000));
+    stack.components.removed.push("minecraft:enchantments");
+    assert_encoding(stack, &[0x01, 0x84, 0x07, 0x01, 0x01, 0x2a, 0x00, 0xff, 0x00, 0x00, 0x0d]);
+
+    // A removed component without an id can't be written, the infallible write sends an empty slot
+    let mut stack = ItemStack::new(1, 1);
+    stack.components.removed.push("minecraft:not_a_component");
+    assert!(stack.write_for(ProtocolVersion::LATEST, &mut Vec::new()).is_err());
+    assert_eq!(encode(&stack), [0x00]);
+
+    // Components without a known layout can't be skipped
+    assert!(ByteReader::new(&[0x01, 0x01, 0x01, 0x00, 0x7f]).read_field::<ItemStack>().is_err());
+    // Counts larger than the bytes left are rejected before allocating
+    assert!(ByteReader::new(&[0x01, 0x01, 0xff, 0xff, 0xff, 0xff, 0x07, 0x00]).read_field::<ItemStack>().is_err());
+}
+
+#[test]
+fn every_data_component_has_an_id() {
+    let components = [
+        DataComponent::CustomData(Nbt::Compound(vec![])),
+        DataComponent::MaxStackSize(1),
+        DataComponent::MaxDamage(1),
+        DataComponent::Damage(1),
+        DataComponent::Unbreakable,
+        DataComponent::CustomName(TextComponent::text("")),
+        DataComponent::ItemName(TextComponent::text("")),
+        DataComponent::Lore(vec![]),
+        DataComponent::Rarity(Rarity::Epic),
+        DataComponent::Enchantments(vec![]),
+        DataComponent::RepairCost(1),
+        DataComponent::EnchantmentGlintOverride(true),
+        DataComponent::StoredEnchantments(vec![]),
+        DataComponent::DyedColor(1),
+    ];
+
+    for version in ProtocolVersion::ALL {
+        let mut ids: Vec<i32> = components.iter().map(|component| data_component_id(version, component.name()).unwrap()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), components.len(), "duplicate data component ids in {}", version);
+    }
+}
+
+#[test]
+fn hashed_slot_encodings() {
+    assert_encoding(None::<HashedItemStack>, &[0x00]);
+
+    let stack = HashedItemStack {
+        item_id: VarInt(1),
+        count: VarInt(2),
+        added: vec![HashedComponent { component_type: VarInt(3), hash: -1 }],
+        removed: vec![VarInt(4)],
+    };
+    assert_encoding(Some(stack), &[0x01, 0x01, 0x02, 0x01, 0x03, 0xff, 0xff, 0xff, 0xff, 0x01, 0x04]);
+}
+
+// Regressions found by the properties and fuzz targets above
+
+/// Shifted past 32 bits and panicked in debug builds instead of failing
+#[test]
+fn var_int_with_a_sixth_byte_is_rejected() {
+    let bytes = [0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+    assert!(ByteReader::new(&bytes).read_field::<VarInt>().is_err());
+    assert!(VarInt::peek(&bytes).is_err());
+}
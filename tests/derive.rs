@@ -0,0 +1,190 @@
+//! Round trips through the Packet, FieldRead and PacketWrite derives, with every kind of field they support.
+
+use proptest::prelude::*;
+use uuid::Uuid;
+use nullspace::networking::data_types::{ByteReader, FieldRead, PacketWrite};
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::identifier::Identifier;
+use nullspace::networking::data_types::position::Position;
+use nullspace::networking::data_types::u_byte::UnsignedByte;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::data_types::var_long::VarLong;
+use nullspace::networking::packets::Packet;
+
+#[derive(Debug, Clone, PartialEq, FieldRead, PacketWrite)]
+struct Nested {
+    id: VarInt,
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, FieldRead, PacketWrite)]
+struct Empty {}
+
+#[derive(Debug, Clone, PartialEq, Packet, FieldRead, PacketWrite)]
+struct EveryField {
+    flag: bool,
+    byte: Byte,
+    unsigned: UnsignedByte,
+    short: i16,
+    port: u16,
+    int: i32,
+    long: i64,
+    float: f32,
+    double: f64,
+    var_int: VarInt,
+    var_long: VarLong,
+    uuid: Uuid,
+    identifier: Identifier,
+    position: Position,
+    fixed: [u8; 4],
+    #[field(max_length = 16)]
+    name: String,
+    nested: Nested,
+    empty: Empty,
+    list: Vec<Nested>,
+    #[field(max_length = 3)]
+    bounded_list: Vec<i32>,
+    optional: Option<i64>,
+    #[field(max_length = 8)]
+    bounded_optional: Option<String>,
+    has_extra: bool,
+    #[field(when = *has_extra)]
+    extra: Option<Position>,
+    count: VarInt,
+    #[field(length = count.0)]
+    unprefixed: Vec<i16>,
+}
+
+/// Bounded strings are measured in UTF-16 units like vanilla, these take one per character
+const BMP_TEXT_16: &str = "[\\u{20}-\\u{D7FF}]{0,16}";
+const BMP_TEXT_8: &str = "[\\u{20}-\\u{D7FF}]{0,8}";
+
+fn nested() -> impl Strategy<Value = Nested> {
+    (any::<i32>(), "\\PC{0,16}").prop_map(|(id, name)| Nested { id: VarInt(id), name })
+}
+
+fn position() -> impl Strategy<Value = Position> {
+    (-(1i32 << 25)..(1 << 25), -2048i16..2048, -(1i32 << 25)..(1 << 25)).prop_map(|(x, y, z)| Position { x, y, z })
+}
+
+fn every_field() -> impl Strategy<Value = EveryField> {
+    let numbers = (any::<bool>(), any::<i8>(), any::<u8>(), any::<i16>(), any::<u16>(), any::<i32>(), any::<i64>(), any::<f32>(), any::<f64>(), any::<i32>(), any::<i64>());
+    let names = (any::<u128>(), "[a-z]{1,8}", "[a-z/]{1,16}", position(), any::<[u8; 4]>(), BMP_TEXT_16, nested());
+    let collections = (
+        proptest::collection::vec(nested(), 0..4),
+        proptest::collection::vec(any::<i32>(), 0..=3),
+        proptest::option::of(any::<i64>()),
+        proptest::option::of(BMP_TEXT_8),
+        proptest::option::of(position()),
+        proptest::collection::vec(any::<i16>(), 0..8),
+    );
+
+    (numbers, names, collections).prop_map(|(numbers, names, collections)| {
+        let (flag, byte, unsigned, short, port, int, long, float, double, var_int, var_long) = numbers;
+        let (uuid, namespace, value, position, fixed, name, nested) = names;
+        let (list, bounded_list, optional, bounded_optional, extra, unprefixed) = collections;
+
+        EveryField {
+            flag, byte: Byte(byte), unsigned: UnsignedByte(unsigned), short, port, int, long, float, double,
+            var_int: VarInt(var_int), var_long: VarLong(var_long), uuid: Uuid::from_u128(uuid),
+            identifier: Identifier::new(namespace, value), position, fixed, name, nested, empty: Empty {},
+            list, bounded_list, optional, bounded_optional,
+            has_extra: extra.is_some(), extra,
+            count: VarInt(unprefixed.len() as i32), unprefixed,
+        }
+    })
+}
+
+fn encode<T: PacketWrite>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.write_to(&mut bytes);
+    bytes
+}
+
+proptest! {
+    /// NaN floats aren't equal to themselves, so the decoded values are compared through their bytes
+    #[test]
+    fn every_field_round_trips(value in every_field()) {
+        let bytes = encode(&value);
+
+        let mut reader = ByteReader::new(&bytes);
+        let read = EveryField::read_from(&mut reader).unwrap();
+        prop_assert!(reader.is_empty());
+        prop_assert_eq!(encode(&read), bytes.clone());
+
+        let mut reader = ByteReader::new(&bytes);
+        let decoded = EveryField::decode(&mut reader).unwrap();
+        prop_assert!(reader.is_empty());
+        prop_assert_eq!(encode(&decoded), bytes);
+
+        if !value.float.is_nan() && !value.double.is_nan() {
+            prop_assert_eq!(read, value);
+        }
+    }
+}
+
+fn sample() -> EveryField {
+    EveryField {
+        flag: true, byte: Byte(-1), unsigned: UnsignedByte(0xFE), short: 2, port: 25565, int: 3, long: 4,
+        float: 1.0, double: 2.0, var_int: VarInt(300), var_long: VarLong(-1), uuid: Uuid::from_u128(5),
+        identifier: Identifier::new("minecraft", "stone"), position: Position { x: 1, y: 2, z: 3 },
+        fixed: [9, 8, 7, 6], name: "Steve".to_string(), nested: Nested { id: VarInt(1), name: "a".to_string() },
+        empty: Empty {}, list: vec![Nested { id: VarInt(2), name: String::new() }], bounded_list: vec![7],
+        optional: None, bounded_optional: Some("b".to_string()), has_extra: false, extra: None,
+        count: VarInt(2), unprefixed: vec![1, -1],
+    }
+}
+
+#[test]
+fn fields_are_written_in_order_with_their_prefixes() {
+    let mut expected = vec![0x01, 0xFF, 0xFE, 0x00, 0x02, 0x63, 0xDD, 0x00, 0x00, 0x00, 0x03];
+    expected.extend_from_slice(&4i64.to_be_bytes());
+    expected.extend_from_slice(&1.0f32.to_be_bytes());
+    expected.extend_from_slice(&2.0f64.to_be_bytes());
+    expected.extend_from_slice(&[0xAC, 0x02]);
+    expected.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+    expected.extend_from_slice(&5u128.to_be_bytes());
+    expected.push(15);
+    expected.extend_from_slice(b"minecraft:stone");
+    expected.extend_from_slice(&((1u64 << 38) | (3 << 12) | 2).to_be_bytes());
+    expected.extend_from_slice(&[9, 8, 7, 6]);
+    expected.push(5);
+    expected.extend_from_slice(b"Steve");
+    // Nested structs have no framing of their own, the empty one writes nothing
+    expected.extend_from_slice(&[0x01, 0x01, b'a']);
+    // Prefixed lists and optionals
+    expected.extend_from_slice(&[0x01, 0x02, 0x00]);
+    expected.extend_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x07]);
+    expected.push(0x00);
+    expected.extend_from_slice(&[0x01, 0x01, b'b']);
+    // A `when` field that doesn't hold writes nothing, not even a boolean
+    expected.push(0x00);
+    // A `length` list is only its items
+    expected.extend_from_slice(&[0x02, 0x00, 0x01, 0xFF, 0xFF]);
+
+    assert_eq!(encode(&sample()), expected);
+
+    let mut with_extra = sample();
+    with_extra.has_extra = true;
+    with_extra.extra = Some(Position { x: 0, y: 1, z: 0 });
+    let mut expected_end = vec![0x01];
+    expected_end.extend_from_slice(&1u64.to_be_bytes());
+    expected_end.extend_from_slice(&[0x02, 0x00, 0x01, 0xFF, 0xFF]);
+    let bytes = encode(&with_extra);
+    assert_eq!(bytes[bytes.len() - expected_end.len()..], expected_end);
+}
+
+#[test]
+fn max_lengths_are_enforced_when_reading() {
+    let mut long_name = sample();
+    long_name.name = "a".repeat(17);
+    assert!(EveryField::read_from(&mut ByteReader::new(&encode(&long_name))).is_err());
+
+    let mut long_list = sample();
+    long_list.bounded_list = vec![1, 2, 3, 4];
+    assert!(EveryField::decode(&mut ByteReader::new(&encode(&long_list))).is_err());
+
+    let mut long_optional = sample();
+    long_optional.bounded_optional = Some("a".repeat(9));
+    assert!(EveryField::read_from(&mut ByteReader::new(&encode(&long_optional))).is_err());
+}
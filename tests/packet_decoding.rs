@@ -0,0 +1,243 @@
+//! Property tests of serverbound packet decoding through the registries: random frames fail with an
+//! error instead of panicking, and packets written like a client does decode back to the same fields.
+
+use bytes::BytesMut;
+use proptest::prelude::*;
+use uuid::Uuid;
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::data_types::{ByteReader, PacketWrite};
+use nullspace::networking::data_types::bit_set::FixedBitSet;
+use nullspace::networking::data_types::i_byte::Byte;
+use nullspace::networking::data_types::identifier::Identifier;
+use nullspace::networking::data_types::known_pack::KnownPack;
+use nullspace::networking::data_types::raw_bytes::RawBytes;
+use nullspace::networking::data_types::u_byte::UnsignedByte;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::framing::split_frame;
+use nullspace::networking::metrics::{self, Rejection};
+use nullspace::networking::packets::{self, PacketRegistries, PacketRegistry};
+use nullspace::networking::packets::common::plugin_message_request::PluginMessageRequestPacket;
+use nullspace::networking::packets::common::resource_pack_response_request::{ResourcePackResponseRequestPacket, ResourcePackStatus};
+use nullspace::networking::packets::configuration::client_information_request::ClientInformationRequestPacket;
+use nullspace::networking::packets::configuration::known_packs_request::KnownPacksRequestPacket;
+use nullspace::networking::packets::handshake::HandshakePacket;
+use nullspace::networking::packets::inspect::{describe_clientbound, describe_serverbound};
+use nullspace::networking::packets::login::login_start_request::LoginStartRequestPacket;
+use nullspace::networking::packets::play::chat_message_request::ChatMessageRequestPacket;
+use nullspace::networking::packets::play::set_player_position_and_rotation_request::SetPlayerPositionAndRotationRequestPacket;
+use nullspace::networking::packets::play::teleport_confirmation_request::TeleportConfirmationRequestPacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+
+const PHASES: [ConnectionPhase; 5] = [
+    ConnectionPhase::Handshaking,
+    ConnectionPhase::Status,
+    ConnectionPhase::Login,
+    ConnectionPhase::Configuration,
+    ConnectionPhase::Play,
+];
+
+fn registries() -> PacketRegistries {
+    PacketRegistries::new(packets::register_all)
+}
+
+/// Writes a packet as a client would and decodes it through the registry, both printed with Debug
+fn round_trip<P: PacketWrite + std::fmt::Debug + 'static>(registry: &PacketRegistry, phase: ConnectionPhase, packet: P) -> (String, String) {
+    let id = registry.id_of::<P>(phase).expect("packet isn't registered in the phase");
+    let mut data = Vec::new();
+    packet.write_to(&mut data);
+
+    let mut reader = ByteReader::new(&data);
+    let decoded = registry.decode(phase, id, &mut reader).unwrap().unwrap();
+    assert!(reader.is_empty(), "{} bytes left after decoding {:?}", reader.remaining(), packet);
+
+    (format!("{:?}", decoded), format!("{:?}", packet))
+}
+
+fn identifier() -> impl Strategy<Value = Identifier> {
+    ("[a-z0-9._-]{1,16}", "[a-z0-9._/-]{1,32}").prop_map(|(namespace, value)| Identifier::new(namespace, value))
+}
+
+fn resource_pack_status() -> impl Strategy<Value = ResourcePackStatus> {
+    prop_oneof![
+        Just(ResourcePackStatus::Loaded),
+        Just(ResourcePackStatus::Declined),
+        Just(ResourcePackStatus::FailedDownload),
+        Just(ResourcePackStatus::Accepted),
+        Just(ResourcePackStatus::Downloaded),
+        Just(ResourcePackStatus::InvalidUrl),
+        Just(ResourcePackStatus::FailedReload),
+        Just(ResourcePackStatus::Discarded),
+    ]
+}
+
+proptest! {
+    /// Random bodies for every id around the registered ones, in every phase and version
+    #[test]
+    fn random_packets_never_panic(id in -1i32..0x40, data in proptest::collection::vec(any::<u8>(), 0..128)) {
+        let registries = registries();
+        for version in ProtocolVersion::ALL {
+            let registry = registries.get(version);
+            for phase in PHASES {
+                let mut reader = ByteReader::new(&data);
+                if let Some(Ok(packet)) = registry.decode(phase, id, &mut reader) {
+                    let _ = format!("{:?}", packet);
+                }
+            }
+        }
+    }
+
+    /// Random byte streams split into frames and described in both directions, like the fuzz targets do
+    #[test]
+    fn random_frames_never_panic(stream in proptest::collection::vec(any::<u8>(), 0..256)) {
+        let registries = registries();
+        let mut buffer = BytesMut::from(&stream[..]);
+        while let Ok(Some(frame)) = split_frame(&mut buffer, ConnectionPhase::Play.max_frame_size()) {
+            for version in ProtocolVersion::ALL {
+                for phase in PHASES {
+                    describe_serverbound(registries.get(version), phase, &frame);
+                    describe_clientbound(version, phase, &frame);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handshake_and_login_round_trip(protocol in any::<i32>(), address in "[a-z0-9.]{0,255}", port in any::<u16>(), intent in 1i32..=3, name in "[A-Za-z0-9_]{3,16}", uuid in any::<u128>()) {
+        let registries = registries();
+        let registry = registries.get(ProtocolVersion::LATEST);
+
+        let handshake = HandshakePacket { protocol_version: VarInt(protocol), server_address: address, port, next_state: VarInt(intent) };
+        let (decoded, expected) = round_trip(registry, ConnectionPhase::Handshaking, handshake);
+        prop_assert_eq!(decoded, expected);
+
+        let login = LoginStartRequestPacket { name, player_uuid: Uuid::from_u128(uuid) };
+        let (decoded, expected) = round_trip(registry, ConnectionPhase::Login, login);
+        prop_assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn configuration_round_trip(
+        locale in "[a-z_]{2,16}",
+        view_distance in any::<i8>(),
+        flags in any::<(bool, bool, bool, bool)>(),
+        skin_parts in any::<u8>(),
+        packs in proptest::collection::vec(("[a-z]{1,16}", "[a-z]{1,16}", "[0-9.]{1,8}"), 0..8),
+    ) {
+        let registries = registries();
+        for version in ProtocolVersion::ALL {
+            let registry = registries.get(version);
+
+            let information = ClientInformationRequestPacket {
+                locale: locale.clone(),
+                view_distance: Byte(view_distance),
+                chat_mode: VarInt(0),
+                chat_colors: flags.0,
+                displayed_skin_parts: UnsignedByte(skin_parts),
+                main_hand: VarInt(1),
+                enable_text_filtering: flags.1,
+                allow_server_listings: flags.2,
+                particle_status: VarInt(flags.3 as i32),
+            };
+            let (decoded, expected) = round_trip(registry, ConnectionPhase::Configuration, information);
+            prop_assert_eq!(decoded, expected);
+
+            let known_packs = packs.iter().cloned().map(|(namespace, id, version)| KnownPack { namespace, id, version }).collect();
+            let (decoded, expected) = round_trip(registry, ConnectionPhase::Configuration, KnownPacksRequestPacket { known_packs });
+            prop_assert_eq!(decoded, expected);
+        }
+    }
+
+    /// Plugin messages and resource pack responses are registered in configuration and play
+    #[test]
+    fn common_packets_round_trip(channel in identifier(), data in proptest::collection::vec(any::<u8>(), 0..512), uuid in any::<u128>(), result in resource_pack_status()) {
+        let registries = registries();
+        let registry = registries.get(ProtocolVersion::LATEST);
+
+        for phase in [ConnectionPhase::Configuration, ConnectionPhase::Play] {
+            let message = PluginMessageRequestPacket { channel: channel.clone(), data: RawBytes(data.clone()) };
+            let (decoded, expected) = round_trip(registry, phase, message);
+            prop_assert_eq!(decoded, expected);
+
+            let response = ResourcePackResponseRequestPacket { uuid: Uuid::from_u128(uuid), result };
+            let (decoded, expected) = round_trip(registry, phase, response);
+            prop_assert_eq!(decoded, expected);
+        }
+    }
+
+    #[test]
+    fn play_round_trip(
+        position in any::<(f64, f64, f64)>(),
+        rotation in any::<(f32, f32)>(),
+        flags in any::<i8>(),
+        teleport_id in any::<i32>(),
+        message in "\\PC{0,128}",
+        timestamp in any::<i64>(),
+        salt in any::<i64>(),
+        signed in any::<bool>(),
+        seen in 0u32..(1 << 20),
+    ) {
+        let registries = registries();
+        let registry = registries.get(ProtocolVersion::LATEST);
+
+        let movement = SetPlayerPositionAndRotationRequestPacket {
+            x: position.0, feet_y: position.1, z: position.2, yaw: rotation.0, pitch: rotation.1, flags: Byte(flags),
+        };
+        let (decoded, expected) = round_trip(registry, ConnectionPhase::Play, movement);
+        prop_assert_eq!(decoded, expected);
+
+        let (decoded, expected) = round_trip(registry, ConnectionPhase::Play, TeleportConfirmationRequestPacket { teleport_id: VarInt(teleport_id) });
+        prop_assert_eq!(decoded, expected);
+
+        let mut acknowledged = FixedBitSet::new();
+        for index in 0..20 {
+            acknowledged.set(index, seen & (1 << index) != 0);
+        }
+
+        let chat = ChatMessageRequestPacket {
+            message,
+            timestamp,
+            salt,
+            signature: signed.then_some([7; 256]),
+            message_count: VarInt(0),
+            acknowledged,
+            checksum: Byte(1),
+        };
+        let (decoded, expected) = round_trip(registry, ConnectionPhase::Play, chat);
+        prop_assert_eq!(decoded, expected);
+    }
+}
+
+#[test]
+fn known_packs_are_limited_like_vanilla() {
+    let registries = registries();
+    let registry = registries.get(ProtocolVersion::LATEST);
+    let id = registry.id_of::<KnownPacksRequestPacket>(ConnectionPhase::Configuration).unwrap();
+    let pack = KnownPack { namespace: "minecraft".to_string(), id: "core".to_string(), version: "1.21.11".to_string() };
+
+    let mut data = Vec::new();
+    KnownPacksRequestPacket { known_packs: vec![pack.clone(); 64] }.write_to(&mut data);
+    assert!(registry.decode(ConnectionPhase::Configuration, id, &mut ByteReader::new(&data)).unwrap().is_ok());
+
+    // Rejected from the count alone, and counted like the other oversized arrays
+    let before = metrics::count(Rejection::OversizedArray);
+    let mut data = Vec::new();
+    KnownPacksRequestPacket { known_packs: vec![pack; 65] }.write_to(&mut data);
+    assert!(registry.decode(ConnectionPhase::Configuration, id, &mut ByteReader::new(&data)).unwrap().is_err());
+    assert!(metrics::count(Rejection::OversizedArray) > before);
+}
+
+#[test]
+fn the_longest_handshake_fits_its_frame_limit() {
+    let registries = registries();
+    let registry = registries.get(ProtocolVersion::LATEST);
+
+    // 5 byte VarInts, and 255 characters of 3 bytes each
+    let handshake = HandshakePacket { protocol_version: VarInt(-1), server_address: "€".repeat(255), port: 25565, next_state: VarInt(-1) };
+    let mut frame = vec![0x80, 0x80, 0x80, 0x80, 0x00];
+    handshake.write_to(&mut frame);
+    assert!(frame.len() <= ConnectionPhase::Handshaking.max_frame_size());
+
+    let id = registry.id_of::<HandshakePacket>(ConnectionPhase::Handshaking).unwrap();
+    let mut reader = ByteReader::new(&frame[5..]);
+    assert!(registry.decode(ConnectionPhase::Handshaking, id, &mut reader).unwrap().is_ok());
+}
@@ -0,0 +1,88 @@
+//! Clientbound packets encoded for each supported version, which share packet ids but not item layouts.
+
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::data_types::{ByteReader, PacketWrite};
+use nullspace::networking::data_types::data_component::DataComponent;
+use nullspace::networking::data_types::item_stack::ItemStack;
+use nullspace::networking::data_types::text_component::TextComponent;
+use nullspace::networking::data_types::var_int::VarInt;
+use nullspace::networking::packets::clientbound::{clientbound_id, clientbound_name};
+use nullspace::networking::packets::play::set_container_slot_response::SetContainerSlotResponsePacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+use nullspace::networking::writer::encode_packet;
+
+fn container_slot() -> SetContainerSlotResponsePacket {
+    SetContainerSlotResponsePacket {
+        window_id: VarInt(0),
+        state_id: VarInt(3),
+        slot: 36,
+        item: ItemStack::new(1, 1).with(DataComponent::Lore(vec![TextComponent::text("Old")])),
+    }
+}
+
+/// Body of a frame after its length prefix and packet id
+fn encode_body(version: ProtocolVersion) -> (i32, Vec<u8>) {
+    let frame = encode_packet(version, ConnectionPhase::Play, &container_slot()).unwrap();
+    let mut reader = ByteReader::new(&frame);
+    reader.read_field::<VarInt>().unwrap();
+    let id = reader.read_field::<VarInt>().unwrap().0;
+    (id, reader.read_bytes(reader.remaining()).unwrap().to_vec())
+}
+
+#[test]
+fn container_slot_items_take_the_component_ids_of_the_version() {
+    let (latest_id, latest) = encode_body(ProtocolVersion::V1_21_11);
+    let (old_id, old) = encode_body(ProtocolVersion::V1_21_9);
+    assert_eq!(latest_id, 0x14);
+    assert_eq!(old_id, latest_id);
+
+    // Window, state id and slot, then count, item id, one added and no removed component
+    let header = [0x00, 0x03, 0x00, 0x24, 0x01, 0x01, 0x01, 0x00];
+    assert_eq!(latest[..header.len()], header);
+    assert_eq!(old[..header.len()], header);
+
+    // Lore is component 11 in 1.21.11 and 8 before it, the data after it is the same
+    assert_eq!(latest[header.len()], 11);
+    assert_eq!(old[header.len()], 8);
+    assert_eq!(latest[header.len() + 1..], old[header.len() + 1..]);
+
+    let mut lore = Vec::new();
+    vec![TextComponent::text("Old")].write_to(&mut lore);
+    assert_eq!(latest[header.len() + 1..], lore);
+}
+
+#[test]
+fn container_slot_items_decode_with_their_version() {
+    for version in ProtocolVersion::ALL {
+        let (_, body) = encode_body(version);
+        let mut reader = ByteReader::new(&body[4..]);
+        assert_eq!(ItemStack::read_for(version, &mut reader).unwrap(), container_slot().item);
+        assert!(reader.is_empty());
+    }
+}
+
+#[test]
+fn clientbound_ids_are_numbered_in_order_per_phase() {
+    let phases = [ConnectionPhase::Status, ConnectionPhase::Login, ConnectionPhase::Configuration, ConnectionPhase::Play];
+    for version in ProtocolVersion::ALL {
+        for phase in phases {
+            let ids: Vec<i32> = (0..0x100).filter(|id| clientbound_name(version, phase, *id).is_some()).collect();
+            assert!(!ids.is_empty(), "no packets in {:?}", phase);
+            assert_eq!(ids, (0..ids.len() as i32).collect::<Vec<_>>(), "gap in the {:?} ids of {}", phase, version);
+
+            // A name listed twice in a phase would map back to only one of its ids
+            for id in ids {
+                let name = clientbound_name(version, phase, id).unwrap();
+                assert_eq!(clientbound_id(version, phase, name), Some(id), "{} is listed twice in {:?}", name, phase);
+            }
+        }
+        assert_eq!(clientbound_name(version, ConnectionPhase::Handshaking, 0), None);
+    }
+}
+
+#[test]
+fn items_the_version_cant_represent_fail_the_packet() {
+    let mut packet = container_slot();
+    packet.item.components.removed.push("minecraft:not_a_component");
+    assert!(encode_packet(ProtocolVersion::LATEST, ConnectionPhase::Play, &packet).is_err());
+}
@@ -0,0 +1,173 @@
+//! Token buckets driven by a manual clock, and the actions the server takes when a client runs out.
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+use nullspace::config::{LimitAction, RateLimitConfig, ServerConfig};
+use nullspace::networking::metrics::{self, Rejection};
+use nullspace::networking::packets::status::status_request::StatusRequestPacket;
+use nullspace::networking::packets::status::status_response::StatusResponsePacket;
+use nullspace::networking::protocol_version::ProtocolVersion;
+use nullspace::networking::rate_limit::{Clock, IpThrottle, PacketRateLimiter, ThrottleResult, TokenBucket};
+use nullspace::testing::{self, Intent, TestClient};
+
+const REMOTE: IpAddr = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+
+#[test]
+fn bucket_allows_its_burst_then_refills_at_its_rate() {
+    let clock = Clock::manual();
+    let mut bucket = TokenBucket::with_clock(2.0, 3.0, clock.clone());
+
+    for _ in 0..3 {
+        assert!(bucket.try_take());
+    }
+    assert!(!bucket.try_take());
+
+    // One token every 500ms
+    clock.advance(Duration::from_millis(499));
+    assert!(!bucket.try_take());
+    clock.advance(Duration::from_millis(1));
+    assert!(bucket.try_take());
+    assert!(!bucket.try_take());
+
+    // Partial tokens add up over several refills
+    for _ in 0..4 {
+        clock.advance(Duration::from_millis(100));
+        assert!(!bucket.try_take());
+    }
+    clock.advance(Duration::from_millis(100));
+    assert!(bucket.try_take());
+}
+
+#[test]
+fn bucket_never_holds_more_than_its_burst() {
+    let clock = Clock::manual();
+    let mut bucket = TokenBucket::with_clock(10.0, 2.0, clock.clone());
+    assert!(bucket.is_full());
+    assert!(bucket.try_take());
+    assert!(!bucket.is_full());
+
+    clock.advance(Duration::from_secs(60));
+    assert!(bucket.is_full());
+    assert!(bucket.try_take());
+    assert!(bucket.try_take());
+    assert!(!bucket.try_take());
+}
+
+#[test]
+fn movement_and_other_packets_have_separate_budgets() {
+    let config = RateLimitConfig { movement_per_second: 1.0, movement_burst: 2.0, packets_per_second: 1.0, packets_burst: 1.0, ..RateLimitConfig::default() };
+    let clock = Clock::manual();
+    let mut limiter = PacketRateLimiter::with_clock(&config, clock.clone());
+
+    assert!(limiter.allow(false));
+    assert!(!limiter.allow(false));
+    assert!(limiter.allow(true));
+    assert!(limiter.allow(true));
+    assert!(!limiter.allow(true));
+
+    clock.advance(Duration::from_secs(1));
+    assert!(limiter.allow(false));
+    assert!(limiter.allow(true));
+}
+
+#[test]
+fn connections_are_throttled_per_address() {
+    let config = RateLimitConfig { connections_per_second: 0.25, connection_burst: 2.0, ..RateLimitConfig::default() };
+    let clock = Clock::manual();
+    let throttle = IpThrottle::with_clock(&config, clock.clone());
+
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Allowed);
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Allowed);
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Throttled);
+    assert_eq!(throttle.check(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 8))), ThrottleResult::Allowed);
+
+    // Loopback connections skip the throttle
+    for _ in 0..10 {
+        assert_eq!(throttle.check(IpAddr::V4(Ipv4Addr::LOCALHOST)), ThrottleResult::Allowed);
+    }
+
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Allowed);
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Throttled);
+}
+
+#[test]
+fn bans_last_their_duration() {
+    let clock = Clock::manual();
+    let throttle = IpThrottle::with_clock(&RateLimitConfig::default(), clock.clone());
+
+    throttle.ban(REMOTE, Duration::from_secs(300));
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Banned);
+    // Even loopback addresses can be banned
+    throttle.ban(IpAddr::V4(Ipv4Addr::LOCALHOST), Duration::from_secs(300));
+    assert_eq!(throttle.check(IpAddr::V4(Ipv4Addr::LOCALHOST)), ThrottleResult::Banned);
+
+    clock.advance(Duration::from_secs(299));
+    assert!(throttle.is_banned(REMOTE));
+    clock.advance(Duration::from_secs(1));
+    assert!(!throttle.is_banned(REMOTE));
+    assert_eq!(throttle.check(REMOTE), ThrottleResult::Allowed);
+}
+
+/// Server whose packet budget never refills: the handshake and two status requests, then nothing
+async fn limited_server(action: LimitAction) -> TestClient {
+    let mut config = ServerConfig::default();
+    config.rate_limits.packets_per_second = 0.0;
+    config.rate_limits.packets_burst = 3.0;
+    config.rate_limits.packet_action = action;
+    let (address, _) = testing::start_server(config).await.unwrap();
+
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.handshake(Intent::Status).await.unwrap();
+    for _ in 0..2 {
+        client.send(StatusRequestPacket {}).await.unwrap();
+        client.expect::<StatusResponsePacket>().await.unwrap();
+    }
+    client
+}
+
+async fn wait_for_rejection(before: u64) {
+    for _ in 0..100 {
+        if metrics::count(Rejection::PacketRateLimited) > before {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the packet was never rate limited");
+}
+
+#[tokio::test]
+async fn dropped_packets_leave_the_connection_open() {
+    let mut client = limited_server(LimitAction::Drop).await;
+
+    let before = metrics::count(Rejection::PacketRateLimited);
+    client.send(StatusRequestPacket {}).await.unwrap();
+    wait_for_rejection(before).await;
+
+    // Neither an answer nor a closed connection, which would fail the receive right away
+    assert!(tokio::time::timeout(Duration::from_millis(300), client.receive_any()).await.is_err());
+}
+
+#[tokio::test]
+async fn clients_over_the_budget_are_kicked() {
+    let mut client = limited_server(LimitAction::Kick).await;
+
+    let before = metrics::count(Rejection::PacketRateLimited);
+    client.send(StatusRequestPacket {}).await.unwrap();
+    client.expect_closed().await.unwrap();
+    assert!(metrics::count(Rejection::PacketRateLimited) > before);
+}
+
+#[tokio::test]
+async fn banned_clients_cant_reconnect() {
+    let mut client = limited_server(LimitAction::Ban).await;
+    let address = client.address;
+
+    client.send(StatusRequestPacket {}).await.unwrap();
+    client.expect_closed().await.unwrap();
+
+    let before = metrics::count(Rejection::BannedAddress);
+    let mut client = TestClient::connect(address, ProtocolVersion::LATEST).await.unwrap();
+    client.expect_closed().await.unwrap();
+    assert!(metrics::count(Rejection::BannedAddress) > before);
+}
@@ -0,0 +1,59 @@
+//! Replays captured client sessions and checks that the server still answers with the same packets.
+//! Record new ones with `capture.enabled` in nullspace.json and inspect them with `nullspace-capture print`.
+
+use std::sync::Arc;
+use nullspace::config::ServerConfig;
+use nullspace::networking::capture::{self, CaptureRecord, Direction, PacketCapture};
+use nullspace::networking::channels::{self, ChannelRegistry};
+use nullspace::networking::connection::ConnectionPhase;
+use nullspace::networking::packets::{self, PacketRegistries};
+use nullspace::networking::protocol_version::ProtocolVersion;
+
+/// Outbound packets by phase and id, their contents can change between runs
+fn outbound_packets<'a>(records: impl IntoIterator<Item = &'a CaptureRecord>) -> Vec<(ConnectionPhase, i32)> {
+    records.into_iter()
+        .filter(|record| record.direction == Direction::Outbound)
+        .map(|record| (record.phase, record.packet_id().unwrap().0))
+        .collect()
+}
+
+async fn assert_replay_matches(name: &str) {
+    let path = format!("{}/tests/captures/{}.nscap", env!("CARGO_MANIFEST_DIR"), name);
+    let records = capture::read_capture(&path).unwrap();
+
+    let registries = Arc::new(PacketRegistries::new(packets::register_all));
+    let mut channels = ChannelRegistry::new();
+    channels::register_all(&mut channels);
+
+    let replayed = capture::replay(&records, &registries, Arc::new(ServerConfig::default()), Arc::new(channels)).await.unwrap();
+
+    let expected = outbound_packets(&records);
+    assert!(expected.iter().any(|(phase, _)| *phase == ConnectionPhase::Play), "capture {} doesn't reach play", name);
+    assert_eq!(outbound_packets(&replayed), expected);
+}
+
+#[tokio::test]
+async fn join_1_21_11() {
+    assert_replay_matches("join_1_21_11").await;
+}
+
+#[tokio::test]
+async fn join_1_21_9() {
+    assert_replay_matches("join_1_21_9").await;
+}
+
+#[test]
+fn frames_after_an_invalid_length_are_not_recorded() {
+    // -1, i32::MIN and a length past the end of the buffer
+    for invalid in [&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F][..], &[0x80, 0x80, 0x80, 0x80, 0x08], &[0xFF, 0xFF, 0xFF, 0xFF, 0x07]] {
+        let mut frames = vec![0x02, 0x01, 0xAA];
+        frames.extend_from_slice(invalid);
+        frames.extend_from_slice(&[0x01, 0x02]);
+
+        let capture = PacketCapture::in_memory();
+        capture.record_frames(Direction::Outbound, ProtocolVersion::LATEST, ConnectionPhase::Play, &frames);
+
+        let bodies: Vec<Vec<u8>> = capture.records().into_iter().map(|record| record.body).collect();
+        assert_eq!(bodies, vec![vec![0x01, 0xAA]]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Fetches packs from the embedded resource pack server like a client downloading them.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use nullspace::config::ResourcePackServerConfig;
+use nullspace::networking::resource_pack_server::{self, HostedPack};
+
+/// An empty zip: only the end of central directory record
+const PACK: [u8; 22] = [0x50, 0x4B, 0x05, 0x06, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+/// SHA-1 of PACK, computed outside of the server
+const PACK_HASH: &str = "b04f3ee8f5e43fa3b162981b50bb72fe1acabb33";
+
+/// Serves a directory holding only "my pack.zip", on a port of its own
+async fn start(name: &str, pack: &[u8], request_timeout_secs: u64) -> (SocketAddr, Vec<HostedPack>) {
+    let directory = std::env::temp_dir().join(format!("nullspace-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("my pack.zip"), pack).unwrap();
+    std::fs::write(directory.join("notes.txt"), "not a pack").unwrap();
+
+    let config = ResourcePackServerConfig {
+        bind: "127.0.0.1:0".to_string(),
+        directory: PathBuf::from(&directory),
+        request_timeout_secs,
+        ..ResourcePackServerConfig::default()
+    };
+    let packs = resource_pack_server::scan_packs(&config).unwrap();
+    let address = resource_pack_server::start(&config, &packs).await.unwrap();
+    (address, packs)
+}
+
+/// Sends a request and reads the response until the server closes the connection
+async fn request(address: SocketAddr, request: &str) -> (String, Vec<u8>) {
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    socket.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = Vec::new();
+    socket.read_to_end(&mut response).await.unwrap();
+
+    let head_end = response.windows(4).position(|window| window == b"\r\n\r\n").unwrap() + 4;
+    let body = response.split_off(head_end);
+    (String::from_utf8(response).unwrap(), body)
+}
+
+#[tokio::test]
+async fn packs_are_served_with_their_hash() {
+    let (address, packs) = start("fetch", &PACK, 10).await;
+
+    assert_eq!(packs.len(), 1);
+    assert_eq!(packs[0].path, "my%20pack.zip");
+    assert_eq!(packs[0].hash, PACK_HASH);
+    assert_eq!(packs[0].id.as_bytes()[..], hex_bytes(PACK_HASH)[..16]);
+
+    let config = ResourcePackServerConfig { public_url: "http://example.com:8080/".to_string(), ..ResourcePackServerConfig::default() };
+    assert_eq!(packs[0].to_pack_config(&config).url, "http://example.com:8080/my%20pack.zip");
+
+    let (head, body) = request(address, "GET /my%20pack.zip HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "{}", head);
+    assert!(head.contains("Content-Type: application/zip\r\n"), "{}", head);
+    assert!(head.contains(&format!("Content-Length: {}\r\n", PACK.len())), "{}", head);
+    assert_eq!(body, PACK);
+
+    // HEAD has the same headers without the body
+    let (head, body) = request(address, "HEAD /my%20pack.zip HTTP/1.1\r\n\r\n").await;
+    assert!(head.contains(&format!("Content-Length: {}\r\n", PACK.len())), "{}", head);
+    assert!(body.is_empty());
+
+    let (head, _) = request(address, "GET /notes.txt HTTP/1.1\r\n\r\n").await;
+    assert!(head.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", head);
+    let (head, _) = request(address, "POST /my%20pack.zip HTTP/1.1\r\n\r\n").await;
+    assert!(head.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"), "{}", head);
+}
+
+#[tokio::test]
+async fn clients_that_never_send_a_request_are_dropped() {
+    let (address, _) = start("timeout", &PACK, 1).await;
+
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    socket.write_all(b"GET /my%20pack.zip HTTP/1.1\r\n").await.unwrap();
+
+    let mut response = Vec::new();
+    let read = tokio::time::timeout(std::time::Duration::from_secs(5), socket.read_to_end(&mut response)).await;
+    assert!(read.is_ok(), "connection still open");
+    assert!(response.is_empty());
+}
+
+#[tokio::test]
+async fn clients_that_stop_reading_are_dropped() {
+    // Much more than the socket buffers hold, so the server has to wait for the client
+    let pack = vec![0u8; 32 * 1024 * 1024];
+    let (address, _) = start("stalled", &pack, 1).await;
+
+    let mut socket = TcpStream::connect(address).await.unwrap();
+    socket.write_all(b"GET /my%20pack.zip HTTP/1.1\r\n\r\n").await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+
+    // The server gave up on the response: the connection ends before the whole pack arrived
+    let mut response = Vec::new();
+    let read = tokio::time::timeout(std::time::Duration::from_secs(5), socket.read_to_end(&mut response)).await;
+    assert!(read.is_ok(), "connection still open");
+    assert!(response.len() < pack.len(), "the whole pack was sent");
+}
+
+fn hex_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap()).collect()
+}